@@ -0,0 +1,285 @@
+//! Property-based testing support for the game types
+//!
+//! This provides [`proptest::arbitrary::Arbitrary`] implementations for
+//! boards, cards, and hands, as well as helpers that check the
+//! invariants the rules are meant to uphold.  The helpers return
+//! [`TestCaseError`] so they can be used with `?` inside a
+//! [`proptest::proptest!`] block.
+//!
+//! It is only compiled with the `proptest` feature enabled.
+//!
+//! # Examples
+//!
+//! ```
+//! use proptest::prelude::*;
+//!
+//! proptest! {
+//!     fn moving_keeps_the_board_sane(board: Sokoban, direction: Direction) {
+//!         arbitrary::check_move(&board, direction, &board.you_move(direction))?;
+//!     }
+//! }
+//! ```
+
+use proptest::prelude::*;
+use proptest::test_runner::TestCaseError;
+
+use crate::coordinate::{Direction, I2Array, I2};
+use crate::poker::{Card, Hand, Rank, Suit};
+use crate::sokoban::Sokoban;
+
+/// The width and height of the square area arbitrary boards are drawn in
+///
+/// This is kept small so that the entities on a board are likely to
+/// run into each other.
+pub const BOARD_SIZE: i32 = 8;
+
+const RANKS: [Rank; 13] = [
+    Rank::Two,
+    Rank::Three,
+    Rank::Four,
+    Rank::Five,
+    Rank::Six,
+    Rank::Seven,
+    Rank::Eight,
+    Rank::Nine,
+    Rank::Ten,
+    Rank::Jack,
+    Rank::Queen,
+    Rank::King,
+    Rank::Ace,
+];
+
+const SUITS: [Suit; 4] = [Suit::Diamond, Suit::Club, Suit::Heart, Suit::Spade];
+
+/// What occupies a single cell of an arbitrary board
+#[derive(Debug, Clone, Copy)]
+enum Cell {
+    Floor,
+    Stop,
+    Push,
+    Target,
+    PushOnTarget,
+}
+
+/// A coordinate inside the `BOARD_SIZE` square at the origin
+pub fn board_coordinate() -> impl Strategy<Value = I2> {
+    (0..BOARD_SIZE, 0..BOARD_SIZE).prop_map(|(x, y)| I2::new(x, y))
+}
+
+/// Every card of a 52-card deck, each exactly once
+pub fn deck() -> Vec<Card> {
+    SUITS
+        .iter()
+        .flat_map(|&suit| RANKS.iter().map(move |&rank| Card::new(rank, suit)))
+        .collect()
+}
+
+impl Arbitrary for Direction {
+    type Parameters = ();
+    type Strategy = BoxedStrategy<Self>;
+
+    fn arbitrary_with(_: Self::Parameters) -> Self::Strategy {
+        prop_oneof![
+            Just(Direction::Up),
+            Just(Direction::Left),
+            Just(Direction::Down),
+            Just(Direction::Right),
+        ]
+        .boxed()
+    }
+}
+
+impl Arbitrary for I2 {
+    type Parameters = ();
+    type Strategy = BoxedStrategy<Self>;
+
+    /// Any coordinate at all, including ones at the integer limits
+    fn arbitrary_with(_: Self::Parameters) -> Self::Strategy {
+        (any::<i32>(), any::<i32>())
+            .prop_map(|(x, y)| I2::new(x, y))
+            .boxed()
+    }
+}
+
+impl Arbitrary for I2Array {
+    type Parameters = ();
+    type Strategy = BoxedStrategy<Self>;
+
+    /// Up to `BOARD_SIZE * BOARD_SIZE` coordinates near the origin
+    fn arbitrary_with(_: Self::Parameters) -> Self::Strategy {
+        proptest::collection::vec(board_coordinate(), 0..(BOARD_SIZE * BOARD_SIZE) as usize)
+            .prop_map(I2Array::from_iter)
+            .boxed()
+    }
+}
+
+impl Arbitrary for Sokoban {
+    type Parameters = ();
+    type Strategy = BoxedStrategy<Self>;
+
+    /// A board whose entities lie in the `BOARD_SIZE` square at the origin
+    ///
+    /// The board is always consistent, as [`check_board`] understands
+    /// it, although it might not be solvable.
+    fn arbitrary_with(_: Self::Parameters) -> Self::Strategy {
+        let cell = prop_oneof![
+            6 => Just(Cell::Floor),
+            3 => Just(Cell::Stop),
+            2 => Just(Cell::Push),
+            1 => Just(Cell::Target),
+            1 => Just(Cell::PushOnTarget),
+        ];
+        let area: usize = (BOARD_SIZE * BOARD_SIZE) as usize;
+
+        (proptest::collection::vec(cell, area), 0..area)
+            .prop_map(|(cells, you_index)| {
+                let mut stops: I2Array = I2Array::from(vec![]);
+                let mut pushes: I2Array = I2Array::from(vec![]);
+                let mut targets: I2Array = I2Array::from(vec![]);
+                for (i, cell) in cells.iter().enumerate() {
+                    let coordinate: I2 = I2::new(i as i32 % BOARD_SIZE, i as i32 / BOARD_SIZE);
+                    // you need somewhere to stand
+                    let cell: Cell = match (i == you_index, cell) {
+                        (true, Cell::Target | Cell::PushOnTarget) => Cell::Target,
+                        (true, _) => Cell::Floor,
+                        (false, cell) => *cell,
+                    };
+                    match cell {
+                        Cell::Floor => {}
+                        Cell::Stop => stops.push(coordinate),
+                        Cell::Push => pushes.push(coordinate),
+                        Cell::Target => targets.push(coordinate),
+                        Cell::PushOnTarget => {
+                            pushes.push(coordinate);
+                            targets.push(coordinate);
+                        }
+                    }
+                }
+
+                Sokoban::new(
+                    I2::new(you_index as i32 % BOARD_SIZE, you_index as i32 / BOARD_SIZE),
+                    stops,
+                    pushes,
+                    targets,
+                )
+            })
+            .boxed()
+    }
+}
+
+impl Arbitrary for Rank {
+    type Parameters = ();
+    type Strategy = BoxedStrategy<Self>;
+
+    fn arbitrary_with(_: Self::Parameters) -> Self::Strategy {
+        proptest::sample::select(RANKS.to_vec()).boxed()
+    }
+}
+
+impl Arbitrary for Suit {
+    type Parameters = ();
+    type Strategy = BoxedStrategy<Self>;
+
+    fn arbitrary_with(_: Self::Parameters) -> Self::Strategy {
+        proptest::sample::select(SUITS.to_vec()).boxed()
+    }
+}
+
+impl Arbitrary for Card {
+    type Parameters = ();
+    type Strategy = BoxedStrategy<Self>;
+
+    fn arbitrary_with(_: Self::Parameters) -> Self::Strategy {
+        (any::<Rank>(), any::<Suit>())
+            .prop_map(|(rank, suit)| Card::new(rank, suit))
+            .boxed()
+    }
+}
+
+impl Arbitrary for Hand {
+    type Parameters = ();
+    type Strategy = BoxedStrategy<Self>;
+
+    /// Five distinct cards, as though dealt from a single deck
+    fn arbitrary_with(_: Self::Parameters) -> Self::Strategy {
+        proptest::sample::subsequence(deck(), 5)
+            .prop_shuffle()
+            .prop_map(Hand::new)
+            .boxed()
+    }
+}
+
+/// Checks that no two entities on `board` are somewhere they can't be
+///
+/// That is to say, you aren't on a stop or a push, no push is on a
+/// stop, and no two pushes share a cell.
+pub fn check_board(board: &Sokoban) -> Result<(), TestCaseError> {
+    let stops: I2Array = board.stops();
+    let pushes: I2Array = board.pushes();
+
+    prop_assert!(!stops.contains(&board.you()), "you are on a stop");
+    prop_assert!(!pushes.contains(&board.you()), "you are on a push");
+    for (i, push) in pushes.iter().enumerate() {
+        prop_assert!(!stops.contains(push), "push {:?} is on a stop", push);
+        prop_assert!(
+            !pushes.iter().skip(i + 1).any(|other| other == push),
+            "there are two pushes at {:?}",
+            push
+        );
+    }
+    Ok(())
+}
+
+/// Checks that `after` could be the result of `before` moving `direction`
+///
+/// Stops and targets never change, pushes are neither created nor
+/// destroyed, and you and every push move either not at all or by one
+/// tile in `direction`.  Both boards must also pass [`check_board`].
+pub fn check_move(
+    before: &Sokoban,
+    direction: Direction,
+    after: &Sokoban,
+) -> Result<(), TestCaseError> {
+    check_board(before)?;
+    check_board(after)?;
+
+    prop_assert_eq!(before.stops(), after.stops());
+    prop_assert_eq!(before.targets(), after.targets());
+    prop_assert!(
+        after.you() == before.you() || before.you().nudge(direction) == Some(after.you()),
+        "you jumped from {:?} to {:?}",
+        before.you(),
+        after.you()
+    );
+
+    let pushes_before: I2Array = before.pushes();
+    let pushes_after: I2Array = after.pushes();
+    prop_assert_eq!(pushes_before.iter().count(), pushes_after.iter().count());
+    for (push_before, push_after) in pushes_before.iter().zip(pushes_after.iter()) {
+        prop_assert!(
+            push_after == push_before || push_before.nudge(direction).as_ref() == Some(push_after),
+            "push jumped from {:?} to {:?}",
+            push_before,
+            push_after
+        );
+    }
+    Ok(())
+}
+
+/// Checks that comparing `hand0` and `hand1` gives a consistent answer
+///
+/// The comparison must be the reverse of comparing them the other way
+/// around and must agree with equality.
+pub fn check_hand_order(hand0: &Hand, hand1: &Hand) -> Result<(), TestCaseError> {
+    let forward: std::cmp::Ordering = hand0.cmp(hand1);
+    let backward: std::cmp::Ordering = hand1.cmp(hand0);
+
+    prop_assert_eq!(forward, backward.reverse());
+    prop_assert_eq!(hand0.partial_cmp(hand1), Some(forward));
+    prop_assert_eq!(
+        hand0 == hand1,
+        forward == std::cmp::Ordering::Equal,
+        "equality disagrees with ordering"
+    );
+    Ok(())
+}
@@ -1,10 +1,12 @@
 //! Spacial coordinates to do computations on
 
+use std::collections::HashSet;
+
 /// The directions which things can move in
 ///
 /// This should be understood in the context of a coordinate system
 /// where the y-axis points down and the x-axis points right.
-#[derive(Clone, Copy)]
+#[derive(Debug, PartialEq, Clone, Copy)]
 pub enum Direction {
     /// Toward the side of the screen in which blocks of text begin
     Up,
@@ -18,8 +20,38 @@ pub enum Direction {
     Right,
 }
 
+impl TryFrom<i32> for Direction {
+    type Error = &'static str;
+
+    /// Converts the ordinal of a direction, in declaration order
+    ///
+    /// That is [`Direction::Up`] is `0`, [`Direction::Left`] is `1`,
+    /// [`Direction::Down`] is `2`, and [`Direction::Right`] is `3`.
+    /// Anything else will `Err`.
+    fn try_from(ordinal: i32) -> Result<Self, Self::Error> {
+        match ordinal {
+            0 => Ok(Direction::Up),
+            1 => Ok(Direction::Left),
+            2 => Ok(Direction::Down),
+            3 => Ok(Direction::Right),
+            _ => Err("Not a Direction"),
+        }
+    }
+}
+
+impl From<Direction> for i32 {
+    fn from(direction: Direction) -> Self {
+        match direction {
+            Direction::Up => 0,
+            Direction::Left => 1,
+            Direction::Down => 2,
+            Direction::Right => 3,
+        }
+    }
+}
+
 /// A 2D unsigned integer coordinate
-#[derive(Debug, PartialEq, Clone, Copy)]
+#[derive(Debug, PartialEq, Eq, Hash, Clone, Copy)]
 pub struct I2 {
     x: i32,
     y: i32,
@@ -104,18 +136,25 @@ impl TryFrom<godot::prelude::Variant> for I2 {
 }
 
 /// An array of [`I2`] coordinates
-#[derive(Debug, PartialEq, Clone)]
-pub struct I2Array(Vec<I2>);
+///
+/// The coordinates keep the order they were added in, but they're also
+/// indexed so that checking if the array contains one doesn't have to
+/// look through them all.
+#[derive(Debug, Clone)]
+pub struct I2Array {
+    coordinates: Vec<I2>,
+    index: HashSet<I2>,
+}
 
 impl I2Array {
     /// Returns an iterator over the container
     pub fn iter(&self) -> std::slice::Iter<'_, I2> {
-        self.0.iter()
+        self.coordinates.iter()
     }
 
     /// Returns `true` if the coordinate is in the array
     pub fn contains(&self, coordinate: &I2) -> bool {
-        self.0.contains(coordinate)
+        self.index.contains(coordinate)
     }
 
     /// Appends a coordinate to the back of the array
@@ -124,19 +163,24 @@ impl I2Array {
     ///
     /// Panics if the new capacity exceeds [`isize::MAX`] bytes.
     pub fn push(&mut self, coordinate: I2) {
-        self.0.push(coordinate);
+        self.coordinates.push(coordinate);
+        self.index.insert(coordinate);
+    }
+}
+
+impl PartialEq for I2Array {
+    /// Checks that the arrays have the same coordinates in the same order
+    fn eq(&self, other: &Self) -> bool {
+        self.coordinates == other.coordinates
     }
 }
 
 impl FromIterator<I2> for I2Array {
     fn from_iter<I: IntoIterator<Item = I2>>(iter: I) -> Self {
-        let mut coordinate_vector: Vec<I2> = vec![];
+        let coordinates: Vec<I2> = iter.into_iter().collect();
+        let index: HashSet<I2> = coordinates.iter().copied().collect();
 
-        for coordinate in iter {
-            coordinate_vector.push(coordinate);
-        }
-
-        I2Array(coordinate_vector)
+        I2Array { coordinates, index }
     }
 }
 
@@ -151,12 +195,10 @@ impl From<Vec<[i32; 2]>> for I2Array {
     /// let coords: I2Array = I2Array::from(vec![[0, 0], [1, 10], [115, 6]]);
     /// ```
     fn from(coordinates: Vec<[i32; 2]>) -> Self {
-        I2Array(
-            coordinates
-                .iter()
-                .map(|coordinate| I2::new(coordinate[0], coordinate[1]))
-                .collect(),
-        )
+        coordinates
+            .iter()
+            .map(|coordinate| I2::new(coordinate[0], coordinate[1]))
+            .collect()
     }
 }
 
@@ -185,6 +227,28 @@ impl TryFrom<godot::prelude::Array<godot::builtin::Vector2i>> for I2Array {
 mod tests {
     use super::*;
 
+    mod direction {
+        use super::*;
+
+        #[test]
+        fn round_trips_through_its_ordinal() {
+            for direction in [
+                Direction::Up,
+                Direction::Left,
+                Direction::Down,
+                Direction::Right,
+            ] {
+                assert_eq!(Direction::try_from(i32::from(direction)), Ok(direction));
+            }
+        }
+
+        #[test]
+        fn unknown_ordinals_are_err() {
+            assert!(Direction::try_from(-1).is_err());
+            assert!(Direction::try_from(4).is_err());
+        }
+    }
+
     mod u2 {
         use super::*;
 
@@ -246,7 +310,7 @@ mod tests {
             let coordinate_array = I2Array::from_iter(coordinate_iter);
             assert_eq!(
                 coordinate_array,
-                I2Array(vec![
+                I2Array::from_iter(vec![
                     I2::new(0, 1),
                     I2::new(2, 0),
                     I2::new(3, 3),
@@ -257,7 +321,7 @@ mod tests {
 
         #[test]
         fn can_be_iterated_through() {
-            let array: I2Array = I2Array(vec![
+            let array: I2Array = I2Array::from_iter(vec![
                 I2::new(0, 1),
                 I2::new(2, 0),
                 I2::new(3, 3),
@@ -275,7 +339,7 @@ mod tests {
 
         #[test]
         fn can_be_queried_for_containing() {
-            let array: I2Array = I2Array(vec![
+            let array: I2Array = I2Array::from_iter(vec![
                 I2::new(0, 1),
                 I2::new(2, 0),
                 I2::new(3, 3),
@@ -292,7 +356,7 @@ mod tests {
 
             assert_eq!(
                 I2Array::from(coords),
-                I2Array(vec![I2::new(0, 0), I2::new(1, 0), I2::new(9, 120)])
+                I2Array::from_iter(vec![I2::new(0, 0), I2::new(1, 0), I2::new(9, 120)])
             );
         }
 
@@ -303,6 +367,7 @@ mod tests {
             coords.push(I2::new(1, 2));
 
             assert_eq!(coords, I2Array::from(vec![[125, 216], [0, 0], [1, 2]]));
+            assert!(coords.contains(&I2::new(1, 2)));
         }
     }
 }
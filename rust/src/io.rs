@@ -1,22 +1,248 @@
+use std::collections::VecDeque;
+use std::sync::mpsc::{channel, Receiver, TryRecvError};
+
+use godot::engine::Input;
 use godot::engine::InputEvent;
+use godot::engine::Node;
+use godot::engine::NodeVirtual;
+use godot::engine::Resource;
+use godot::engine::ResourceVirtual;
+use godot::engine::Sprite2D;
+use godot::engine::Texture2D;
 use godot::engine::TileMap;
 use godot::engine::TileMapVirtual;
+use godot::engine::TileSetAtlasSource;
 use godot::prelude::*;
 
 use crate::coordinate::{Direction, I2Array, I2};
+use crate::poker;
+use crate::rng::GameRng;
 use crate::sokoban;
+use crate::sokoban::history::{Action, History};
+use crate::tutorial;
+
+/// Which of the players on the board a move is for
+#[derive(Clone, Copy)]
+enum Avatar {
+    You,
+    Partner,
+}
+
+/// The part a tile plays on the board, which decides how it's drawn
+#[derive(Clone, Copy, PartialEq)]
+enum Role {
+    You,
+    Partner,
+    Stop,
+    Push,
+    Target,
+    TriggeredTarget,
+    Crack,
+    Switch,
+    /// A switch wall that's closed
+    Wall,
+    /// A magnet, turned to face the given way
+    Magnet(Direction),
+    Ice,
+    Teleporter,
+    Enemy,
+    /// An item of the given kind, drawn from the atlas tile at that many
+    /// tiles across
+    Item(u32),
+}
+
+/// A tile moving from one cell to another while a move is animated
+struct Slide {
+    role: Role,
+    from: I2,
+    to: I2,
+    /// Whether the tile fades away on its way, like a push that wears
+    /// out once it gets to `to`
+    fades_out: bool,
+    /// Whether the tile fades into view on its way, like a stop left
+    /// behind by cracked floor breaking
+    fades_in: bool,
+    /// Where the tile jumps to once it gets to `to`, like something
+    /// going through a teleporter
+    teleports_to: Option<I2>,
+}
+
+impl Slide {
+    fn new(role: Role, from: I2, to: I2) -> Slide {
+        Slide {
+            role,
+            from,
+            to,
+            fades_out: false,
+            fades_in: false,
+            teleports_to: None,
+        }
+    }
+}
+
+/// Carry the slide of `role` that ends at `from` on to `to`, or start a
+/// new one if nothing of `role` slid there
+///
+/// This keeps a tile that moves more than once in a move, like a push
+/// shoved and then pulled by a magnet, to a single sprite.
+fn follow(slides: &mut Vec<Slide>, role: Role, from: I2, to: I2) {
+    match slides
+        .iter_mut()
+        .find(|slide| slide.role == role && slide.to == from)
+    {
+        Some(slide) => slide.to = to,
+        None => slides.push(Slide::new(role, from, to)),
+    }
+}
+
+/// Tiles in the middle of sliding from one cell to the next
+struct Animation {
+    remaining: f64,
+    sprites: Vec<Gd<Sprite2D>>,
+}
+
+/// A solution playing itself while nobody's at the controls, and the
+/// game to go back to once somebody is
+struct Attract {
+    moves: VecDeque<Direction>,
+    elapsed: f64,
+    board: sokoban::Sokoban,
+    history: History,
+}
 
 /// A Godot class for managing a game of Sokoban
 #[derive(GodotClass)]
 #[class(base=TileMap)]
 pub struct Sokoban {
-    initial_board: sokoban::Sokoban,
     board: sokoban::Sokoban,
     you_tile: i32,
     stop_tile: i32,
     push_tile: i32,
     target_tile: i32,
     triggered_target_tile: i32,
+    partner_tile: i32,
+    item_tile: i32,
+    crack_tile: i32,
+    switch_tile: i32,
+    wall_tile: i32,
+    magnet_tile: i32,
+    ice_tile: i32,
+    teleporter_tile: i32,
+    enemy_tile: i32,
+
+    /// The [`InputMap`] key for the second player's up input
+    #[export]
+    p2_move_up: GodotString,
+    /// The [`InputMap`] key for the second player's left input
+    #[export]
+    p2_move_left: GodotString,
+    /// The [`InputMap`] key for the second player's down input
+    #[export]
+    p2_move_down: GodotString,
+    /// The [`InputMap`] key for the second player's right input
+    #[export]
+    p2_move_right: GodotString,
+
+    /// Whether to rumble a joypad along with `feedback`
+    #[export]
+    vibration_enabled: bool,
+    /// The joypad device that rumbles when `vibration_enabled` is set
+    #[export]
+    vibration_device: i32,
+
+    /// How long, in seconds, a tile takes to slide into its new cell
+    #[export]
+    animation_duration: f64,
+    /// How many times a second a held move repeats, or `0` to not repeat
+    #[export]
+    input_repeat_rate: f64,
+    /// Draw every move instantly rather than sliding tiles around
+    #[export]
+    reduce_motion: bool,
+    /// How many moves made during an animation are saved to play once
+    /// it's done, where `0` cuts the animation short instead
+    #[export]
+    input_buffer_size: i32,
+    /// How many seconds without any input before a solution starts
+    /// playing by itself, or `0` to never
+    ///
+    /// Any input stops it and puts the game back how it was left.
+    #[export]
+    attract_delay: f64,
+    /// How many moves a second a solution plays at while attracting
+    #[export]
+    attract_move_rate: f64,
+    /// The longest, in seconds, to look for a solution to play before
+    /// waiting out another `attract_delay`
+    #[export]
+    attract_search_seconds: f64,
+    /// How many pushes can be shoved along in a line at once, or `0` for
+    /// any number; classic Sokoban rules are `1`
+    #[export]
+    push_limit: i32,
+    /// How many moves the level must be won in, or `0` for any number
+    #[export]
+    move_limit: i32,
+    /// How many moves the level is meant to be won in, or `0` if it
+    /// isn't known, for grading the player's `moves` against
+    #[export]
+    par: i32,
+    /// The kinds of item, by their tile's atlas column, that only
+    /// decorate the floor rather than being picked up
+    #[export]
+    decal_item_kinds: PackedInt32Array,
+    /// Which walls each switch opens, from the switch's cell to an array
+    /// of wall cells
+    ///
+    /// Switches that aren't in here open every wall.
+    #[export]
+    switch_links: Dictionary,
+    /// Which teleporters are twins, from the cell of one to the cell of
+    /// the other
+    ///
+    /// Teleporters that aren't in here don't go anywhere.
+    #[export]
+    teleporter_links: Dictionary,
+    /// How many moves it costs to step onto some cells, from the cell to
+    /// its cost, like mud that costs `2`
+    ///
+    /// Every other cell costs `1`.
+    #[export]
+    tile_costs: Dictionary,
+
+    /// How tiles are told apart, one of the `ACCESSIBILITY_*` constants
+    #[export]
+    accessibility_mode: i32,
+    /// The atlas coordinates of your tile in `ACCESSIBILITY_SHAPES` mode
+    #[export]
+    you_shape_atlas: Vector2i,
+    /// The atlas coordinates of the second player's tile in
+    /// `ACCESSIBILITY_SHAPES` mode
+    #[export]
+    partner_shape_atlas: Vector2i,
+    /// The atlas coordinates of the stop tile in `ACCESSIBILITY_SHAPES` mode
+    #[export]
+    stop_shape_atlas: Vector2i,
+    /// The atlas coordinates of the push tile in `ACCESSIBILITY_SHAPES` mode
+    #[export]
+    push_shape_atlas: Vector2i,
+    /// The atlas coordinates of the target tile in `ACCESSIBILITY_SHAPES`
+    /// mode
+    #[export]
+    target_shape_atlas: Vector2i,
+    /// The atlas coordinates of the triggered target tile in
+    /// `ACCESSIBILITY_SHAPES` mode
+    #[export]
+    triggered_target_shape_atlas: Vector2i,
+
+    held: Option<(StringName, Avatar, Direction)>,
+    repeat_elapsed: f64,
+    animation: Option<Animation>,
+    buffered_moves: VecDeque<(Avatar, Direction)>,
+    tutorial: Option<tutorial::Tutorial>,
+    history: History,
+    idle_elapsed: f64,
+    attract: Option<Attract>,
 
     #[base]
     base: Base<TileMap>,
@@ -26,12 +252,6 @@ pub struct Sokoban {
 impl TileMapVirtual for Sokoban {
     fn init(base: Base<TileMap>) -> Self {
         Sokoban {
-            initial_board: sokoban::Sokoban::new(
-                I2::new(0, 0),
-                I2Array::from(vec![]),
-                I2Array::from(vec![]),
-                I2Array::from(vec![]),
-            ),
             board: sokoban::Sokoban::new(
                 I2::new(0, 0),
                 I2Array::from(vec![]),
@@ -43,33 +263,540 @@ impl TileMapVirtual for Sokoban {
             push_tile: 0,
             target_tile: 2,
             triggered_target_tile: 3,
+            partner_tile: 5,
+            item_tile: 6,
+            crack_tile: 7,
+            switch_tile: 8,
+            wall_tile: 9,
+            magnet_tile: 10,
+            ice_tile: 11,
+            teleporter_tile: 12,
+            enemy_tile: 13,
+            p2_move_up: Sokoban::P2_MOVE_UP.into(),
+            p2_move_left: Sokoban::P2_MOVE_LEFT.into(),
+            p2_move_down: Sokoban::P2_MOVE_DOWN.into(),
+            p2_move_right: Sokoban::P2_MOVE_RIGHT.into(),
+            vibration_enabled: false,
+            vibration_device: 0,
+            animation_duration: 0.1,
+            input_repeat_rate: 6.0,
+            reduce_motion: false,
+            input_buffer_size: 2,
+            attract_delay: 0.0,
+            attract_move_rate: 4.0,
+            attract_search_seconds: 1.0,
+            push_limit: 0,
+            move_limit: 0,
+            par: 0,
+            decal_item_kinds: PackedInt32Array::new(),
+            switch_links: Dictionary::new(),
+            teleporter_links: Dictionary::new(),
+            tile_costs: Dictionary::new(),
+            accessibility_mode: Sokoban::ACCESSIBILITY_STANDARD,
+            you_shape_atlas: Vector2i::new(1, 0),
+            partner_shape_atlas: Vector2i::new(1, 0),
+            stop_shape_atlas: Vector2i::new(1, 0),
+            push_shape_atlas: Vector2i::new(1, 0),
+            target_shape_atlas: Vector2i::new(1, 0),
+            triggered_target_shape_atlas: Vector2i::new(1, 0),
+            held: None,
+            repeat_elapsed: 0.0,
+            animation: None,
+            buffered_moves: VecDeque::new(),
+            tutorial: None,
+            history: History::new(),
+            idle_elapsed: 0.0,
+            attract: None,
             base,
         }
     }
 
     fn ready(&mut self) {
-        self.initial_board = self.get_initial_board();
-        self.update_board(self.initial_board.clone());
+        let board: sokoban::Sokoban = self.get_initial_board();
+        self.update_board(board);
     }
 
     fn input(&mut self, event: Gd<InputEvent>) {
+        self.idle_elapsed = 0.0;
+        if self.attract.is_some() {
+            self.stop_attract();
+            return;
+        }
         if event.is_pressed() && !event.is_echo() {
-            if event.is_action_pressed(Sokoban::MOVE_UP.into()) {
-                self.update_board(self.board.you_move(Direction::Up));
-            } else if event.is_action_pressed(Sokoban::MOVE_LEFT.into()) {
-                self.update_board(self.board.you_move(Direction::Left));
-            } else if event.is_action_pressed(Sokoban::MOVE_DOWN.into()) {
-                self.update_board(self.board.you_move(Direction::Down));
-            } else if event.is_action_pressed(Sokoban::MOVE_RIGHT.into()) {
-                self.update_board(self.board.you_move(Direction::Right));
-            } else if event.is_action_pressed(Sokoban::RESET.into()) {
-                self.update_board(self.initial_board.clone());
+            if event.is_action_pressed(Sokoban::RESET.into()) {
+                self.reset();
+            } else if event.is_action_pressed(Sokoban::UNDO.into()) {
+                self.undo();
+            } else if let Some((action, avatar, direction)) = self.move_action(&event) {
+                self.held = Some((action, avatar, direction));
+                self.repeat_elapsed = 0.0;
+                self.avatar_move(avatar, direction);
+            }
+        }
+    }
+
+    fn process(&mut self, delta: f64) {
+        self.board
+            .tick(std::time::Duration::from_secs_f64(delta.max(0.0)));
+
+        if let Some(animation) = self.animation.as_mut() {
+            animation.remaining -= delta;
+            if animation.remaining <= 0.0 {
+                self.finish_animation();
+                if let Some((avatar, direction)) = self.buffered_moves.pop_front() {
+                    self.avatar_move(avatar, direction);
+                }
+            }
+        }
+
+        self.process_attract(delta);
+
+        // held moves repeat on our own clock rather than the OS's key
+        // repeat so that the rate is the same everywhere
+        let Some((action, avatar, direction)) = self.held.clone() else {
+            return;
+        };
+        if !Input::singleton().is_action_pressed(action) {
+            self.held = None;
+            return;
+        }
+        if self.input_repeat_rate <= 0.0 {
+            return;
+        }
+        self.repeat_elapsed += delta;
+        if self.repeat_elapsed >= 1.0 / self.input_repeat_rate {
+            self.repeat_elapsed -= 1.0 / self.input_repeat_rate;
+            self.avatar_move(avatar, direction);
+        }
+    }
+}
+
+#[godot_api]
+impl Sokoban {
+    /// The value of [`Direction::Up`] as GDScript sees it
+    #[constant]
+    const DIRECTION_UP: i32 = 0;
+    /// The value of [`Direction::Left`] as GDScript sees it
+    #[constant]
+    const DIRECTION_LEFT: i32 = 1;
+    /// The value of [`Direction::Down`] as GDScript sees it
+    #[constant]
+    const DIRECTION_DOWN: i32 = 2;
+    /// The value of [`Direction::Right`] as GDScript sees it
+    #[constant]
+    const DIRECTION_RIGHT: i32 = 3;
+
+    /// A `feedback` kind for walking into something that won't budge
+    #[constant]
+    const FEEDBACK_BUMP: i32 = 0;
+    /// A `feedback` kind for shoving a chain of two or more pushes
+    #[constant]
+    const FEEDBACK_HEAVY_PUSH: i32 = 1;
+    /// A `feedback` kind for triggering the last target
+    #[constant]
+    const FEEDBACK_WIN: i32 = 2;
+
+    /// A `turn_events` kind for a push shoved `from` one cell `to` another
+    #[constant]
+    const TURN_EVENT_PUSHED: i32 = 0;
+    /// A `turn_events` kind for a push landing on the target `at`
+    #[constant]
+    const TURN_EVENT_TRIGGERED: i32 = 1;
+    /// A `turn_events` kind for a push leaving the target `at`
+    #[constant]
+    const TURN_EVENT_UNTRIGGERED: i32 = 2;
+    /// A `turn_events` kind for a move toward `direction` going nowhere
+    #[constant]
+    const TURN_EVENT_BLOCKED: i32 = 3;
+
+    /// An `events` kind for a push worn down at `at` with `remaining`
+    /// pushes left in it
+    #[constant]
+    const EVENT_WORN: i32 = 0;
+    /// An `events` kind for the cracked floor at `at` breaking into a stop
+    #[constant]
+    const EVENT_CRACKED: i32 = 1;
+    /// An `events` kind for something teleporting `from` one cell `to`
+    /// another
+    #[constant]
+    const EVENT_TELEPORTED: i32 = 2;
+    /// An `events` kind for a magnet pulling a push `from` one cell `to`
+    /// the next
+    #[constant]
+    const EVENT_PULLED: i32 = 3;
+    /// An `events` kind for picking up the item `at` a cell, with its
+    /// `item_kind`
+    #[constant]
+    const EVENT_PICKED_UP: i32 = 4;
+    /// An `events` kind for pushes merging `at` a cell into one of `size`,
+    /// with the `color` it's become, or `-1` if it has none
+    #[constant]
+    const EVENT_MERGED: i32 = 5;
+    /// An `events` kind for a push splitting off `from` a splitter `to`
+    /// the next cell
+    #[constant]
+    const EVENT_SPLIT: i32 = 6;
+    /// An `events` kind for a push sliding over ice `from` where it was
+    /// pushed `to` where it stopped
+    #[constant]
+    const EVENT_SLID: i32 = 7;
+
+    /// An `accessibility_mode` where every tile is drawn as normal
+    #[constant]
+    const ACCESSIBILITY_STANDARD: i32 = 0;
+    /// An `accessibility_mode` where tiles are drawn with their
+    /// `*_shape_atlas` tiles, which are told apart by shape and pattern
+    /// rather than by color alone
+    ///
+    /// Any role whose source has no tile at its `*_shape_atlas`
+    /// coordinates is drawn with its standard tile instead.
+    #[constant]
+    const ACCESSIBILITY_SHAPES: i32 = 1;
+
+    /// A tutorial step `goal` of standing on the cell at `at`
+    #[constant]
+    const TUTORIAL_GOAL_YOU_AT: i32 = 0;
+    /// A tutorial step `goal` of getting a push onto the cell at `at`
+    #[constant]
+    const TUTORIAL_GOAL_PUSH_AT: i32 = 1;
+    /// A tutorial step `goal` of triggering every target
+    #[constant]
+    const TUTORIAL_GOAL_ALL_TARGETS_TRIGGERED: i32 = 2;
+
+    /// Emitted when a tutorial step begins, with what to tell the player
+    #[signal]
+    fn tutorial_step_started(index: i32, prompt: GodotString);
+
+    /// Emitted when the last tutorial step is done
+    #[signal]
+    fn tutorial_finished();
+
+    /// Emitted when a move ought to be felt, with a `strength` in `[0, 1]`
+    ///
+    /// `kind` is one of the `FEEDBACK_*` constants.
+    #[signal]
+    fn feedback(kind: i32, strength: f64);
+
+    /// Emitted for each of the `events` of a move, once it's been made
+    #[signal]
+    fn board_event(event: Dictionary);
+
+    /// Emitted when a solution starts playing by itself after
+    /// `attract_delay`
+    #[signal]
+    fn attract_started();
+
+    /// Emitted when a solution playing by itself stops, and the game is
+    /// back how it was left
+    #[signal]
+    fn attract_stopped();
+
+    /// Move you one tile toward `direction`, one of the `DIRECTION_*`s
+    #[func]
+    fn you_move(&mut self, direction: i32) {
+        self.stop_attract();
+        match Direction::try_from(direction) {
+            Ok(direction) => self.avatar_move(Avatar::You, direction),
+            Err(error) => godot_error!("{}: {}", error, direction),
+        }
+    }
+
+    /// Gets the first move of a way to win from here, one of the
+    /// `DIRECTION_*`s, or `-1` if none was found within `seconds`
+    #[func]
+    fn hint(&self, seconds: f64) -> i32 {
+        let limits: sokoban::solver::Limits = sokoban::solver::Limits {
+            nodes: None,
+            time: Some(std::time::Duration::from_secs_f64(seconds.max(0.0))),
+        };
+        match self.board.hint(limits) {
+            Some(direction) => direction.into(),
+            None => -1,
+        }
+    }
+
+    /// Checks if you could move toward `direction`, one of the `DIRECTION_*`s
+    #[func]
+    fn can_move(&self, direction: i32) -> bool {
+        match Direction::try_from(direction) {
+            Ok(direction) => self.board.can_move(direction),
+            Err(error) => {
+                godot_error!("{}: {}", error, direction);
+                false
+            }
+        }
+    }
+
+    /// Checks if moving toward `direction` would push something
+    #[func]
+    fn can_push(&self, direction: i32) -> bool {
+        match Direction::try_from(direction) {
+            Ok(direction) => self.board.can_push(direction),
+            Err(error) => {
+                godot_error!("{}: {}", error, direction);
+                false
+            }
+        }
+    }
+
+    /// The global position of the center of the cell at `coord`
+    ///
+    /// This accounts for the node's whole transform, so it's suitable
+    /// for pointing a camera at.
+    #[func]
+    fn cell_to_world(&self, coord: Vector2i) -> Vector2 {
+        self.base.to_global(self.base.map_to_local(coord))
+    }
+
+    /// The cell that the global position `pos` falls in
+    #[func]
+    fn world_to_cell(&self, pos: Vector2) -> Vector2i {
+        self.base.local_to_map(self.base.to_local(pos))
+    }
+
+    /// The smallest rectangle of cells containing the whole board
+    #[func]
+    fn used_rect(&self) -> Rect2i {
+        self.base.get_used_rect()
+    }
+
+    /// The cells of all the pushes stuck in a corner off of a target
+    ///
+    /// If there are any, the level can't be won without undoing.
+    #[func]
+    fn deadlocked_pushes(&self) -> Array<Vector2i> {
+        let mut pushes: Array<Vector2i> = Array::new();
+        for push in self.board.deadlocked_pushes().iter() {
+            pushes.push((*push).into());
+        }
+        pushes
+    }
+
+    /// The cells the player could walk to without pushing anything
+    #[func]
+    fn reachable_tiles(&self) -> Array<Vector2i> {
+        let mut tiles: Array<Vector2i> = Array::new();
+        for tile in self.board.reachable_tiles().iter() {
+            tiles.push((*tile).into());
+        }
+        tiles
+    }
+
+    /// What happened on the last move, as dictionaries with a `kind`,
+    /// one of the `TURN_EVENT_*` constants, and the cells or
+    /// `DIRECTION_*` it happened with
+    #[func]
+    fn turn_events(&self) -> Array<Dictionary> {
+        let mut events: Array<Dictionary> = Array::new();
+        for event in self.board.turn_events() {
+            let mut dictionary: Dictionary = Dictionary::new();
+            match *event {
+                sokoban::TurnEvent::Pushed { from, to } => {
+                    let (from, to): (Vector2i, Vector2i) = (from.into(), to.into());
+                    dictionary.set("kind", Sokoban::TURN_EVENT_PUSHED);
+                    dictionary.set("from", from);
+                    dictionary.set("to", to);
+                }
+                sokoban::TurnEvent::Triggered { at } => {
+                    let at: Vector2i = at.into();
+                    dictionary.set("kind", Sokoban::TURN_EVENT_TRIGGERED);
+                    dictionary.set("at", at);
+                }
+                sokoban::TurnEvent::Untriggered { at } => {
+                    let at: Vector2i = at.into();
+                    dictionary.set("kind", Sokoban::TURN_EVENT_UNTRIGGERED);
+                    dictionary.set("at", at);
+                }
+                sokoban::TurnEvent::Blocked { direction } => {
+                    dictionary.set("kind", Sokoban::TURN_EVENT_BLOCKED);
+                    dictionary.set("direction", i32::from(direction));
+                }
+            }
+            events.push(dictionary);
+        }
+        events
+    }
+
+    /// What the last move did to the things on the board, as
+    /// dictionaries with a `kind`, one of the `EVENT_*` constants, and
+    /// the cells and numbers it happened with
+    #[func]
+    fn events(&self) -> Array<Dictionary> {
+        let mut events: Array<Dictionary> = Array::new();
+        for event in self.board.events() {
+            events.push(event_to_dictionary(event));
+        }
+        events
+    }
+
+    /// Checks if the player can't move anywhere, so they'll need to
+    /// undo or reset to carry on
+    #[func]
+    fn is_stuck(&self) -> bool {
+        self.board.is_stuck()
+    }
+
+    /// Gets how many moves the board has taken to get where it is
+    ///
+    /// Unlike the `moves` of `stats`, undone moves don't count, so this
+    /// is what `par` and `move_limit` are measured against.
+    #[func]
+    fn moves(&self) -> i64 {
+        self.board.moves().into()
+    }
+
+    /// Checks if the `move_limit` ran out before the level was won, so
+    /// the player will need to undo or reset to carry on
+    #[func]
+    fn is_failed(&self) -> bool {
+        self.board.is_failed()
+    }
+
+    /// Walk the player through `steps`, one after the other
+    ///
+    /// Each step is a dictionary with the keys
+    ///
+    /// - `prompt`, a string of what to tell the player,
+    /// - `allowed`, an optional array of the `DIRECTION_*`s the player
+    ///   may move in,
+    /// - `board`, an optional dictionary of the board to start the step
+    ///   on, with `you` as a `Vector2i`, `stops`, `pushes`, and
+    ///   `targets` as arrays of `Vector2i`s, and `switches` as an
+    ///   optional array of dictionaries with the switch's `at` and the
+    ///   `walls` it opens,
+    /// - `goal`, one of the `TUTORIAL_GOAL_*` constants, and
+    /// - `at`, the `Vector2i` the goal is about, if it's about one.
+    #[func]
+    fn start_tutorial(&mut self, steps: Array<Dictionary>) {
+        match steps
+            .iter_shared()
+            .map(step_from_dictionary)
+            .collect::<Result<Vec<tutorial::Step>, String>>()
+        {
+            Ok(steps) => {
+                self.tutorial = Some(tutorial::Tutorial::new(steps));
+                self.begin_tutorial_step();
+            }
+            Err(error) => godot_error!("{}", error),
+        }
+    }
+
+    /// Draw the board again, such as after changing `accessibility_mode`
+    #[func]
+    fn redraw(&mut self) {
+        self.finish_animation();
+        self.draw_board();
+    }
+
+    /// Put the board back the way it was when the level started
+    #[func]
+    fn reset(&mut self) {
+        self.stop_attract();
+        self.finish_animation();
+        self.held = None;
+        self.buffered_moves.clear();
+        self.history.push(Action::Reset);
+        self.update_board(self.board.reset());
+    }
+
+    /// Gets the tally of how the game has gone, with the keys `moves`,
+    /// `pushes`, `undos`, `rewinds`, `resets`, and `turns`, and
+    /// `elapsed`, the seconds played while the node was processing
+    ///
+    /// This carries on through undos and resets, which are counted too,
+    /// but starts over when a new board is loaded.
+    #[func]
+    fn stats(&self) -> Dictionary {
+        let stats: sokoban::GameStats = self.board.stats();
+        let mut dictionary: Dictionary = Dictionary::new();
+        dictionary.set("moves", stats.moves);
+        dictionary.set("pushes", stats.pushes);
+        dictionary.set("undos", stats.undos);
+        dictionary.set("rewinds", stats.rewinds);
+        dictionary.set("resets", stats.resets);
+        dictionary.set("turns", stats.turns);
+        dictionary.set("elapsed", stats.elapsed.as_secs_f64());
+        dictionary
+    }
+
+    /// Take back the last move that changed the board
+    #[func]
+    fn undo(&mut self) {
+        self.stop_attract();
+        self.finish_animation();
+        self.held = None;
+        self.buffered_moves.clear();
+        self.history.push(Action::Undo);
+        self.update_board(self.board.undo());
+    }
+
+    /// Take back up to the last `moves` moves at once, if the board has
+    /// any rewinds left
+    #[func]
+    fn rewind(&mut self, moves: i64) {
+        self.stop_attract();
+        self.finish_animation();
+        self.held = None;
+        self.buffered_moves.clear();
+        let moves: usize = moves.max(0) as usize;
+        self.history.push(Action::Rewind(moves));
+        self.update_board(self.board.rewind(moves));
+    }
+
+    /// Everything done on the level since it started, as a line of text
+    /// to save and hand back to `import_history` later
+    ///
+    /// Moves, undos, rewinds, and resets are all kept, so the level
+    /// picks back up with the same undos and `stats`, apart from
+    /// `elapsed`.  A tutorial step with its own board starts the history
+    /// over.
+    #[func]
+    fn export_history(&self) -> GodotString {
+        self.history.export().into()
+    }
+
+    /// Start the level over and play back `history`, as given by
+    /// `export_history`
+    #[func]
+    fn import_history(&mut self, history: GodotString) {
+        match History::import(&history.to_string()) {
+            Ok(history) => {
+                self.stop_attract();
+                self.finish_animation();
+                self.held = None;
+                self.buffered_moves.clear();
+                let board: sokoban::Sokoban = history.replay(&self.get_initial_board());
+                self.history = history;
+                self.update_board(board);
             }
+            Err(error) => godot_error!("{}: {}", error, history),
         }
     }
 }
 
 impl Sokoban {
+    /// The cells on the board drawn from the tileset source `source_id`
+    fn used_cells(&self, source_id: i32) -> I2Array {
+        I2Array::try_from(
+            self.base
+                .get_used_cells_by_id_ex(0)
+                .source_id(source_id)
+                .done(),
+        )
+        .unwrap_or(I2Array::from(vec![]))
+    }
+
+    /// The alternative tile of the magnet source for a magnet facing
+    /// toward `facing`, turning the tile for facing right to match
+    const fn magnet_alternative(facing: Direction) -> i32 {
+        match facing {
+            Direction::Right => 0,
+            Direction::Left => 1,
+            Direction::Down => 4,
+            Direction::Up => 6,
+        }
+    }
+
     /// `you`, the name of the tile in the tileset used for you
     pub const YOU_TILE_NAME: &'static str = "you";
     /// `stop`, the name of the tile in the tileset used for stops    
@@ -91,6 +818,73 @@ impl Sokoban {
     /// The [`InputMap`] key for the right input, `move_right`
     pub const MOVE_RIGHT: &'static str = "move_right";
     pub const RESET: &'static str = "reset";
+    /// The [`InputMap`] key for the undo input, `undo`
+    pub const UNDO: &'static str = "undo";
+    /// The default [`InputMap`] key for the second player's up input
+    pub const P2_MOVE_UP: &'static str = "p2_move_up";
+    /// The default [`InputMap`] key for the second player's left input
+    pub const P2_MOVE_LEFT: &'static str = "p2_move_left";
+    /// The default [`InputMap`] key for the second player's down input
+    pub const P2_MOVE_DOWN: &'static str = "p2_move_down";
+    /// The default [`InputMap`] key for the second player's right input
+    pub const P2_MOVE_RIGHT: &'static str = "p2_move_right";
+
+    /// Find the move input in `event`, along with who it moves where
+    fn move_action(&self, event: &Gd<InputEvent>) -> Option<(StringName, Avatar, Direction)> {
+        [
+            (Sokoban::MOVE_UP.into(), Avatar::You, Direction::Up),
+            (Sokoban::MOVE_LEFT.into(), Avatar::You, Direction::Left),
+            (Sokoban::MOVE_DOWN.into(), Avatar::You, Direction::Down),
+            (Sokoban::MOVE_RIGHT.into(), Avatar::You, Direction::Right),
+            ((&self.p2_move_up).into(), Avatar::Partner, Direction::Up),
+            (
+                (&self.p2_move_left).into(),
+                Avatar::Partner,
+                Direction::Left,
+            ),
+            (
+                (&self.p2_move_down).into(),
+                Avatar::Partner,
+                Direction::Down,
+            ),
+            (
+                (&self.p2_move_right).into(),
+                Avatar::Partner,
+                Direction::Right,
+            ),
+        ]
+        .into_iter()
+        .find(|(action, _, _): &(StringName, Avatar, Direction)| {
+            event.is_action_pressed(action.clone())
+        })
+    }
+
+    fn avatar_move(&mut self, avatar: Avatar, direction: Direction) {
+        if let Some(tutorial) = &self.tutorial {
+            if !tutorial.allows(direction) {
+                return;
+            }
+        }
+        if self.animation.is_some() && self.input_buffer_size > 0 {
+            if self.buffered_moves.len() < self.input_buffer_size as usize {
+                self.buffered_moves.push_back((avatar, direction));
+            }
+            return;
+        }
+
+        self.finish_animation();
+        let previous: Option<sokoban::Sokoban> =
+            (!self.reduce_motion && self.animation_duration > 0.0).then(|| self.board.clone());
+        let outcome: sokoban::MoveOutcome = match avatar {
+            Avatar::You => self.board.you_move_mut(direction),
+            Avatar::Partner => self.board.partner_move_mut(direction),
+        };
+        self.history.push(match avatar {
+            Avatar::You => Action::You(direction),
+            Avatar::Partner => Action::Partner(direction),
+        });
+        self.apply_move(outcome, previous);
+    }
 
     fn get_initial_board(&self) -> sokoban::Sokoban {
         let mut pushes = self
@@ -110,7 +904,7 @@ impl Sokoban {
             .done();
         pushes.extend_array(triggered_targets.clone());
         targets.extend_array(triggered_targets.clone());
-        sokoban::Sokoban::new(
+        let board: sokoban::Sokoban = sokoban::Sokoban::new(
             I2::try_from(
                 self.base
                     .get_used_cells_by_id_ex(0)
@@ -128,48 +922,1220 @@ impl Sokoban {
             .unwrap_or(I2Array::from(vec![])),
             I2Array::try_from(pushes).unwrap_or(I2Array::from(vec![])),
             I2Array::try_from(targets).unwrap_or(I2Array::from(vec![])),
-        )
+        );
+        let partners: I2Array = self.used_cells(self.partner_tile);
+        let board: sokoban::Sokoban = match partners.iter().next() {
+            Some(partner) => board.with_partner(*partner),
+            None => board,
+        };
+        // an item's kind is which column of its source it's drawn from
+        let items: Vec<sokoban::Item> = self
+            .used_cells(self.item_tile)
+            .iter()
+            .map(|at| {
+                let kind: i32 = self.base.get_cell_atlas_coords(0, (*at).into()).x;
+                sokoban::Item {
+                    at: *at,
+                    kind: kind.max(0) as u32,
+                    collectible: !self.decal_item_kinds.contains(&kind),
+                }
+            })
+            .collect();
+        let walls: I2Array = self.used_cells(self.wall_tile);
+        let switches: Vec<sokoban::Switch> = self
+            .used_cells(self.switch_tile)
+            .iter()
+            .map(|at| {
+                let at_cell: Vector2i = (*at).into();
+                let linked: Option<Result<I2Array, String>> =
+                    self.switch_links.get(at_cell).map(coordinates_from_variant);
+                let walls: I2Array = match linked {
+                    Some(Ok(linked)) => linked,
+                    Some(Err(error)) => {
+                        godot_error!("the walls linked to the switch at {:?}: {}", at, error);
+                        I2Array::from(vec![])
+                    }
+                    None => walls.clone(),
+                };
+                sokoban::Switch { at: *at, walls }
+            })
+            .collect();
+        // a magnet faces whichever way its alternative tile is turned
+        let magnets: Vec<sokoban::Magnet> = self
+            .used_cells(self.magnet_tile)
+            .iter()
+            .map(|at| {
+                let alternative: i32 = self.base.get_cell_alternative_tile(0, (*at).into());
+                let facing: Direction = [
+                    Direction::Up,
+                    Direction::Left,
+                    Direction::Down,
+                    Direction::Right,
+                ]
+                .into_iter()
+                .find(|facing| Sokoban::magnet_alternative(*facing) == alternative)
+                .unwrap_or(Direction::Right);
+                sokoban::Magnet { at: *at, facing }
+            })
+            .collect();
+        let board: sokoban::Sokoban = board
+            .with_items(items)
+            .with_cracks(self.used_cells(self.crack_tile))
+            .with_switches(switches)
+            .with_magnets(magnets)
+            .with_ices(self.used_cells(self.ice_tile));
+        let teleporters: Vec<(I2, I2)> = self
+            .teleporter_links
+            .iter_shared()
+            .filter_map(
+                |(a, b)| match (I2::try_from(a.clone()), I2::try_from(b.clone())) {
+                    (Ok(a), Ok(b)) => Some((a, b)),
+                    _ => {
+                        godot_error!("teleporters must be linked by cell: {} and {}", a, b);
+                        None
+                    }
+                },
+            )
+            .collect();
+        // enemies placed in a scene chase you
+        let enemies: Vec<sokoban::Enemy> = self
+            .used_cells(self.enemy_tile)
+            .iter()
+            .map(|at| sokoban::Enemy {
+                at: *at,
+                movement: sokoban::Movement::Chase,
+            })
+            .collect();
+        let costs: Vec<(I2, u32)> = self
+            .tile_costs
+            .iter_shared()
+            .filter_map(|(cell, cost)| {
+                let moves: Option<u32> = cost
+                    .try_to::<i64>()
+                    .ok()
+                    .and_then(|moves| u32::try_from(moves).ok());
+                match (I2::try_from(cell.clone()), moves) {
+                    (Ok(at), Some(moves)) => Some((at, moves)),
+                    _ => {
+                        godot_error!("{} is not a cost for the cell {}", cost, cell);
+                        None
+                    }
+                }
+            })
+            .collect();
+        let board: sokoban::Sokoban = board
+            .with_teleporters(teleporters)
+            .with_enemies(enemies)
+            .with_costs(costs);
+        let board: sokoban::Sokoban = match self.push_limit {
+            limit if limit > 0 => board.with_push_limit(Some(limit as usize)),
+            _ => board,
+        };
+        let board: sokoban::Sokoban = match self.move_limit {
+            limit if limit > 0 => board.with_move_limit(Some(limit as u32)),
+            _ => board,
+        };
+        match self.par {
+            par if par > 0 => board.with_par(Some(par as u32)),
+            _ => board,
+        }
     }
 
-    fn update_board(&mut self, board: sokoban::Sokoban) {
-        self.board = board;
-        self.base.clear_layer(0);
-        for stop in self.board.stops().iter() {
-            self.base
-                .set_cell_ex(0, (*stop).into())
-                .source_id(self.stop_tile)
-                .atlas_coords(Vector2i::new(0, 0))
-                .done();
+    /// Show the move the board just made, giving feedback on it
+    ///
+    /// The move is animated from `previous`, if there is one.
+    fn apply_move(&mut self, outcome: sokoban::MoveOutcome, previous: Option<sokoban::Sokoban>) {
+        match outcome {
+            // the board is done with, so there's nothing to show
+            sokoban::MoveOutcome::GameOver => return,
+            sokoban::MoveOutcome::Blocked => self.give_feedback(Sokoban::FEEDBACK_BUMP, 0.3),
+            sokoban::MoveOutcome::Pushed(pushes_moved) if pushes_moved >= 2 => self.give_feedback(
+                Sokoban::FEEDBACK_HEAVY_PUSH,
+                (0.25 * pushes_moved as f64).min(1.0),
+            ),
+            sokoban::MoveOutcome::Won => self.give_feedback(Sokoban::FEEDBACK_WIN, 1.0),
+            _ => {}
         }
-        for push in self.board.pushes().iter() {
-            self.base
-                .set_cell_ex(0, (*push).into())
-                .source_id(self.push_tile)
-                .atlas_coords(Vector2i::new(0, 0))
-                .done();
+
+        self.board_changed();
+        if let Some(previous) = previous {
+            self.animate(&previous);
         }
-        for target in self.board.targets().iter() {
+        for event in self.events().iter_shared() {
             self.base
-                .set_cell_ex(0, (*target).into())
-                .source_id(self.target_tile)
-                .atlas_coords(Vector2i::new(0, 0))
-                .done();
+                .emit_signal("board_event".into(), &[event.to_variant()]);
+        }
+
+        if let Some(tutorial) = self.tutorial.as_mut() {
+            if tutorial.advance(&self.board) {
+                self.begin_tutorial_step();
+            }
+        }
+    }
+
+    /// Set up the current tutorial step and let GDScript know it began
+    fn begin_tutorial_step(&mut self) {
+        let Some(tutorial) = &self.tutorial else {
+            return;
+        };
+        let Some(step) = tutorial.current_step() else {
+            self.tutorial = None;
+            self.base.emit_signal("tutorial_finished".into(), &[]);
+            return;
+        };
+        let index: i32 = tutorial.current_index() as i32;
+        let prompt: GodotString = step.prompt.as_str().into();
+
+        if let Some(board) = step.board.clone() {
+            self.finish_animation();
+            self.buffered_moves.clear();
+            self.history = History::new();
+            self.update_board(board);
+        }
+        self.base.emit_signal(
+            "tutorial_step_started".into(),
+            &[index.to_variant(), prompt.to_variant()],
+        );
+    }
+
+    /// Slide everything that moved since `previous` into its new cell
+    ///
+    /// Pushes are followed by what the move says happened to them,
+    /// since merging, splitting, and breaking pushes all change which
+    /// push is which in the order the board keeps them in.
+    ///
+    /// The moving tiles are stood in for by sprites until the animation
+    /// finishes, at which point the board is drawn as normal.
+    fn animate(&mut self, previous: &sokoban::Sokoban) {
+        // pushes that wore out where they were pushed to are gone by the
+        // end of the move
+        let worn_out: Vec<I2> = self
+            .board
+            .events()
+            .iter()
+            .filter_map(|event| match *event {
+                sokoban::Event::Worn { at, remaining: 0 } => Some(at),
+                _ => None,
+            })
+            .filter(|at| !self.board.pushes().contains(at))
+            .collect();
+        let shoves = self
+            .board
+            .turn_events()
+            .iter()
+            .filter_map(|event| match *event {
+                sokoban::TurnEvent::Pushed { from, to } => Some(Slide {
+                    fades_out: worn_out.contains(&to),
+                    ..Slide::new(Role::Push, from, to)
+                }),
+                _ => None,
+            });
+        let mut slides: Vec<Slide> = shoves.collect();
+        for event in self.board.events() {
+            match *event {
+                sokoban::Event::Split { from, to } => {
+                    slides.push(Slide::new(Role::Push, from, to));
+                }
+                sokoban::Event::Cracked { at } => slides.push(Slide {
+                    fades_in: true,
+                    ..Slide::new(Role::Stop, at, at)
+                }),
+                sokoban::Event::Pulled { from, to } | sokoban::Event::Slid { from, to } => {
+                    follow(&mut slides, Role::Push, from, to)
+                }
+                _ => {}
+            }
+        }
+        if previous.you() != self.board.you() {
+            slides.push(Slide::new(Role::You, previous.you(), self.board.you()));
+        }
+        if let (Some(from), Some(to)) = (previous.partner(), self.board.partner()) {
+            if from != to {
+                slides.push(Slide::new(Role::Partner, from, to));
+            }
+        }
+        for (from, to) in previous.enemies().iter().zip(self.board.enemies()) {
+            if from.at != to.at {
+                slides.push(Slide::new(Role::Enemy, from.at, to.at));
+            }
+        }
+
+        // anything that went through a teleporter slides onto it before
+        // jumping to its twin, where pushes slid to the one end and you
+        // and your partner ended up at the other
+        for event in self.board.events() {
+            let sokoban::Event::Teleported { from, to } = *event else {
+                continue;
+            };
+            if let Some(slide) = slides.iter_mut().find(|slide| {
+                slide.teleports_to.is_none()
+                    && match slide.role {
+                        Role::Push => slide.to == from,
+                        _ => slide.to == to,
+                    }
+            }) {
+                slide.to = from;
+                slide.teleports_to = Some(to);
+            }
         }
-        for triggered_target in self.board.triggered_targets().iter() {
+
+        let mut sprites: Vec<Gd<Sprite2D>> = vec![];
+        for slide in slides {
+            let mut sprite: Gd<Sprite2D> = Sprite2D::new_alloc();
+            if let Some((texture, region)) = self.tile_texture(slide.role) {
+                sprite.set_texture(texture);
+                sprite.set_region_enabled(true);
+                sprite.set_region_rect(Rect2::from_rect2i(region));
+            }
+            sprite.set_position(self.base.map_to_local(slide.from.into()));
+            self.base.add_child(sprite.clone().upcast());
             self.base
-                .set_cell_ex(0, (**triggered_target).into())
-                .source_id(self.triggered_target_tile)
-                .atlas_coords(Vector2i::new(0, 0))
-                .done();
+                .erase_cell(0, slide.teleports_to.unwrap_or(slide.to).into());
+            if let Some(mut tween) = sprite.create_tween() {
+                tween.tween_property(
+                    sprite.clone().upcast(),
+                    "position".into(),
+                    self.base.map_to_local(slide.to.into()).to_variant(),
+                    self.animation_duration,
+                );
+                if let Some(twin) = slide.teleports_to {
+                    tween.tween_property(
+                        sprite.clone().upcast(),
+                        "position".into(),
+                        self.base.map_to_local(twin.into()).to_variant(),
+                        0.0,
+                    );
+                }
+            }
+            if slide.fades_out || slide.fades_in {
+                let alpha: f64 = if slide.fades_in { 1.0 } else { 0.0 };
+                sprite.set_modulate(Color::from_rgba(1.0, 1.0, 1.0, 1.0 - alpha as f32));
+                if let Some(mut tween) = sprite.create_tween() {
+                    tween.tween_property(
+                        sprite.clone().upcast(),
+                        "modulate:a".into(),
+                        alpha.to_variant(),
+                        self.animation_duration,
+                    );
+                }
+            }
+            sprites.push(sprite);
+        }
+        self.animation = Some(Animation {
+            remaining: self.animation_duration,
+            sprites,
+        });
+    }
+
+    /// Count how long nobody's been playing, and play the next move of
+    /// a solution once they've been gone for `attract_delay`
+    fn process_attract(&mut self, delta: f64) {
+        let Some(attract) = self.attract.as_mut() else {
+            // holding a move down counts as playing
+            if self.attract_delay <= 0.0 || self.held.is_some() || self.tutorial.is_some() {
+                self.idle_elapsed = 0.0;
+                return;
+            }
+            self.idle_elapsed += delta;
+            if self.idle_elapsed >= self.attract_delay {
+                self.idle_elapsed = 0.0;
+                self.start_attract();
+            }
+            return;
+        };
+        if self.attract_move_rate <= 0.0 {
+            return;
+        }
+        attract.elapsed += delta;
+        if attract.elapsed < 1.0 / self.attract_move_rate {
+            return;
+        }
+        attract.elapsed -= 1.0 / self.attract_move_rate;
+        match attract.moves.pop_front() {
+            Some(direction) => self.avatar_move(Avatar::You, direction),
+            None => self.stop_attract(),
+        }
+    }
+
+    /// Look for a way to win from here, and start playing it if there is
+    /// one
+    fn start_attract(&mut self) {
+        let limits: sokoban::solver::Limits = sokoban::solver::Limits {
+            nodes: None,
+            time: Some(std::time::Duration::from_secs_f64(
+                self.attract_search_seconds.max(0.0),
+            )),
+        };
+        let moves: Vec<Direction> = match sokoban::solver::iterative_deepening(&self.board, limits)
+        {
+            sokoban::solver::SolverResult::Solved(moves) if !moves.is_empty() => moves,
+            _ => return,
+        };
+        self.finish_animation();
+        self.buffered_moves.clear();
+        self.attract = Some(Attract {
+            moves: moves.into(),
+            elapsed: 0.0,
+            board: self.board.clone(),
+            history: self.history.clone(),
+        });
+        self.base.emit_signal("attract_started".into(), &[]);
+    }
+
+    /// Stop any solution playing by itself, and put the game back how
+    /// it was before it started
+    fn stop_attract(&mut self) {
+        let Some(attract) = self.attract.take() else {
+            return;
+        };
+        self.finish_animation();
+        self.buffered_moves.clear();
+        self.idle_elapsed = 0.0;
+        self.history = attract.history;
+        self.update_board(attract.board);
+        self.base.emit_signal("attract_stopped".into(), &[]);
+    }
+
+    /// Skip to the end of any animation in progress
+    fn finish_animation(&mut self) {
+        if let Some(animation) = self.animation.take() {
+            for mut sprite in animation.sprites {
+                sprite.queue_free();
+            }
+            self.draw_board();
+        }
+    }
+
+    /// The texture `role` is drawn from, and where in it the tile is
+    fn tile_texture(&self, role: Role) -> Option<(Gd<Texture2D>, Rect2i)> {
+        let source: Gd<TileSetAtlasSource> = self.atlas_source(role)?;
+        Some((
+            source.get_texture()?,
+            source.get_tile_texture_region(self.atlas_coords(role)),
+        ))
+    }
+
+    /// The atlas source in the tileset that `role` is drawn from
+    fn atlas_source(&self, role: Role) -> Option<Gd<TileSetAtlasSource>> {
+        self.base
+            .get_tileset()?
+            .get_source(self.source_id(role))?
+            .try_cast::<TileSetAtlasSource>()
+    }
+
+    fn source_id(&self, role: Role) -> i32 {
+        match role {
+            Role::You => self.you_tile,
+            Role::Partner => self.partner_tile,
+            Role::Stop => self.stop_tile,
+            Role::Push => self.push_tile,
+            Role::Target => self.target_tile,
+            Role::TriggeredTarget => self.triggered_target_tile,
+            Role::Crack => self.crack_tile,
+            Role::Switch => self.switch_tile,
+            Role::Wall => self.wall_tile,
+            Role::Magnet(_) => self.magnet_tile,
+            Role::Ice => self.ice_tile,
+            Role::Teleporter => self.teleporter_tile,
+            Role::Enemy => self.enemy_tile,
+            Role::Item(_) => self.item_tile,
+        }
+    }
+
+    /// Where in its source `role`'s tile is under the accessibility mode
+    ///
+    /// Only the roles with a `*_shape_atlas` have shape tiles, and shape
+    /// tiles the source doesn't have fall back on the standard one, so a
+    /// tileset without them still draws everything.  The same goes for
+    /// items of a kind the source has no tile for.
+    fn atlas_coords(&self, role: Role) -> Vector2i {
+        let standard: Vector2i = Vector2i::new(0, 0);
+        let shape: Vector2i = match role {
+            Role::Item(kind) => Vector2i::new(i32::try_from(kind).unwrap_or(0), 0),
+            _ if self.accessibility_mode != Sokoban::ACCESSIBILITY_SHAPES => return standard,
+            Role::You => self.you_shape_atlas,
+            Role::Partner => self.partner_shape_atlas,
+            Role::Stop => self.stop_shape_atlas,
+            Role::Push => self.push_shape_atlas,
+            Role::Target => self.target_shape_atlas,
+            Role::TriggeredTarget => self.triggered_target_shape_atlas,
+            _ => return standard,
+        };
+        match self.atlas_source(role) {
+            Some(source) if source.has_tile(shape) => shape,
+            _ => standard,
         }
+    }
+
+    fn set_role_cell(&mut self, coordinate: I2, role: Role) {
+        let source_id: i32 = self.source_id(role);
+        let atlas_coords: Vector2i = self.atlas_coords(role);
+        let alternative_tile: i32 = match role {
+            Role::Magnet(facing) => Sokoban::magnet_alternative(facing),
+            _ => 0,
+        };
         self.base
-            .set_cell_ex(0, dbg!(self.board.you().into()))
-            .source_id(self.you_tile)
-            .atlas_coords(Vector2i::new(0, 0))
+            .set_cell_ex(0, coordinate.into())
+            .source_id(source_id)
+            .atlas_coords(atlas_coords)
+            .alternative_tile(alternative_tile)
             .done();
+    }
+
+    fn give_feedback(&mut self, kind: i32, strength: f64) {
+        self.base.emit_signal(
+            "feedback".into(),
+            &[kind.to_variant(), strength.to_variant()],
+        );
+        if self.vibration_enabled {
+            Input::singleton()
+                .start_joy_vibration_ex(self.vibration_device, strength as f32, strength as f32)
+                .duration((0.1 + 0.2 * strength) as f32)
+                .done();
+        }
+    }
+
+    fn update_board(&mut self, board: sokoban::Sokoban) {
+        self.board = board;
+        self.board_changed();
+    }
 
-        if self.board.all_targets_triggered() {
+    fn board_changed(&mut self) {
+        self.draw_board();
+
+        if self.board.is_won() {
             godot_print!("Win!");
         }
     }
+
+    fn draw_board(&mut self) {
+        self.base.clear_layer(0);
+        let cells: Vec<(I2, sokoban::Entity)> = self.board.cells().collect();
+        for (cell, entity) in cells {
+            let role: Role = match entity {
+                sokoban::Entity::Stop => Role::Stop,
+                sokoban::Entity::Wall => Role::Wall,
+                sokoban::Entity::Push => Role::Push,
+                sokoban::Entity::Target => Role::Target,
+                sokoban::Entity::TriggeredTarget => Role::TriggeredTarget,
+                sokoban::Entity::Enemy => Role::Enemy,
+                sokoban::Entity::Partner => Role::Partner,
+                sokoban::Entity::You | sokoban::Entity::Clone => Role::You,
+                sokoban::Entity::Ice => Role::Ice,
+                sokoban::Entity::Crack => Role::Crack,
+                sokoban::Entity::Teleporter => Role::Teleporter,
+                sokoban::Entity::Switch => Role::Switch,
+                sokoban::Entity::Magnet(facing) => Role::Magnet(facing),
+                sokoban::Entity::Item(kind) => Role::Item(kind),
+                _ => continue,
+            };
+            self.set_role_cell(cell, role);
+        }
+    }
+}
+
+/// `event` as a dictionary for GDScript, as described by
+/// [`Sokoban::events`]
+fn event_to_dictionary(event: &sokoban::Event) -> Dictionary {
+    let mut dictionary: Dictionary = Dictionary::new();
+    match *event {
+        sokoban::Event::Worn { at, remaining } => {
+            let at: Vector2i = at.into();
+            dictionary.set("kind", Sokoban::EVENT_WORN);
+            dictionary.set("at", at);
+            dictionary.set("remaining", remaining as i64);
+        }
+        sokoban::Event::Cracked { at } => {
+            let at: Vector2i = at.into();
+            dictionary.set("kind", Sokoban::EVENT_CRACKED);
+            dictionary.set("at", at);
+        }
+        sokoban::Event::Teleported { from, to } => {
+            let (from, to): (Vector2i, Vector2i) = (from.into(), to.into());
+            dictionary.set("kind", Sokoban::EVENT_TELEPORTED);
+            dictionary.set("from", from);
+            dictionary.set("to", to);
+        }
+        sokoban::Event::Pulled { from, to } => {
+            let (from, to): (Vector2i, Vector2i) = (from.into(), to.into());
+            dictionary.set("kind", Sokoban::EVENT_PULLED);
+            dictionary.set("from", from);
+            dictionary.set("to", to);
+        }
+        sokoban::Event::PickedUp { item } => {
+            let at: Vector2i = item.at.into();
+            dictionary.set("kind", Sokoban::EVENT_PICKED_UP);
+            dictionary.set("at", at);
+            dictionary.set("item_kind", item.kind as i64);
+        }
+        sokoban::Event::Merged { at, size, color } => {
+            let at: Vector2i = at.into();
+            dictionary.set("kind", Sokoban::EVENT_MERGED);
+            dictionary.set("at", at);
+            dictionary.set("size", size as i64);
+            dictionary.set("color", color.map_or(-1, i64::from));
+        }
+        sokoban::Event::Split { from, to } => {
+            let (from, to): (Vector2i, Vector2i) = (from.into(), to.into());
+            dictionary.set("kind", Sokoban::EVENT_SPLIT);
+            dictionary.set("from", from);
+            dictionary.set("to", to);
+        }
+        sokoban::Event::Slid { from, to } => {
+            let (from, to): (Vector2i, Vector2i) = (from.into(), to.into());
+            dictionary.set("kind", Sokoban::EVENT_SLID);
+            dictionary.set("from", from);
+            dictionary.set("to", to);
+        }
+    }
+    dictionary
+}
+
+fn step_from_dictionary(step: Dictionary) -> Result<tutorial::Step, String> {
+    let prompt: String = match step.get("prompt") {
+        Some(prompt) => prompt
+            .try_to::<GodotString>()
+            .map_err(|_| format!("{} is not a prompt", prompt))?
+            .to_string(),
+        None => String::new(),
+    };
+    let allowed: Option<Vec<Direction>> = match step.get("allowed") {
+        Some(allowed) => Some(
+            allowed
+                .try_to::<VariantArray>()
+                .map_err(|_| format!("{} is not an array of directions", allowed))?
+                .iter_shared()
+                .map(|direction| {
+                    Direction::try_from(direction.try_to::<i32>().unwrap_or(-1))
+                        .map_err(|error| format!("{}: {}", error, direction))
+                })
+                .collect::<Result<Vec<Direction>, String>>()?,
+        ),
+        None => None,
+    };
+    let board: Option<sokoban::Sokoban> = match step.get("board") {
+        Some(board) => Some(board_from_dictionary(
+            board
+                .try_to::<Dictionary>()
+                .map_err(|_| format!("{} is not a board", board))?,
+        )?),
+        None => None,
+    };
+    let at = || -> Result<I2, String> {
+        I2::try_from(step.get("at").unwrap_or_default())
+            .map_err(|error| format!("a tutorial goal's `at`: {}", error))
+    };
+    let goal: tutorial::Goal = match step.get("goal").and_then(|goal| goal.try_to::<i32>().ok()) {
+        Some(Sokoban::TUTORIAL_GOAL_YOU_AT) => tutorial::Goal::YouAt(at()?),
+        Some(Sokoban::TUTORIAL_GOAL_PUSH_AT) => tutorial::Goal::PushAt(at()?),
+        Some(Sokoban::TUTORIAL_GOAL_ALL_TARGETS_TRIGGERED) => tutorial::Goal::AllTargetsTriggered,
+        _ => return Err(String::from("a tutorial step needs a TUTORIAL_GOAL_* goal")),
+    };
+
+    Ok(tutorial::Step {
+        prompt,
+        allowed,
+        board,
+        goal,
+    })
+}
+
+fn board_from_dictionary(board: Dictionary) -> Result<sokoban::Sokoban, String> {
+    let switches: Vec<sokoban::Switch> = match board.get("switches") {
+        Some(switches) => switches
+            .try_to::<VariantArray>()
+            .map_err(|_| String::from("a board's `switches` must be an array"))?
+            .iter_shared()
+            .map(|switch| {
+                let switch: Dictionary = switch
+                    .try_to::<Dictionary>()
+                    .map_err(|_| format!("{} is not a switch", switch))?;
+                Ok(sokoban::Switch {
+                    at: I2::try_from(switch.get("at").unwrap_or_default())
+                        .map_err(|error| format!("a switch's `at`: {}", error))?,
+                    walls: coordinates_from_dictionary(&switch, "walls")?,
+                })
+            })
+            .collect::<Result<Vec<sokoban::Switch>, String>>()?,
+        None => vec![],
+    };
+
+    Ok(sokoban::Sokoban::new(
+        I2::try_from(board.get("you").unwrap_or_default())
+            .map_err(|error| format!("a board's `you`: {}", error))?,
+        coordinates_from_dictionary(&board, "stops")?,
+        coordinates_from_dictionary(&board, "pushes")?,
+        coordinates_from_dictionary(&board, "targets")?,
+    )
+    .with_switches(switches))
+}
+
+fn coordinates_from_dictionary(dictionary: &Dictionary, key: &str) -> Result<I2Array, String> {
+    match dictionary.get(key) {
+        Some(coordinates) => {
+            coordinates_from_variant(coordinates).map_err(|error| format!("`{}`: {}", key, error))
+        }
+        None => Ok(I2Array::from(vec![])),
+    }
+}
+
+fn coordinates_from_variant(coordinates: Variant) -> Result<I2Array, String> {
+    coordinates
+        .try_to::<VariantArray>()
+        .map_err(|_| String::from("must be an array"))?
+        .iter_shared()
+        .map(|coordinate| I2::try_from(coordinate).map_err(String::from))
+        .collect::<Result<I2Array, String>>()
+}
+
+/// Poker hand evaluation for GDScript, with no need for any instances
+///
+/// Cards are given either as strings like `"As"` or `"Td"`, a rank
+/// character followed by a suit character, as ints `13 * suit + rank`,
+/// where ranks count up from `0` for Two and suits are ordered Diamond,
+/// Club, Heart, Spade, or as [`Card`]s.  Hands must be exactly five
+/// cards.
+#[derive(GodotClass)]
+#[class(base=Object, init)]
+pub struct PokerUtils {}
+
+#[godot_api]
+impl PokerUtils {
+    /// The `hand_score` of a hand only evaluated on its ranks
+    #[constant]
+    const HAND_HIGH_CARD: i32 = 0;
+    /// The `hand_score` of a hand with a pair
+    #[constant]
+    const HAND_PAIR: i32 = 1;
+    /// The `hand_score` of a hand with two pairs
+    #[constant]
+    const HAND_TWO_PAIR: i32 = 2;
+    /// The `hand_score` of a hand with three of a kind
+    #[constant]
+    const HAND_THREE_OF_A_KIND: i32 = 3;
+    /// The `hand_score` of a straight
+    #[constant]
+    const HAND_STRAIGHT: i32 = 4;
+    /// The `hand_score` of a flush
+    #[constant]
+    const HAND_FLUSH: i32 = 5;
+    /// The `hand_score` of a full house
+    #[constant]
+    const HAND_FULL_HOUSE: i32 = 6;
+    /// The `hand_score` of a hand with four of a kind
+    #[constant]
+    const HAND_FOUR_OF_A_KIND: i32 = 7;
+    /// The `hand_score` of a straight flush
+    #[constant]
+    const HAND_STRAIGHT_FLUSH: i32 = 8;
+    /// The `hand_score` of a royal flush
+    #[constant]
+    const HAND_ROYAL_FLUSH: i32 = 9;
+
+    /// `1` if hand `a` beats hand `b`, `-1` if it loses, or `0` if they tie
+    ///
+    /// This is also `0` if either isn't a valid hand.
+    #[func]
+    fn compare_hands(a: VariantArray, b: VariantArray) -> i32 {
+        match (hand_from_variants(a), hand_from_variants(b)) {
+            (Ok(a), Ok(b)) => match a.cmp(&b) {
+                std::cmp::Ordering::Greater => 1,
+                std::cmp::Ordering::Less => -1,
+                std::cmp::Ordering::Equal => 0,
+            },
+            (Err(error), _) | (_, Err(error)) => {
+                godot_error!("{}", error);
+                0
+            }
+        }
+    }
+
+    /// The name of the kind of hand `cards` make, like `"Full House"`
+    ///
+    /// This is empty if `cards` isn't a valid hand.
+    #[func]
+    fn hand_name(cards: VariantArray) -> GodotString {
+        match hand_from_variants(cards) {
+            Ok(hand) => hand.kind().name().into(),
+            Err(error) => {
+                godot_error!("{}", error);
+                GodotString::new()
+            }
+        }
+    }
+
+    /// What `cards` make, ranks and all, like
+    /// `"Full House, Kings over Twos"`
+    ///
+    /// This is empty if `cards` isn't a valid hand.
+    #[func]
+    fn hand_description(cards: VariantArray) -> GodotString {
+        match hand_from_variants(cards) {
+            Ok(hand) => hand.kind().to_string().into(),
+            Err(error) => {
+                godot_error!("{}", error);
+                GodotString::new()
+            }
+        }
+    }
+
+    /// The kind of hand `cards` make, as one of the `HAND_*` constants
+    ///
+    /// Better kinds of hand score higher.  This is `-1` if `cards`
+    /// isn't a valid hand.
+    ///
+    /// Only the category counts, so a pair of Aces scores the same as a
+    /// pair of Twos.  Use `compare_hands` or `hand_strength` to tell
+    /// which of two hands wins.
+    #[func]
+    fn hand_score(cards: VariantArray) -> i32 {
+        match hand_from_variants(cards) {
+            Ok(hand) => hand_kind_score(&hand.kind()),
+            Err(error) => {
+                godot_error!("{}", error);
+                -1
+            }
+        }
+    }
+
+    /// How strong the hand `cards` make is, including the ranks that
+    /// break ties within its kind
+    ///
+    /// A stronger hand beats a weaker one and equal strengths tie, so
+    /// these can be stored or sorted in place of the hands.  This is
+    /// `-1` if `cards` isn't a valid hand.
+    #[func]
+    fn hand_strength(cards: VariantArray) -> i64 {
+        match hand_from_variants(cards) {
+            Ok(hand) => hand.kind().strength().into(),
+            Err(error) => {
+                godot_error!("{}", error);
+                -1
+            }
+        }
+    }
+
+    /// How often `hole_cards` beat `opponents` other players, as
+    /// `win`, `tie`, and `lose` percentages
+    ///
+    /// This deals out the rest of `board` and the opponents' cards
+    /// `iterations` times, drawing from `seed`.  It's empty if the cards
+    /// can't be dealt like that.
+    #[func]
+    fn hand_equity(
+        hole_cards: VariantArray,
+        board: VariantArray,
+        opponents: i64,
+        iterations: i64,
+        seed: i64,
+    ) -> Dictionary {
+        match equity_from_variants(hole_cards, board, opponents, iterations, seed) {
+            Ok(equity) => equity_to_dictionary(&equity),
+            Err(error) => {
+                godot_error!("{}", error);
+                Dictionary::new()
+            }
+        }
+    }
+}
+
+fn equity_from_variants(
+    hole_cards: VariantArray,
+    board: VariantArray,
+    opponents: i64,
+    iterations: i64,
+    seed: i64,
+) -> Result<poker::equity::Equity, String> {
+    EquityQuery::from_variants(hole_cards, board, opponents, iterations)?
+        .simulate(&mut GameRng::new(seed as u64))
+}
+
+/// The cards and players to estimate equity for, read from GDScript
+/// but not yet simulated, so they can be sent to another thread
+struct EquityQuery {
+    hole_cards: [poker::Card; 2],
+    board: Vec<poker::Card>,
+    opponents: usize,
+    iterations: u32,
+}
+
+impl EquityQuery {
+    fn from_variants(
+        hole_cards: VariantArray,
+        board: VariantArray,
+        opponents: i64,
+        iterations: i64,
+    ) -> Result<EquityQuery, String> {
+        let hole_cards: Vec<poker::Card> = hole_cards
+            .iter_shared()
+            .map(card_from_variant)
+            .collect::<Result<Vec<poker::Card>, String>>()?;
+        let hole_cards: [poker::Card; 2] =
+            hole_cards.try_into().map_err(|cards: Vec<poker::Card>| {
+                format!("hole cards must be 2 cards, not {}", cards.len())
+            })?;
+        let board: Vec<poker::Card> = board
+            .iter_shared()
+            .map(card_from_variant)
+            .collect::<Result<Vec<poker::Card>, String>>()?;
+        let opponents: usize = usize::try_from(opponents)
+            .map_err(|_| format!("{} is not a number of opponents", opponents))?;
+        let iterations: u32 = u32::try_from(iterations)
+            .map_err(|_| format!("{} is not a number of iterations", iterations))?;
+        Ok(EquityQuery {
+            hole_cards,
+            board,
+            opponents,
+            iterations,
+        })
+    }
+
+    fn simulate(&self, rng: &mut GameRng) -> Result<poker::equity::Equity, String> {
+        poker::equity::simulate(
+            &self.hole_cards,
+            &self.board,
+            self.opponents,
+            self.iterations,
+            rng,
+        )
+        .map_err(String::from)
+    }
+}
+
+/// `equity` as the dictionary `hand_equity` and `odds_updated` give
+fn equity_to_dictionary(equity: &poker::equity::Equity) -> Dictionary {
+    let mut dictionary: Dictionary = Dictionary::new();
+    dictionary.set("win", equity.win);
+    dictionary.set("tie", equity.tie);
+    dictionary.set("lose", equity.lose);
+    dictionary
+}
+
+fn hand_from_variants(cards: VariantArray) -> Result<poker::Hand, String> {
+    let cards: Vec<poker::Card> = cards
+        .iter_shared()
+        .map(card_from_variant)
+        .collect::<Result<Vec<poker::Card>, String>>()?;
+    if cards.len() != 5 {
+        return Err(format!("a hand must be 5 cards, not {}", cards.len()));
+    }
+    Ok(poker::Hand::new(cards))
+}
+
+fn card_from_variant(card: Variant) -> Result<poker::Card, String> {
+    match card.get_type() {
+        VariantType::String => card_from_str(&card.to::<GodotString>().to_string()),
+        VariantType::Int => {
+            let index: i64 = card.to::<i64>();
+            if !(0..52).contains(&index) {
+                return Err(format!("{} is not a card", index));
+            }
+            Ok(poker::Card::new(
+                poker::Rank::try_from(index as i32 % 13)?,
+                poker::Suit::try_from(index as i32 / 13)?,
+            ))
+        }
+        VariantType::Object => match card.try_to::<Gd<Card>>() {
+            Ok(card) => Ok(card.bind().card()?),
+            Err(_) => Err(format!("{} is not a card", card)),
+        },
+        _ => Err(format!("{} is not a card", card)),
+    }
+}
+
+fn card_from_str(card: &str) -> Result<poker::Card, String> {
+    card.parse::<poker::Card>()
+        .map_err(|error| format!("{}: {:?}", error, card))
+}
+
+fn hand_kind_score(kind: &poker::HandKind) -> i32 {
+    match kind {
+        poker::HandKind::HighCard(_) => PokerUtils::HAND_HIGH_CARD,
+        poker::HandKind::Pair { .. } => PokerUtils::HAND_PAIR,
+        poker::HandKind::TwoPair { .. } => PokerUtils::HAND_TWO_PAIR,
+        poker::HandKind::ThreeOfAKind { .. } => PokerUtils::HAND_THREE_OF_A_KIND,
+        poker::HandKind::Straight(_) => PokerUtils::HAND_STRAIGHT,
+        poker::HandKind::Flush(_) => PokerUtils::HAND_FLUSH,
+        poker::HandKind::FullHouse { .. } => PokerUtils::HAND_FULL_HOUSE,
+        poker::HandKind::FourOfAKind { .. } => PokerUtils::HAND_FOUR_OF_A_KIND,
+        poker::HandKind::StraightFlush(_) => PokerUtils::HAND_STRAIGHT_FLUSH,
+        poker::HandKind::RoyalFlush => PokerUtils::HAND_ROYAL_FLUSH,
+    }
+}
+
+/// A playing card that can travel through signals and scene properties
+///
+/// Cards compare by rank alone, as they do in [`poker::Card`].
+#[derive(GodotClass)]
+#[class(base=Resource)]
+pub struct Card {
+    /// The card's rank, from `0` for Two up to `12` for Ace
+    #[export]
+    rank: i32,
+    /// The card's suit, one of `0` for Diamond, `1` for Club, `2` for
+    /// Heart, or `3` for Spade
+    #[export]
+    suit: i32,
+
+    #[base]
+    base: Base<Resource>,
+}
+
+#[godot_api]
+impl ResourceVirtual for Card {
+    fn init(base: Base<Resource>) -> Self {
+        Card {
+            rank: 0,
+            suit: 0,
+            base,
+        }
+    }
+
+    /// The card written as rank then suit, like `"As"` or `"Td"`
+    fn to_string(&self) -> GodotString {
+        match self.card() {
+            Ok(card) => card.to_string().into(),
+            Err(error) => error.into(),
+        }
+    }
+}
+
+#[godot_api]
+impl Card {
+    /// Make a card from a string like `"As"`, or null if it isn't one
+    #[func]
+    fn from_string(card: GodotString) -> Option<Gd<Card>> {
+        match card_from_str(&card.to_string()) {
+            Ok(card) => Some(Card::from_card(&card)),
+            Err(error) => {
+                godot_error!("{}", error);
+                None
+            }
+        }
+    }
+
+    /// `1` if this card outranks `other`, `-1` if it's outranked, or `0`
+    #[func]
+    fn compare(&self, other: Gd<Card>) -> i32 {
+        match self.rank.cmp(&other.bind().rank) {
+            std::cmp::Ordering::Greater => 1,
+            std::cmp::Ordering::Less => -1,
+            std::cmp::Ordering::Equal => 0,
+        }
+    }
+
+    /// Whether this card and `other` are the same suit
+    #[func]
+    fn same_suit(&self, other: Gd<Card>) -> bool {
+        self.suit == other.bind().suit
+    }
+}
+
+impl Card {
+    /// Make a Godot-side copy of `card`
+    pub fn from_card(card: &poker::Card) -> Gd<Card> {
+        let mut gd_card: Gd<Card> = Gd::new_default();
+        {
+            let mut bound = gd_card.bind_mut();
+            bound.rank = card.rank().into();
+            bound.suit = card.suit().into();
+        }
+        gd_card
+    }
+
+    /// The Rust-side card this represents
+    ///
+    /// This will `Err` if either `rank` or `suit` is out of range.
+    pub fn card(&self) -> Result<poker::Card, &'static str> {
+        Ok(poker::Card::new(
+            poker::Rank::try_from(self.rank)?,
+            poker::Suit::try_from(self.suit)?,
+        ))
+    }
+}
+
+/// A Godot node that deals cards from a shuffled deck
+///
+/// Every card dealt is announced with `card_dealt`, so whatever shows
+/// cards being dealt follows the deck rather than keeping its own idea
+/// of what's been dealt where.
+#[derive(GodotClass)]
+#[class(base=Node)]
+pub struct Deck {
+    deck: poker::Deck,
+    rng: GameRng,
+
+    /// The seed shuffling starts from when the node is ready, so the
+    /// same seed deals the same cards
+    #[export]
+    seed: i64,
+    /// Deal from a short deck, Six to Ace, rather than all 52 cards
+    #[export]
+    short_deck: bool,
+
+    #[base]
+    base: Base<Node>,
+}
+
+#[godot_api]
+impl NodeVirtual for Deck {
+    fn init(base: Base<Node>) -> Self {
+        Deck {
+            deck: poker::Deck::new(),
+            rng: GameRng::new(0),
+            seed: 0,
+            short_deck: false,
+            base,
+        }
+    }
+
+    fn ready(&mut self) {
+        self.rng = GameRng::new(self.seed as u64);
+        self.shuffle();
+    }
+}
+
+#[godot_api]
+impl Deck {
+    /// Emitted for each card dealt, with the `position` it was dealt to
+    #[signal]
+    fn card_dealt(card: Gd<Card>, position: Vector2i);
+
+    /// Gather every card back into the deck and shuffle it
+    ///
+    /// Each shuffle carries on drawing from `seed`, so they're all
+    /// different, but the same from one game to the next.
+    #[func]
+    fn shuffle(&mut self) {
+        self.deck = match self.short_deck {
+            true => poker::Deck::short(),
+            false => poker::Deck::new(),
+        };
+        self.deck.shuffle(&mut self.rng);
+    }
+
+    /// Deal the top card to `position`, or null if the deck is empty
+    ///
+    /// `position` is whatever the game deals to, like the cell of a
+    /// board, or a seat and which of its cards.
+    #[func]
+    fn deal_to(&mut self, position: Vector2i) -> Option<Gd<Card>> {
+        let card: Gd<Card> = Card::from_card(&self.deck.draw()?);
+        self.base.emit_signal(
+            "card_dealt".into(),
+            &[card.to_variant(), position.to_variant()],
+        );
+        Some(card)
+    }
+
+    /// How many cards are left to deal
+    #[func]
+    fn remaining(&self) -> i64 {
+        self.deck.remaining() as i64
+    }
+}
+
+/// A Godot node that works out the odds of the hero's hand off of the
+/// main thread, for a HUD to show without holding up the frame
+///
+/// Each `update_odds` starts a simulation like `hand_equity`'s on a
+/// worker thread, and `odds_updated` is emitted from `process` once
+/// it's done.  Only the latest update is reported; any still running
+/// when another starts is forgotten.
+#[derive(GodotClass)]
+#[class(base=Node)]
+pub struct OddsHud {
+    rng: GameRng,
+    pending: Option<Receiver<Result<poker::equity::Equity, String>>>,
+
+    /// How many opponents the hero's hand is up against
+    #[export]
+    opponents: i32,
+    /// How many hands are dealt out for each update, where more is
+    /// slower but closer to the true odds
+    #[export]
+    iterations: i32,
+    /// The seed the simulations draw from when the node is ready
+    #[export]
+    seed: i64,
+
+    #[base]
+    base: Base<Node>,
+}
+
+#[godot_api]
+impl NodeVirtual for OddsHud {
+    fn init(base: Base<Node>) -> Self {
+        OddsHud {
+            rng: GameRng::new(0),
+            pending: None,
+            opponents: 1,
+            iterations: 10000,
+            seed: 0,
+            base,
+        }
+    }
+
+    fn ready(&mut self) {
+        self.rng = GameRng::new(self.seed as u64);
+    }
+
+    fn process(&mut self, _delta: f64) {
+        let Some(pending) = &self.pending else {
+            return;
+        };
+        let result: Result<poker::equity::Equity, String> = match pending.try_recv() {
+            Ok(result) => result,
+            Err(TryRecvError::Empty) => return,
+            Err(TryRecvError::Disconnected) => Err(String::from("the odds couldn't be worked out")),
+        };
+        self.pending = None;
+        match result {
+            Ok(equity) => {
+                let odds: Dictionary = equity_to_dictionary(&equity);
+                self.base
+                    .emit_signal("odds_updated".into(), &[odds.to_variant()]);
+            }
+            Err(error) => godot_error!("{}", error),
+        }
+    }
+}
+
+#[godot_api]
+impl OddsHud {
+    /// Emitted when an update's odds are ready, as `win`, `tie`, and
+    /// `lose` percentages
+    #[signal]
+    fn odds_updated(odds: Dictionary);
+
+    /// Start working out the odds of `hole_cards` with `board` showing,
+    /// given as for `PokerUtils`
+    #[func]
+    fn update_odds(&mut self, hole_cards: VariantArray, board: VariantArray) {
+        let query: EquityQuery = match EquityQuery::from_variants(
+            hole_cards,
+            board,
+            self.opponents.into(),
+            self.iterations.into(),
+        ) {
+            Ok(query) => query,
+            Err(error) => {
+                godot_error!("{}", error);
+                return;
+            }
+        };
+        let mut rng: GameRng = self.rng.split();
+        let (sender, receiver) = channel();
+        std::thread::spawn(move || {
+            // nobody's listening if a newer update replaced this one
+            let _ = sender.send(query.simulate(&mut rng));
+        });
+        self.pending = Some(receiver);
+    }
+
+    /// Whether an update is still being worked out
+    #[func]
+    fn is_updating(&self) -> bool {
+        self.pending.is_some()
+    }
 }
@@ -0,0 +1,419 @@
+//! Collections of levels played one after another
+//!
+//! A [`LevelPack`] is an ordered list of [`Level`]s, usually read from
+//! a file of many XSB boards, along with which of them have been
+//! completed.  It's up to the game to decide when a level counts as
+//! completed; the pack only keeps track of it and says what's next.
+
+use crate::sokoban::{solver, Sokoban, DIRECTIONS};
+
+/// One board of a [`LevelPack`] along with what's known about it
+#[derive(Debug, Clone, PartialEq)]
+pub struct Level {
+    /// What the level is called
+    pub name: String,
+    /// Any other `key: value` pairs given for the level, like its author
+    pub metadata: Vec<(String, String)>,
+    /// The board the level starts with
+    pub board: Sokoban,
+}
+
+/// How far through a [`LevelPack`] the player is
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum CompletionState {
+    /// None of the levels have been completed
+    NotStarted,
+    /// Some, but not all, of the levels have been completed
+    InProgress { completed: usize, total: usize },
+    /// Every level has been completed
+    Finished,
+}
+
+/// An ordered list of levels and which of them have been completed
+///
+/// # Examples
+///
+/// ```
+/// let pack: LevelPack = "\
+/// ; First
+/// #####
+/// #@$.#
+/// #####
+///
+/// ; Second
+/// ######
+/// #@$ .#
+/// ######"
+///     .parse()
+///     .unwrap();
+///
+/// assert_eq!(pack.next_level(), Some(0));
+/// let pack: LevelPack = pack.with_completed(0);
+/// assert_eq!(pack.next_level(), Some(1));
+/// assert_eq!(
+///     pack.completion_state(),
+///     CompletionState::InProgress { completed: 1, total: 2 }
+/// );
+/// ```
+#[derive(Debug, Clone, PartialEq)]
+pub struct LevelPack {
+    levels: Vec<Level>,
+    completed: Vec<bool>,
+}
+
+impl LevelPack {
+    /// Create a pack of `levels`, none of which have been completed
+    ///
+    /// # Panics
+    ///
+    /// Panics if there are no levels.
+    pub fn new(levels: Vec<Level>) -> LevelPack {
+        assert!(!levels.is_empty(), "a level pack needs at least one level");
+        LevelPack {
+            completed: vec![false; levels.len()],
+            levels,
+        }
+    }
+
+    /// All the levels in the pack, in order
+    pub fn levels(&self) -> &[Level] {
+        &self.levels
+    }
+
+    /// The level at `index`, if there is one
+    pub fn level(&self, index: usize) -> Option<&Level> {
+        self.levels.get(index)
+    }
+
+    /// Mark the level at `index` as completed
+    ///
+    /// Indices past the end of the pack are ignored.
+    pub fn with_completed(self, index: usize) -> LevelPack {
+        let mut completed: Vec<bool> = self.completed;
+        if let Some(level) = completed.get_mut(index) {
+            *level = true;
+        }
+        LevelPack { completed, ..self }
+    }
+
+    /// Checks if the level at `index` has been completed
+    pub fn is_completed(&self, index: usize) -> bool {
+        self.completed.get(index).copied().unwrap_or(false)
+    }
+
+    /// The index of the first level that's yet to be completed, if
+    /// there's any left
+    pub fn next_level(&self) -> Option<usize> {
+        self.completed.iter().position(|completed| !completed)
+    }
+
+    /// Put the levels in order from easiest to hardest, as scored by
+    /// [`difficulty`]
+    ///
+    /// Levels that couldn't be scored within `limits` go last, in the
+    /// order they were in.  Whether a level has been completed moves
+    /// along with it.
+    pub fn sorted_by_difficulty(self, limits: solver::Limits) -> LevelPack {
+        let mut levels: Vec<(Option<f64>, Level, bool)> = self
+            .levels
+            .into_iter()
+            .zip(self.completed)
+            .map(|(level, completed)| (difficulty(&level, limits), level, completed))
+            .collect();
+        levels.sort_by(|(a, ..), (b, ..)| match (a, b) {
+            (Some(a), Some(b)) => a.total_cmp(b),
+            (Some(_), None) => std::cmp::Ordering::Less,
+            (None, Some(_)) => std::cmp::Ordering::Greater,
+            (None, None) => std::cmp::Ordering::Equal,
+        });
+        let (levels, completed): (Vec<Level>, Vec<bool>) = levels
+            .into_iter()
+            .map(|(_, level, completed)| (level, completed))
+            .unzip();
+        LevelPack { levels, completed }
+    }
+
+    /// Works out how far through the pack the player is
+    pub fn completion_state(&self) -> CompletionState {
+        let completed: usize = self
+            .completed
+            .iter()
+            .filter(|completed| **completed)
+            .count();
+        if completed == 0 {
+            CompletionState::NotStarted
+        } else if completed == self.levels.len() {
+            CompletionState::Finished
+        } else {
+            CompletionState::InProgress {
+                completed,
+                total: self.levels.len(),
+            }
+        }
+    }
+}
+
+/// Estimates how hard `level` is, higher being harder
+///
+/// This adds up a few things that tend to make a level harder: how many
+/// pushes there are, a tenth of the moves in a shortest solution, how
+/// many ways you could move on average at each step of that solution,
+/// and ten times the share of those ways that would leave a push
+/// deadlocked.  It's rough, but it's good enough to put a pack in
+/// order.  If no solution is found within `limits`, this is `None`.
+///
+/// # Examples
+///
+/// ```
+/// let easy: Level = Level {
+///     name: "Easy".to_string(),
+///     metadata: vec![],
+///     board: Sokoban::from_xsb("#####\n#@$.#\n#####").unwrap(),
+/// };
+///
+/// // one push, one move, and only the one way to go
+/// assert_eq!(difficulty(&easy, solver::Limits::default()), Some(2.1));
+/// ```
+pub fn difficulty(level: &Level, limits: solver::Limits) -> Option<f64> {
+    let solver::SolverResult::Solved(moves) = solver::iterative_deepening(&level.board, limits)
+    else {
+        return None;
+    };
+
+    let mut board: Sokoban = level.board.clone();
+    let mut ways: usize = 0;
+    let mut traps: usize = 0;
+    for direction in &moves {
+        for way in DIRECTIONS.into_iter().filter(|way| board.can_move(*way)) {
+            ways += 1;
+            if board
+                .you_move(way)
+                .deadlocked_pushes()
+                .iter()
+                .next()
+                .is_some()
+            {
+                traps += 1;
+            }
+        }
+        board = board.you_move(*direction);
+    }
+
+    let pushes: f64 = level.board.pushes().iter().count() as f64;
+    let length: f64 = moves.len() as f64;
+    let branching: f64 = ways as f64 / length.max(1.0);
+    let deadlock_density: f64 = if ways == 0 {
+        0.0
+    } else {
+        traps as f64 / ways as f64
+    };
+    Some(pushes + length / 10.0 + branching + 10.0 * deadlock_density)
+}
+
+impl std::str::FromStr for LevelPack {
+    type Err = &'static str;
+
+    /// Reads a pack from a file of many boards in the XSB level format
+    ///
+    /// Each run of lines that only contain XSB board characters is a
+    /// board, as read by [`Sokoban::from_xsb`].  Any other lines before
+    /// a board describe it: `Title: ...` names the level, other
+    /// `key: value` lines are kept as metadata, and a `; ...` comment
+    /// names the level if there's no title.  A `Par: ...` line is also
+    /// given to the board as its [`Sokoban::par`].  Unnamed levels are named by
+    /// their place in the pack, starting from `Level 1`.  Anything after
+    /// the last board is ignored.
+    fn from_str(xsb: &str) -> Result<Self, Self::Err> {
+        let is_board_row = |line: &str| {
+            line.contains('#')
+                && line
+                    .chars()
+                    .all(|character| "#@+$*.-_ ".contains(character))
+        };
+
+        let mut levels: Vec<Level> = vec![];
+        let mut title: Option<String> = None;
+        let mut comment: Option<String> = None;
+        let mut metadata: Vec<(String, String)> = vec![];
+        let mut rows: Vec<&str> = vec![];
+        // a blank line after the rows ends the board as well as the
+        // first line that isn't a row
+        for line in xsb.lines().chain(std::iter::once("")) {
+            let line: &str = line.trim_end();
+            if is_board_row(line) {
+                rows.push(line);
+                continue;
+            }
+            if !rows.is_empty() {
+                let comment: Option<String> = comment.take();
+                let par: Option<u32> = metadata
+                    .iter()
+                    .find(|(key, _)| key.eq_ignore_ascii_case("par"))
+                    .map(|(_, value)| value.parse().map_err(|_| "Par isn't a number of moves"))
+                    .transpose()?;
+                levels.push(Level {
+                    name: title
+                        .take()
+                        .or(comment)
+                        .unwrap_or_else(|| format!("Level {}", levels.len() + 1)),
+                    metadata: std::mem::take(&mut metadata),
+                    board: Sokoban::from_xsb(&rows.join("\n"))?.with_par(par),
+                });
+                rows.clear();
+            }
+
+            if let Some(text) = line.strip_prefix(';') {
+                comment = Some(text.trim().to_string());
+            } else if let Some((key, value)) = line.split_once(':') {
+                let (key, value): (&str, &str) = (key.trim(), value.trim());
+                if key.eq_ignore_ascii_case("title") {
+                    title = Some(value.to_string());
+                } else {
+                    metadata.push((key.to_string(), value.to_string()));
+                }
+            }
+        }
+
+        if levels.is_empty() {
+            return Err("There are no levels");
+        }
+        Ok(LevelPack::new(levels))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::coordinate;
+
+    #[test]
+    fn packs_read_every_level_with_its_description() {
+        let pack: LevelPack = "\
+Title: First Steps
+Author: Someone
+
+; 1
+#####
+#@$.#
+#####
+
+Title: The Long Way
+Author: Someone Else
+Par: 3
+######
+#@$ .#
+######
+
+#####
+#+  #
+#$  #
+#####"
+            .parse()
+            .unwrap();
+
+        assert_eq!(pack.levels().len(), 3);
+        assert_eq!(pack.levels()[0].name, "First Steps");
+        assert_eq!(
+            pack.levels()[0].metadata,
+            vec![("Author".to_string(), "Someone".to_string())]
+        );
+        assert!(pack.levels()[0]
+            .board
+            .you_move(coordinate::Direction::Right)
+            .all_targets_triggered());
+        assert_eq!(pack.levels()[1].name, "The Long Way");
+        assert_eq!(
+            pack.levels()[1].metadata,
+            vec![
+                ("Author".to_string(), "Someone Else".to_string()),
+                ("Par".to_string(), "3".to_string())
+            ]
+        );
+        assert_eq!(pack.levels()[0].board.par(), None);
+        assert_eq!(pack.levels()[1].board.par(), Some(3));
+        assert_eq!(pack.levels()[2].name, "Level 3");
+        assert!(pack.levels()[2].metadata.is_empty());
+        assert_eq!(pack.level(3), None);
+    }
+
+    #[test]
+    fn packs_need_levels() {
+        assert_eq!(
+            "Title: Nothing Here\n; not even this".parse::<LevelPack>(),
+            Err("There are no levels")
+        );
+        assert_eq!(
+            "#####\n#$$.#\n#####".parse::<LevelPack>(),
+            Err("There's none of you")
+        );
+        assert_eq!(
+            "Par: lots\n#@$.#".parse::<LevelPack>(),
+            Err("Par isn't a number of moves")
+        );
+    }
+
+    #[test]
+    fn harder_levels_score_higher() {
+        let pack: LevelPack = "\
+; Hard
+#######
+#  .  #
+# $   #
+#.$ ###
+# @####
+#######
+
+; Impossible
+#####
+#$ .#
+#@  #
+#####
+
+; Easy
+#####
+#@$.#
+#####"
+            .parse()
+            .unwrap();
+        let limits: solver::Limits = solver::Limits::default();
+
+        let easy: f64 = difficulty(&pack.levels()[2], limits).unwrap();
+        let hard: f64 = difficulty(&pack.levels()[0], limits).unwrap();
+        assert!(easy < hard);
+        assert_eq!(difficulty(&pack.levels()[1], limits), None);
+
+        let pack: LevelPack = pack.with_completed(2).sorted_by_difficulty(limits);
+        let names: Vec<&str> = pack
+            .levels()
+            .iter()
+            .map(|level| level.name.as_str())
+            .collect();
+        assert_eq!(names, vec!["Easy", "Hard", "Impossible"]);
+        assert!(pack.is_completed(0));
+        assert!(!pack.is_completed(1));
+    }
+
+    #[test]
+    fn progress_goes_through_the_levels_in_order() {
+        let pack: LevelPack = "#@$.#\n\n#@$.#\n\n#@$.#".parse().unwrap();
+
+        assert_eq!(pack.completion_state(), CompletionState::NotStarted);
+        assert_eq!(pack.next_level(), Some(0));
+
+        let pack: LevelPack = pack.with_completed(1);
+        assert!(pack.is_completed(1));
+        assert!(!pack.is_completed(0));
+        assert_eq!(pack.next_level(), Some(0));
+        assert_eq!(
+            pack.completion_state(),
+            CompletionState::InProgress {
+                completed: 1,
+                total: 3
+            }
+        );
+
+        let pack: LevelPack = pack.with_completed(0).with_completed(2).with_completed(7);
+        assert_eq!(pack.next_level(), None);
+        assert_eq!(pack.completion_state(), CompletionState::Finished);
+    }
+}
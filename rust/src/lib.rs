@@ -1,9 +1,15 @@
 use godot::prelude::*;
 
+#[cfg(any(test, feature = "proptest"))]
+pub mod arbitrary;
 pub mod coordinate;
 pub mod io;
+pub mod level_pack;
 pub mod poker;
+pub mod rng;
 pub mod sokoban;
+pub mod tutorial;
+pub mod world;
 
 struct PushblockPoker;
 
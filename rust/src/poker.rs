@@ -4,8 +4,17 @@
 //! words, cards that go from Two to Ace and are suited Spade, Heart,
 //! Club, and Diamond.
 
+use crate::rng::GameRng;
+
+pub mod equity;
+#[cfg(feature = "lookup")]
+pub mod lookup;
+pub mod preflop;
+pub mod range;
+
 /// Face value of a playing card, with Ace high and Two low
 #[derive(Debug, Ord, PartialOrd, Eq, PartialEq, Hash, Clone, Copy)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub enum Rank {
     Two,
     Three,
@@ -24,6 +33,7 @@ pub enum Rank {
 
 /// The suits of conventional playing cards
 #[derive(Debug, PartialEq, Clone, Copy)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub enum Suit {
     Diamond,
     Club,
@@ -31,8 +41,192 @@ pub enum Suit {
     Spade,
 }
 
+impl Rank {
+    /// The rank's name, like `"Ace"`
+    fn name(&self) -> &'static str {
+        match self {
+            Rank::Two => "Two",
+            Rank::Three => "Three",
+            Rank::Four => "Four",
+            Rank::Five => "Five",
+            Rank::Six => "Six",
+            Rank::Seven => "Seven",
+            Rank::Eight => "Eight",
+            Rank::Nine => "Nine",
+            Rank::Ten => "Ten",
+            Rank::Jack => "Jack",
+            Rank::Queen => "Queen",
+            Rank::King => "King",
+            Rank::Ace => "Ace",
+        }
+    }
+
+    /// The name of more than one card of the rank, like `"Aces"`
+    fn plural(&self) -> &'static str {
+        match self {
+            Rank::Two => "Twos",
+            Rank::Three => "Threes",
+            Rank::Four => "Fours",
+            Rank::Five => "Fives",
+            Rank::Six => "Sixes",
+            Rank::Seven => "Sevens",
+            Rank::Eight => "Eights",
+            Rank::Nine => "Nines",
+            Rank::Ten => "Tens",
+            Rank::Jack => "Jacks",
+            Rank::Queen => "Queens",
+            Rank::King => "Kings",
+            Rank::Ace => "Aces",
+        }
+    }
+}
+
+impl TryFrom<i32> for Rank {
+    type Error = &'static str;
+
+    /// Converts the ordinal of a rank, from `0` for Two to `12` for Ace
+    ///
+    /// Anything outside of that range will `Err`.
+    fn try_from(ordinal: i32) -> Result<Self, Self::Error> {
+        match ordinal {
+            0 => Ok(Rank::Two),
+            1 => Ok(Rank::Three),
+            2 => Ok(Rank::Four),
+            3 => Ok(Rank::Five),
+            4 => Ok(Rank::Six),
+            5 => Ok(Rank::Seven),
+            6 => Ok(Rank::Eight),
+            7 => Ok(Rank::Nine),
+            8 => Ok(Rank::Ten),
+            9 => Ok(Rank::Jack),
+            10 => Ok(Rank::Queen),
+            11 => Ok(Rank::King),
+            12 => Ok(Rank::Ace),
+            _ => Err("Not a Rank"),
+        }
+    }
+}
+
+impl From<Rank> for i32 {
+    fn from(rank: Rank) -> Self {
+        rank as i32
+    }
+}
+
+impl TryFrom<i32> for Suit {
+    type Error = &'static str;
+
+    /// Converts the ordinal of a suit, in declaration order
+    ///
+    /// That is [`Suit::Diamond`] is `0`, [`Suit::Club`] is `1`,
+    /// [`Suit::Heart`] is `2`, and [`Suit::Spade`] is `3`.  Anything
+    /// else will `Err`.
+    fn try_from(ordinal: i32) -> Result<Self, Self::Error> {
+        match ordinal {
+            0 => Ok(Suit::Diamond),
+            1 => Ok(Suit::Club),
+            2 => Ok(Suit::Heart),
+            3 => Ok(Suit::Spade),
+            _ => Err("Not a Suit"),
+        }
+    }
+}
+
+impl From<Suit> for i32 {
+    fn from(suit: Suit) -> Self {
+        suit as i32
+    }
+}
+
+impl std::str::FromStr for Rank {
+    type Err = &'static str;
+
+    /// Reads a rank from its character, `2` to `9`, then `T`, `J`, `Q`,
+    /// `K`, and `A`
+    ///
+    /// Ten is `T` so that every rank is one character.  Anything else
+    /// will `Err`.
+    fn from_str(rank: &str) -> Result<Self, Self::Err> {
+        match rank {
+            "2" => Ok(Rank::Two),
+            "3" => Ok(Rank::Three),
+            "4" => Ok(Rank::Four),
+            "5" => Ok(Rank::Five),
+            "6" => Ok(Rank::Six),
+            "7" => Ok(Rank::Seven),
+            "8" => Ok(Rank::Eight),
+            "9" => Ok(Rank::Nine),
+            "T" => Ok(Rank::Ten),
+            "J" => Ok(Rank::Jack),
+            "Q" => Ok(Rank::Queen),
+            "K" => Ok(Rank::King),
+            "A" => Ok(Rank::Ace),
+            _ => Err("Not a Rank"),
+        }
+    }
+}
+
+/// Writes the rank as its character, the way [`Rank::from_str`] reads it
+impl std::fmt::Display for Rank {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let rank: &str = match self {
+            Rank::Two => "2",
+            Rank::Three => "3",
+            Rank::Four => "4",
+            Rank::Five => "5",
+            Rank::Six => "6",
+            Rank::Seven => "7",
+            Rank::Eight => "8",
+            Rank::Nine => "9",
+            Rank::Ten => "T",
+            Rank::Jack => "J",
+            Rank::Queen => "Q",
+            Rank::King => "K",
+            Rank::Ace => "A",
+        };
+        f.write_str(rank)
+    }
+}
+
+impl std::str::FromStr for Suit {
+    type Err = &'static str;
+
+    /// Reads a suit from its letter, `s`, `h`, `c`, or `d`, or its
+    /// symbol, `♠`, `♥`, `♣`, or `♦`
+    ///
+    /// Anything else will `Err`.
+    fn from_str(suit: &str) -> Result<Self, Self::Err> {
+        match suit {
+            "s" | "♠" => Ok(Suit::Spade),
+            "h" | "♥" => Ok(Suit::Heart),
+            "c" | "♣" => Ok(Suit::Club),
+            "d" | "♦" => Ok(Suit::Diamond),
+            _ => Err("Not a Suit"),
+        }
+    }
+}
+
+/// Writes the suit as its letter, like `s`, or with `{:#}` as its
+/// symbol, like `♠`
+impl std::fmt::Display for Suit {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let suit: &str = match (self, f.alternate()) {
+            (Suit::Spade, false) => "s",
+            (Suit::Heart, false) => "h",
+            (Suit::Club, false) => "c",
+            (Suit::Diamond, false) => "d",
+            (Suit::Spade, true) => "♠",
+            (Suit::Heart, true) => "♥",
+            (Suit::Club, true) => "♣",
+            (Suit::Diamond, true) => "♦",
+        };
+        f.write_str(suit)
+    }
+}
+
 /// A representation of a conventional playing card
 #[derive(Debug, Clone)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct Card {
     rank: Rank,
     suit: Suit,
@@ -55,6 +249,47 @@ impl Card {
     }
 }
 
+impl std::str::FromStr for Card {
+    type Err = &'static str;
+
+    /// Reads a card written as its rank then its suit, like `As`, `Td`,
+    /// or `A♠`
+    ///
+    /// See [`Rank::from_str`] and [`Suit::from_str`] for how each is
+    /// written.  Anything else will `Err`.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// let card: Card = "Qh".parse().unwrap();
+    ///
+    /// assert_eq!(card.rank(), Rank::Queen);
+    /// assert_eq!(card.suit(), Suit::Heart);
+    /// assert_eq!("Q♥".parse::<Card>().unwrap().suit(), Suit::Heart);
+    /// ```
+    fn from_str(card: &str) -> Result<Self, Self::Err> {
+        let mut chars = card.chars();
+        let rank: Rank = match chars.next() {
+            Some(rank) => rank.encode_utf8(&mut [0; 4]).parse()?,
+            None => return Err("Not a Card"),
+        };
+        let suit: Suit = chars.as_str().parse()?;
+        Ok(Card::new(rank, suit))
+    }
+}
+
+/// Writes the card as its rank then its suit, like `As`, or with `{:#}`
+/// like `A♠`
+impl std::fmt::Display for Card {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        if f.alternate() {
+            write!(f, "{}{:#}", self.rank, self.suit)
+        } else {
+            write!(f, "{}{}", self.rank, self.suit)
+        }
+    }
+}
+
 /// Compare based on rank
 impl PartialOrd for Card {
     fn partial_cmp(&self, other: &Self) -> Option<std::cmp::Ordering> {
@@ -73,8 +308,105 @@ impl PartialEq for Card {
     }
 }
 
+/// Whether `a` and `b` are the same card, suit and all, since [`Card`]s
+/// compare by rank alone
+fn is_same_card(a: &Card, b: &Card) -> bool {
+    a.rank == b.rank && a.suit == b.suit
+}
+
+/// A pile of cards to deal from, starting as all 52 of them
+///
+/// Shuffling draws from a [`GameRng`], so the same seed always deals
+/// the same cards, which is what daily challenges and replays need.
+///
+/// # Examples
+///
+/// ```
+/// let mut deck: Deck = Deck::new();
+/// deck.shuffle(&mut GameRng::new(2024));
+/// let mut replayed: Deck = Deck::new();
+/// replayed.shuffle(&mut GameRng::new(2024));
+///
+/// assert_eq!(deck.deal(5), replayed.deal(5));
+/// assert_eq!(deck.remaining(), 47);
+/// ```
+#[derive(Debug, Clone)]
+pub struct Deck {
+    cards: Vec<Card>,
+}
+
+impl Deck {
+    /// A full deck in order, Two to Ace of each suit in turn
+    ///
+    /// The cards are in the same order as their ordinals, with each
+    /// card's [`Rank`] ordinal plus thirteen times its [`Suit`]
+    /// ordinal, so it's dealt from the top, the Ace of Spades, down.
+    pub fn new() -> Deck {
+        Deck {
+            cards: (0..52)
+                .map(|index| {
+                    Card::new(
+                        Rank::try_from(index % 13).unwrap(),
+                        Suit::try_from(index / 13).unwrap(),
+                    )
+                })
+                .collect(),
+        }
+    }
+
+    /// Put the cards left in the deck in a random order drawn from `rng`
+    pub fn shuffle(&mut self, rng: &mut GameRng) {
+        rng.shuffle(&mut self.cards);
+    }
+
+    /// A short deck in order, Six to Ace of each suit in turn
+    ///
+    /// This is the deck for [`Rules::ShortDeck`], with every Two
+    /// through Five taken out of [`Deck::new`].
+    pub fn short() -> Deck {
+        Deck {
+            cards: Deck::new()
+                .cards
+                .into_iter()
+                .filter(|card| card.rank() >= Rank::Six)
+                .collect(),
+        }
+    }
+
+    /// Take the top card off the deck, if there are any left
+    pub fn draw(&mut self) -> Option<Card> {
+        self.cards.pop()
+    }
+
+    /// Take `count` cards off the top of the deck, or none at all if
+    /// there aren't that many left
+    pub fn deal(&mut self, count: usize) -> Option<Vec<Card>> {
+        if count > self.cards.len() {
+            return None;
+        }
+        Some((0..count).filter_map(|_| self.draw()).collect())
+    }
+
+    /// How many cards are left to deal
+    pub fn remaining(&self) -> usize {
+        self.cards.len()
+    }
+}
+
+impl Default for Deck {
+    fn default() -> Self {
+        Deck::new()
+    }
+}
+
 /// Every kind of poker hand category
-#[derive(Debug, PartialEq, PartialOrd)]
+///
+/// Kinds are ordered first by category, from [`HandKind::HighCard`] up
+/// to [`HandKind::RoyalFlush`], and then by the ranks they carry in the
+/// order they're declared, so the ranks that matter most are compared
+/// first.
+#[derive(Debug, PartialEq, Eq, PartialOrd, Ord)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub enum HandKind {
     /// A hand only evaluated on the ranks of its cards
     HighCard([Rank; 5]),
@@ -94,41 +426,276 @@ pub enum HandKind {
         /// The card not in the pair
         high_card: Rank,
     },
-    /// A hand with three `Rank`s
-    ThreeOfAKind(Rank),
+    /// A hand with three cards ranked `trips` and others `high_cards`
+    ThreeOfAKind {
+        /// The rank of the set of three
+        trips: Rank,
+        /// The ranks of the remaining cards
+        high_cards: [Rank; 2],
+    },
     /// A hand of cards of sequential rank with `Rank` the highest
     Straight(Rank),
     /// A hand of uniform suit with cards of the described ranks
     Flush([Rank; 5]),
-    /// A hand with a set of three `Rank`s and any pair
-    FullHouse(Rank),
-    /// A hand with four `Rank`s
-    FourOfAKind(Rank),
+    /// A hand with a set of three cards and a pair
+    FullHouse {
+        /// The rank of the set of three
+        trips: Rank,
+        /// The rank of the pair
+        pair: Rank,
+    },
+    /// A hand with four cards of the same rank
+    FourOfAKind {
+        /// The rank of the four
+        quads: Rank,
+        /// The rank of the card not in the four
+        kicker: Rank,
+    },
     /// A hand of all the same suit that's also a straight
     StraightFlush(Rank),
     /// A hand of all the same suit that's also an Ace-high straight
     RoyalFlush,
 }
 
+impl HandKind {
+    /// The name of the category of hand, like `"Full House"`
+    pub fn name(&self) -> &'static str {
+        match self {
+            HandKind::HighCard(_) => "High Card",
+            HandKind::Pair { .. } => "Pair",
+            HandKind::TwoPair { .. } => "Two Pair",
+            HandKind::ThreeOfAKind { .. } => "Three of a Kind",
+            HandKind::Straight(_) => "Straight",
+            HandKind::Flush(_) => "Flush",
+            HandKind::FullHouse { .. } => "Full House",
+            HandKind::FourOfAKind { .. } => "Four of a Kind",
+            HandKind::StraightFlush(_) => "Straight Flush",
+            HandKind::RoyalFlush => "Royal Flush",
+        }
+    }
+
+    /// A number that's higher for better hands, and the same only for
+    /// hands that tie
+    ///
+    /// The category of hand is in the bits above the lowest twenty,
+    /// and each rank the kind carries takes four bits below that, most
+    /// important first, so comparing strengths is the same as
+    /// comparing kinds.  This is for when a kind needs to be stored,
+    /// sorted, or sent somewhere cheaply.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// let aces_full: HandKind = HandKind::FullHouse { trips: Rank::Ace, pair: Rank::Two };
+    /// let kings_full: HandKind = HandKind::FullHouse { trips: Rank::King, pair: Rank::Ace };
+    ///
+    /// assert_eq!(aces_full.strength(), 0x6c0000);
+    /// assert!(aces_full.strength() > kings_full.strength());
+    /// ```
+    pub fn strength(&self) -> u32 {
+        let ranks: Vec<Rank> = match self {
+            HandKind::HighCard(ranks) | HandKind::Flush(ranks) => ranks.to_vec(),
+            HandKind::Pair { pair, high_cards } => {
+                std::iter::once(*pair).chain(*high_cards).collect()
+            }
+            HandKind::TwoPair {
+                pair_high,
+                pair_low,
+                high_card,
+            } => vec![*pair_high, *pair_low, *high_card],
+            HandKind::ThreeOfAKind { trips, high_cards } => {
+                std::iter::once(*trips).chain(*high_cards).collect()
+            }
+            HandKind::Straight(rank) | HandKind::StraightFlush(rank) => vec![*rank],
+            HandKind::FullHouse { trips, pair } => vec![*trips, *pair],
+            HandKind::FourOfAKind { quads, kicker } => vec![*quads, *kicker],
+            HandKind::RoyalFlush => vec![],
+        };
+        let category: u32 = Rules::Standard.category(self) as u32;
+        ranks
+            .iter()
+            .enumerate()
+            .fold(category << 20, |strength, (i, rank)| {
+                strength | (i32::from(*rank) as u32) << (16 - 4 * i)
+            })
+    }
+}
+
+/// Writes the category of hand and the ranks that make it, like
+/// `Full House, Kings over Twos` or `Straight, Nine high`
+impl std::fmt::Display for HandKind {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let name: &str = self.name();
+        match self {
+            HandKind::HighCard(ranks) | HandKind::Flush(ranks) => {
+                write!(f, "{}, {} high", name, ranks[0].name())
+            }
+            HandKind::Pair { pair, .. } => write!(f, "{}, {}", name, pair.plural()),
+            HandKind::TwoPair {
+                pair_high,
+                pair_low,
+                ..
+            } => write!(
+                f,
+                "{}, {} and {}",
+                name,
+                pair_high.plural(),
+                pair_low.plural()
+            ),
+            HandKind::ThreeOfAKind { trips: rank, .. }
+            | HandKind::FourOfAKind { quads: rank, .. } => {
+                write!(f, "{}, {}", name, rank.plural())
+            }
+            HandKind::Straight(rank) | HandKind::StraightFlush(rank) => {
+                write!(f, "{}, {} high", name, rank.name())
+            }
+            HandKind::FullHouse { trips, pair } => {
+                write!(f, "{}, {} over {}", name, trips.plural(), pair.plural())
+            }
+            HandKind::RoyalFlush => f.write_str(name),
+        }
+    }
+}
+
+/// Every kind of hand in three-card poker
+///
+/// With only three cards, a straight is harder to make than a flush,
+/// and three of a kind harder still, so they're ordered differently
+/// than a [`HandKind`].  Kinds are ordered first by category, from
+/// [`ThreeCardKind::HighCard`] up to [`ThreeCardKind::StraightFlush`],
+/// and then by their ranks.
+#[derive(Debug, PartialEq, Eq, PartialOrd, Ord)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub enum ThreeCardKind {
+    /// A hand only evaluated on the ranks of its cards
+    HighCard([Rank; 3]),
+    /// A hand with two cards ranked `pair`
+    Pair {
+        /// The rank of the pair
+        pair: Rank,
+        /// The card not in the pair
+        high_card: Rank,
+    },
+    /// A hand of uniform suit with cards of the described ranks
+    Flush([Rank; 3]),
+    /// A hand of cards of sequential rank with `Rank` the highest
+    ///
+    /// An Ace can play low, making A-2-3 the lowest straight.
+    Straight(Rank),
+    /// A hand with three `Rank`s
+    ThreeOfAKind(Rank),
+    /// A hand of all the same suit that's also a straight
+    StraightFlush(Rank),
+}
+
+/// Which variant of poker's rules hands are valued by
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum Rules {
+    /// The usual rules, played with all 52 cards
+    #[default]
+    Standard,
+    /// Short-deck, or six-plus, played with [`Deck::short`]
+    ///
+    /// The Ace can still play low in a straight, which is then
+    /// A-6-7-8-9, and since flushes are harder to make with fewer
+    /// cards, a flush beats a full house.
+    ShortDeck,
+}
+
+impl Rules {
+    /// The lowest rank in the deck these rules are played with, which
+    /// an Ace plays just below in a straight
+    fn lowest_rank(&self) -> Rank {
+        match self {
+            Rules::Standard => Rank::Two,
+            Rules::ShortDeck => Rank::Six,
+        }
+    }
+
+    /// How good `kind`'s category of hand is, higher being better
+    fn category(&self, kind: &HandKind) -> u8 {
+        match (kind, self) {
+            (HandKind::HighCard(_), _) => 0,
+            (HandKind::Pair { .. }, _) => 1,
+            (HandKind::TwoPair { .. }, _) => 2,
+            (HandKind::ThreeOfAKind { .. }, _) => 3,
+            (HandKind::Straight(_), _) => 4,
+            (HandKind::Flush(_), Rules::Standard) => 5,
+            (HandKind::FullHouse { .. }, Rules::Standard) => 6,
+            (HandKind::FullHouse { .. }, Rules::ShortDeck) => 5,
+            (HandKind::Flush(_), Rules::ShortDeck) => 6,
+            (HandKind::FourOfAKind { .. }, _) => 7,
+            (HandKind::StraightFlush(_), _) => 8,
+            (HandKind::RoyalFlush, _) => 9,
+        }
+    }
+}
+
+/// The best high and low hands in a set of cards, for split-pot games
+/// like Omaha Hi-Lo
+#[derive(Debug, PartialEq)]
+pub struct HiLo {
+    /// The best hand going by the usual rules
+    pub high: HandKind,
+    /// The best ace-to-five low, as [`Hand::ace_to_five_low`] gives it,
+    /// if there's one that qualifies as eight or better
+    pub low: Option<[Rank; 5]>,
+}
+
 /// A construct for evaluating and comparing sets of cards
-#[derive(Debug)]
+///
+/// With the `serde` feature, a hand is stored as its list of cards, and
+/// reading back fewer than five cards is an error rather than a panic.
+/// Hands are read back under [`Rules::Standard`].
+#[derive(Debug, Clone)]
+#[cfg_attr(
+    feature = "serde",
+    derive(serde::Serialize, serde::Deserialize),
+    serde(try_from = "Vec<Card>", into = "Vec<Card>")
+)]
 pub struct Hand {
     cards: Vec<Card>,
+    rules: Rules,
 }
 
 impl Hand {
     /// This creates a hand from a set of five or more cards
     ///
-    /// This will panic if fewer than five cards are passed in
+    /// With more than five cards, the hand is as good as the best five
+    /// of them.  This will panic if fewer than five cards are passed in
     pub fn new(cards: Vec<Card>) -> Hand {
         assert!(cards.len() >= 5, "there must be 5 or more cards in a hand");
         let mut sorted_cards: Vec<Card> = cards;
-        sorted_cards.sort_by(|card0, card1| card1.rank().partial_cmp(&card0.rank()).unwrap());
+        sorted_cards.sort_by_key(|card| std::cmp::Reverse(card.rank()));
         Hand {
             cards: sorted_cards,
+            rules: Rules::default(),
         }
     }
 
+    /// Value the hand by `rules` rather than the standard ones
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// let cards: Vec<Card> = "As 6h 7d 8c 9s"
+    ///     .split(' ')
+    ///     .map(|card| card.parse().unwrap())
+    ///     .collect();
+    /// let short: Hand = Hand::new(cards.clone()).with_rules(Rules::ShortDeck);
+    ///
+    /// assert_eq!(Hand::new(cards).kind().name(), "High Card");
+    /// assert_eq!(short.kind(), HandKind::Straight(Rank::Nine));
+    /// ```
+    pub fn with_rules(self, rules: Rules) -> Hand {
+        Hand { rules, ..self }
+    }
+
+    /// The rules the hand is valued by
+    pub fn rules(&self) -> Rules {
+        self.rules
+    }
+
     /// This compute the category of hand that represents the cards
     ///
     /// It will find the kind of hand that most favorably describes
@@ -136,6 +703,9 @@ impl Hand {
     /// would be described as a full house instead of as a pair or
     /// three of a kind since that's the highest ranked option.
     pub fn kind(&self) -> HandKind {
+        if self.cards.len() > 5 {
+            return self.fives().iter().map(Hand::value).max().unwrap().1;
+        }
         if self.is_flush() {
             match self.straight_high_card() {
                 Some(Rank::Ace) => return HandKind::RoyalFlush,
@@ -170,11 +740,259 @@ impl Hand {
         );
     }
 
+    /// The best kind of hand that any five of `cards` make
+    ///
+    /// This is how hands are valued in games like Texas Hold'em, where
+    /// the best five of seven cards count.  Like [`Hand::new`], this
+    /// will panic if fewer than five cards are passed in.
+    pub fn best_of(cards: &[Card]) -> HandKind {
+        Hand::new(cards.to_vec())
+            .fives()
+            .iter()
+            .map(Hand::kind)
+            .max()
+            .unwrap()
+    }
+
+    /// The kind of hand this is when playing deuce-to-seven lowball
+    ///
+    /// That's the same as [`Hand::kind`], except that an Ace is only
+    /// ever high, so A-2-3-4-5 isn't a straight.  With more than five
+    /// cards, it's the kind of the lowest five of them.
+    pub fn deuce_to_seven_kind(&self) -> HandKind {
+        if self.cards.len() > 5 {
+            return self
+                .fives()
+                .iter()
+                .map(Hand::deuce_to_seven_kind)
+                .min()
+                .unwrap();
+        }
+        let ranks = || -> [Rank; 5] {
+            self.cards
+                .iter()
+                .map(|card| card.rank())
+                .collect::<Vec<Rank>>()
+                .try_into()
+                .unwrap()
+        };
+        match self.kind() {
+            HandKind::Straight(Rank::Five) => HandKind::HighCard(ranks()),
+            HandKind::StraightFlush(Rank::Five) => HandKind::Flush(ranks()),
+            kind => kind,
+        }
+    }
+
+    /// Compare hands as in deuce-to-seven lowball, where the lowest
+    /// hand wins
+    ///
+    /// This is `Greater` when this hand beats `other`, so it's the
+    /// lower of the two by [`Hand::deuce_to_seven_kind`].  Straights
+    /// and flushes count against a hand, and the best hand there is is
+    /// 7-5-4-3-2 of mixed suits.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// let hand = |cards: &str| -> Hand {
+    ///     Hand::new(cards.split(' ').map(|card| card.parse().unwrap()).collect())
+    /// };
+    /// let seven_five: Hand = hand("7s 5h 4d 3c 2s");
+    /// let wheel: Hand = hand("As 5h 4d 3c 2s");
+    ///
+    /// assert_eq!(seven_five.deuce_to_seven_cmp(&wheel), std::cmp::Ordering::Greater);
+    /// ```
+    pub fn deuce_to_seven_cmp(&self, other: &Hand) -> std::cmp::Ordering {
+        other.deuce_to_seven_kind().cmp(&self.deuce_to_seven_kind())
+    }
+
+    /// The ranks of the lowest five cards when playing ace-to-five
+    /// lowball, in the order they're compared in
+    ///
+    /// Straights and flushes don't count for or against a hand, and an
+    /// Ace is the lowest card of all.  Ranks that are paired up or more
+    /// come first, as they count against the hand the most, then the
+    /// rest go from highest to lowest, with any Ace last.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// let cards: Vec<Card> = "Ks 5h 4d 3c 2s Ad 5c"
+    ///     .split(' ')
+    ///     .map(|card| card.parse().unwrap())
+    ///     .collect();
+    ///
+    /// assert_eq!(
+    ///     Hand::new(cards).ace_to_five_low(),
+    ///     [Rank::Five, Rank::Four, Rank::Three, Rank::Two, Rank::Ace]
+    /// );
+    /// ```
+    pub fn ace_to_five_low(&self) -> [Rank; 5] {
+        let (counts, values): (Vec<usize>, Vec<u8>) = self.ace_to_five_value();
+        counts
+            .iter()
+            .zip(values)
+            .flat_map(|(&count, value)| {
+                let rank: Rank = match value {
+                    0 => Rank::Ace,
+                    value => Rank::try_from(value as i32 - 1).unwrap(),
+                };
+                vec![rank; count]
+            })
+            .collect::<Vec<Rank>>()
+            .try_into()
+            .unwrap()
+    }
+
+    /// Compare hands as in ace-to-five lowball, or razz, where the
+    /// lowest hand wins
+    ///
+    /// This is `Greater` when this hand beats `other`, so it's the
+    /// lower of the two as [`Hand::ace_to_five_low`] has it.  The best
+    /// hand there is is 5-4-3-2-A, of any suits.
+    pub fn ace_to_five_cmp(&self, other: &Hand) -> std::cmp::Ordering {
+        other.ace_to_five_value().cmp(&self.ace_to_five_value())
+    }
+
+    /// How low the lowest five cards are in ace-to-five lowball, with
+    /// lower values being better hands
+    ///
+    /// This is how many there are of each rank, most first, and their
+    /// ranks from highest to lowest with an Ace as `0`, a Two as `1`,
+    /// and so on.  That way, fewer pairs and the like always come out
+    /// lower, and then lower ranks do.
+    fn ace_to_five_value(&self) -> (Vec<usize>, Vec<u8>) {
+        if self.cards.len() > 5 {
+            return self
+                .fives()
+                .iter()
+                .map(Hand::ace_to_five_value)
+                .min()
+                .unwrap();
+        }
+        let mut sets: Vec<(usize, u8)> = vec![];
+        for card in self.cards.iter() {
+            let value: u8 = match card.rank() {
+                Rank::Ace => 0,
+                rank => rank as u8 + 1,
+            };
+            match sets.iter_mut().find(|(_, set)| *set == value) {
+                Some((count, _)) => *count += 1,
+                None => sets.push((1, value)),
+            }
+        }
+        sets.sort_by(|set0, set1| set1.cmp(set0));
+        sets.into_iter().unzip()
+    }
+
+    /// The best high hand and the best qualifying low hand that any
+    /// five of `cards` make
+    ///
+    /// A low hand only qualifies if it's five different ranks of eight
+    /// or lower, counting an Ace as low.  Any five cards can make up
+    /// each, so games that say which cards must be used, like taking
+    /// two from your hand in Omaha, should pass only the cards that
+    /// follow those rules.  Like [`Hand::new`], this will panic if
+    /// fewer than five cards are passed in.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// let cards: Vec<Card> = "As 2d 5c 7h 8s Ks Kd"
+    ///     .split(' ')
+    ///     .map(|card| card.parse().unwrap())
+    ///     .collect();
+    /// let hi_lo: HiLo = Hand::hi_lo(&cards);
+    ///
+    /// assert_eq!(hi_lo.high.name(), "Pair");
+    /// assert_eq!(hi_lo.low, Some([Rank::Eight, Rank::Seven, Rank::Five, Rank::Two, Rank::Ace]));
+    /// ```
+    pub fn hi_lo(cards: &[Card]) -> HiLo {
+        let hand: Hand = Hand::new(cards.to_vec());
+        let low: [Rank; 5] = hand.ace_to_five_low();
+        let distinct: bool = low.windows(2).all(|pair| pair[0] != pair[1]);
+        let eight_or_better: bool = low[0] <= Rank::Eight || low[0] == Rank::Ace;
+        HiLo {
+            high: hand.kind(),
+            low: (distinct && eight_or_better).then_some(low),
+        }
+    }
+
+    /// The kind of hand `cards` make in three-card poker
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// let cards: [Card; 3] = ["Qs", "Kh", "Ad"].map(|card| card.parse().unwrap());
+    ///
+    /// assert_eq!(Hand::three_card_kind(&cards), ThreeCardKind::Straight(Rank::Ace));
+    /// ```
+    pub fn three_card_kind(cards: &[Card; 3]) -> ThreeCardKind {
+        let mut ranks: [Rank; 3] = [cards[0].rank(), cards[1].rank(), cards[2].rank()];
+        ranks.sort_by(|rank0, rank1| rank1.cmp(rank0));
+        let is_flush: bool = cards.iter().all(|card| card.suit() == cards[0].suit());
+        let follows = |higher: Rank, lower: Rank| i32::from(higher) == i32::from(lower) + 1;
+        let straight_high_card: Option<Rank> = match ranks {
+            [Rank::Ace, Rank::Three, Rank::Two] => Some(Rank::Three),
+            [high, middle, low] if follows(high, middle) && follows(middle, low) => Some(high),
+            _ => None,
+        };
+
+        match (straight_high_card, is_flush) {
+            (Some(rank), true) => ThreeCardKind::StraightFlush(rank),
+            _ if ranks[0] == ranks[2] => ThreeCardKind::ThreeOfAKind(ranks[0]),
+            (Some(rank), false) => ThreeCardKind::Straight(rank),
+            (None, true) => ThreeCardKind::Flush(ranks),
+            _ if ranks[0] == ranks[1] => ThreeCardKind::Pair {
+                pair: ranks[0],
+                high_card: ranks[2],
+            },
+            _ if ranks[1] == ranks[2] => ThreeCardKind::Pair {
+                pair: ranks[1],
+                high_card: ranks[0],
+            },
+            _ => ThreeCardKind::HighCard(ranks),
+        }
+    }
+
+    /// What hands are compared by: how good the category of hand is
+    /// under the hand's rules, then the kind of hand itself
+    fn value(&self) -> (u8, HandKind) {
+        let kind: HandKind = self.kind();
+        (self.rules.category(&kind), kind)
+    }
+
+    /// Every hand of five of this hand's cards
+    fn fives(&self) -> Vec<Hand> {
+        let count: usize = self.cards.len();
+
+        // step through the indices of every five cards in order, the
+        // way an odometer would if no digit could repeat
+        let mut indices: [usize; 5] = [0, 1, 2, 3, 4];
+        let mut fives: Vec<Hand> = vec![];
+        loop {
+            fives.push(Hand {
+                cards: indices.iter().map(|&i| self.cards[i].clone()).collect(),
+                rules: self.rules,
+            });
+
+            let Some(i) = (0..5).rev().find(|&i| indices[i] < count - 5 + i) else {
+                break;
+            };
+            indices[i] += 1;
+            for j in i + 1..5 {
+                indices[j] = indices[j - 1] + 1;
+            }
+        }
+        fives
+    }
+
     fn straight_high_card(&self) -> Option<Rank> {
         // handle the Ace-low case
+        let lowest_rank: Rank = self.rules.lowest_rank();
         let mut straight_sorted_cards: Vec<Card> = self.cards.clone();
         if straight_sorted_cards[0].rank() == Rank::Ace
-            && straight_sorted_cards[straight_sorted_cards.len() - 1].rank() == Rank::Two
+            && straight_sorted_cards[straight_sorted_cards.len() - 1].rank() == lowest_rank
         {
             straight_sorted_cards.rotate_left(1);
         }
@@ -183,6 +1001,7 @@ impl Hand {
         for card in straight_sorted_cards.iter().skip(1) {
             if card.rank()
                 != match previous_card.rank() {
+                    rank if rank == lowest_rank => Rank::Ace,
                     Rank::Ace => Rank::King,
                     Rank::Two => Rank::Ace,
                     Rank::Three => Rank::Two,
@@ -220,14 +1039,14 @@ impl Hand {
                     acc
                 });
 
+        let mut four_of_a_kind: Option<Rank> = None;
         let mut three_of_a_kind: Option<Rank> = None;
         let mut pairs: Vec<Rank> = vec![];
         let mut high_cards: Vec<Rank> = vec![];
         for (&rank, &count) in sets.iter() {
             if count == 4 {
-                return Some(HandKind::FourOfAKind(rank));
-            }
-            if count == 3 {
+                four_of_a_kind = Some(rank);
+            } else if count == 3 {
                 three_of_a_kind = Some(rank);
             } else if count == 2 {
                 pairs.push(rank);
@@ -236,11 +1055,28 @@ impl Hand {
             }
         }
 
+        if let Some(four_of_a_kind) = four_of_a_kind {
+            debug_assert_eq!(high_cards.len(), 1);
+
+            return Some(HandKind::FourOfAKind {
+                quads: four_of_a_kind,
+                kicker: high_cards[0],
+            });
+        }
         if let Some(three_of_a_kind) = three_of_a_kind {
             if pairs.is_empty() {
-                return Some(HandKind::ThreeOfAKind(three_of_a_kind));
+                debug_assert_eq!(high_cards.len(), 2);
+
+                high_cards.sort_by(|card0, card1| card1.cmp(card0));
+                return Some(HandKind::ThreeOfAKind {
+                    trips: three_of_a_kind,
+                    high_cards: high_cards.try_into().unwrap(),
+                });
             }
-            return Some(HandKind::FullHouse(three_of_a_kind));
+            return Some(HandKind::FullHouse {
+                trips: three_of_a_kind,
+                pair: pairs[0],
+            });
         }
         if pairs.len() == 2 {
             debug_assert_eq!(high_cards.len(), 1);
@@ -254,7 +1090,7 @@ impl Hand {
         if pairs.len() == 1 {
             debug_assert_eq!(high_cards.len(), 3);
 
-            high_cards.sort_by(|card0, card1| card1.partial_cmp(card0).unwrap());
+            high_cards.sort_by(|card0, card1| card1.cmp(card0));
             return Some(HandKind::Pair {
                 pair: pairs[0],
                 high_cards: high_cards.try_into().unwrap(),
@@ -265,15 +1101,62 @@ impl Hand {
     }
 }
 
+impl TryFrom<Vec<Card>> for Hand {
+    type Error = &'static str;
+
+    /// Makes a hand like [`Hand::new`], but it will `Err` rather than
+    /// panic if there are fewer than five cards
+    fn try_from(cards: Vec<Card>) -> Result<Self, Self::Error> {
+        if cards.len() < 5 {
+            return Err("Not enough cards for a Hand");
+        }
+        Ok(Hand::new(cards))
+    }
+}
+
+impl From<Hand> for Vec<Card> {
+    fn from(hand: Hand) -> Self {
+        hand.cards
+    }
+}
+
+/// Writes the cards from highest to lowest rank, like `As Kd 7c 7h 2s`,
+/// or with `{:#}` like `A♠ K♦ 7♣ 7♥ 2♠`
+impl std::fmt::Display for Hand {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        for (i, card) in self.cards.iter().enumerate() {
+            if i > 0 {
+                f.write_str(" ")?;
+            }
+            if f.alternate() {
+                write!(f, "{:#}", card)?;
+            } else {
+                write!(f, "{}", card)?;
+            }
+        }
+        Ok(())
+    }
+}
+
 impl PartialEq for Hand {
     fn eq(&self, other: &Self) -> bool {
-        self.kind() == other.kind()
+        self.value() == other.value()
     }
 }
 
+impl Eq for Hand {}
+
 impl PartialOrd for Hand {
     fn partial_cmp(&self, other: &Self) -> Option<std::cmp::Ordering> {
-        self.kind().partial_cmp(&other.kind())
+        Some(self.cmp(other))
+    }
+}
+
+/// Compare based on the kind of hand under each hand's rules, so hands
+/// that tie are equal
+impl Ord for Hand {
+    fn cmp(&self, other: &Self) -> std::cmp::Ordering {
+        self.value().cmp(&other.value())
     }
 }
 
@@ -282,35 +1165,7 @@ mod tests {
     use super::*;
 
     fn card_from_str(card: &str) -> Card {
-        assert_eq!(card.len(), 2);
-
-        let rank: Rank = match card.chars().nth(0) {
-            Some('2') => Rank::Two,
-            Some('3') => Rank::Three,
-            Some('4') => Rank::Four,
-            Some('5') => Rank::Five,
-            Some('6') => Rank::Six,
-            Some('7') => Rank::Seven,
-            Some('8') => Rank::Eight,
-            Some('9') => Rank::Nine,
-            // T for 10 to make it so only one character needs to be matched
-            Some('T') => Rank::Ten,
-            Some('J') => Rank::Jack,
-            Some('Q') => Rank::Queen,
-            Some('K') => Rank::King,
-            Some('A') => Rank::Ace,
-            _ => panic!("invalid card rank"),
-        };
-
-        let suit: Suit = match card.chars().nth(1) {
-            Some('s') => Suit::Spade,
-            Some('h') => Suit::Heart,
-            Some('c') => Suit::Club,
-            Some('d') => Suit::Diamond,
-            _ => panic!("invalid card suit"),
-        };
-
-        Card::new(rank, suit)
+        card.parse().unwrap()
     }
 
     fn cards_from_str(cards: &str) -> Vec<Card> {
@@ -366,6 +1221,37 @@ mod tests {
             assert_eq!(card_from_str("Jd").rank(), Rank::Jack);
         }
 
+        #[test]
+        fn rank_and_suit_round_trip_through_their_ordinals() {
+            for ordinal in 0..13 {
+                assert_eq!(i32::from(Rank::try_from(ordinal).unwrap()), ordinal);
+            }
+            for ordinal in 0..4 {
+                assert_eq!(i32::from(Suit::try_from(ordinal).unwrap()), ordinal);
+            }
+            assert!(Rank::try_from(13).is_err());
+            assert!(Suit::try_from(-1).is_err());
+        }
+
+        #[test]
+        fn cards_read_back_what_they_write() {
+            for card in Deck::new().deal(52).unwrap() {
+                let written: String = card.to_string();
+                let read: Card = written.parse().unwrap();
+                assert_eq!((read.rank(), read.suit()), (card.rank(), card.suit()));
+                let read: Card = format!("{:#}", card).parse().unwrap();
+                assert_eq!((read.rank(), read.suit()), (card.rank(), card.suit()));
+            }
+            assert_eq!(card_from_str("Td").to_string(), "Td");
+            assert_eq!(format!("{:#}", card_from_str("As")), "A♠");
+
+            assert_eq!("".parse::<Card>(), Err("Not a Card"));
+            assert_eq!("1s".parse::<Card>(), Err("Not a Rank"));
+            assert_eq!("Ax".parse::<Card>(), Err("Not a Suit"));
+            assert_eq!("Ass".parse::<Card>(), Err("Not a Suit"));
+            assert_eq!("10s".parse::<Card>(), Err("Not a Rank"));
+        }
+
         #[test]
         fn suit_getter() {
             assert_eq!(card_from_str("As").suit(), Suit::Spade);
@@ -387,13 +1273,76 @@ mod tests {
             ("Tc 9c 8c 7c 6c", HandKind::StraightFlush(Rank::Ten)),
             ("9s 5s 6s 7s 8s", HandKind::StraightFlush(Rank::Nine)),
             ("Ad 2d 3d 4d 5d", HandKind::StraightFlush(Rank::Five)),
-            ("9d As Ah Ac Ad", HandKind::FourOfAKind(Rank::Ace)),
-            ("8s 8h Kc 8c 8d", HandKind::FourOfAKind(Rank::Eight)),
-            ("5s 5h 5c 5d 2d", HandKind::FourOfAKind(Rank::Five)),
-            ("2s 2h 2c 2d 8h", HandKind::FourOfAKind(Rank::Two)),
-            ("Ac Kc Kd Kh Ad", HandKind::FullHouse(Rank::King)),
-            ("7h 7d 7c 6s 6c", HandKind::FullHouse(Rank::Seven)),
-            ("6h 7h 6d 7d 6c", HandKind::FullHouse(Rank::Six)),
+            (
+                "As Ah Ac Ad Kd",
+                HandKind::FourOfAKind {
+                    quads: Rank::Ace,
+                    kicker: Rank::King,
+                },
+            ),
+            (
+                "9d As Ah Ac Ad",
+                HandKind::FourOfAKind {
+                    quads: Rank::Ace,
+                    kicker: Rank::Nine,
+                },
+            ),
+            (
+                "8s 8h Kc 8c 8d",
+                HandKind::FourOfAKind {
+                    quads: Rank::Eight,
+                    kicker: Rank::King,
+                },
+            ),
+            (
+                "5s 5h 5c 5d 2d",
+                HandKind::FourOfAKind {
+                    quads: Rank::Five,
+                    kicker: Rank::Two,
+                },
+            ),
+            (
+                "2s 2h 2c 2d 8h",
+                HandKind::FourOfAKind {
+                    quads: Rank::Two,
+                    kicker: Rank::Eight,
+                },
+            ),
+            (
+                "As Ah Ad Kc Ks",
+                HandKind::FullHouse {
+                    trips: Rank::Ace,
+                    pair: Rank::King,
+                },
+            ),
+            (
+                "Ac Ad Ah 2c 2d",
+                HandKind::FullHouse {
+                    trips: Rank::Ace,
+                    pair: Rank::Two,
+                },
+            ),
+            (
+                "Ac Kc Kd Kh Ad",
+                HandKind::FullHouse {
+                    trips: Rank::King,
+                    pair: Rank::Ace,
+                },
+            ),
+            (
+                "7h 7d 7c 6s 6c",
+                HandKind::FullHouse {
+                    trips: Rank::Seven,
+                    pair: Rank::Six,
+                },
+            ),
+            (
+                "6h 7h 6d 7d 6c",
+                HandKind::FullHouse {
+                    trips: Rank::Six,
+                    pair: Rank::Seven,
+                },
+            ),
             (
                 "As 3s 5s 7s Ks",
                 HandKind::Flush([Rank::Ace, Rank::King, Rank::Seven, Rank::Five, Rank::Three]),
@@ -414,9 +1363,34 @@ mod tests {
             ("Td 9s 8h 7d 6c", HandKind::Straight(Rank::Ten)),
             ("7c 5c 9c 6h 8c", HandKind::Straight(Rank::Nine)),
             ("As 2c 3d 4h 5s", HandKind::Straight(Rank::Five)),
-            ("Kh Kd Ks 4c 2h", HandKind::ThreeOfAKind(Rank::King)),
-            ("7d Qd 6h Qc Qs", HandKind::ThreeOfAKind(Rank::Queen)),
-            ("3h 2s 2d 2c 7h", HandKind::ThreeOfAKind(Rank::Two)),
+            (
+                "Kc Kd Ks 5h 2s",
+                HandKind::ThreeOfAKind {
+                    trips: Rank::King,
+                    high_cards: [Rank::Five, Rank::Two],
+                },
+            ),
+            (
+                "Kh Kd Ks 4c 2h",
+                HandKind::ThreeOfAKind {
+                    trips: Rank::King,
+                    high_cards: [Rank::Four, Rank::Two],
+                },
+            ),
+            (
+                "7d Qd 6h Qc Qs",
+                HandKind::ThreeOfAKind {
+                    trips: Rank::Queen,
+                    high_cards: [Rank::Seven, Rank::Six],
+                },
+            ),
+            (
+                "3h 2s 2d 2c 7h",
+                HandKind::ThreeOfAKind {
+                    trips: Rank::Two,
+                    high_cards: [Rank::Seven, Rank::Three],
+                },
+            ),
             (
                 "As Ah Ks Kc Qd",
                 HandKind::TwoPair {
@@ -551,4 +1525,363 @@ mod tests {
             .all(|(other_str, _)| Hand::new(cards_from_str(hand_str))
                 > Hand::new(cards_from_str(other_str)))));
     }
+
+    #[test]
+    fn deuce_to_seven_lowball_favours_the_lowest_hands() {
+        // from best to worst
+        let hands: Vec<&str> = vec![
+            "7s 5h 4d 3c 2s",
+            "7s 6h 4d 3c 2s",
+            "8s 5h 4d 3c 2s",
+            // the Ace is high, so this is no straight
+            "As 5h 4d 3c 2s",
+            "2s 2h 5d 4c 3s",
+            "6s 5h 4d 3c 2s",
+            "7h 5h 4h 3h 2h",
+        ];
+
+        for (i, hand) in hands.iter().enumerate() {
+            let hand: Hand = Hand::new(cards_from_str(hand));
+            for other in hands.iter().skip(i + 1) {
+                let other: Hand = Hand::new(cards_from_str(other));
+                assert_eq!(hand.deuce_to_seven_cmp(&other), std::cmp::Ordering::Greater);
+                assert_eq!(other.deuce_to_seven_cmp(&hand), std::cmp::Ordering::Less);
+            }
+        }
+        assert_eq!(
+            Hand::new(cards_from_str("As 5h 4d 3c 2s")).deuce_to_seven_kind(),
+            HandKind::HighCard([Rank::Ace, Rank::Five, Rank::Four, Rank::Three, Rank::Two])
+        );
+        // with more cards, the lowest five count
+        assert_eq!(
+            Hand::new(cards_from_str("Kd Kc 7s 5h 4d 3c 2s")).deuce_to_seven_kind(),
+            HandKind::HighCard([Rank::Seven, Rank::Five, Rank::Four, Rank::Three, Rank::Two])
+        );
+    }
+
+    #[test]
+    fn ace_to_five_lowball_favours_the_lowest_cards() {
+        // from best to worst
+        let hands: Vec<&str> = vec![
+            // straights and flushes don't count against you
+            "5h 4h 3h 2h Ah",
+            "6s 4h 3d 2c As",
+            "6s 5h 4d 3c 2s",
+            "8s 7h 6d 5c 4s",
+            "Ks Qh Jd Tc 9s",
+            "As Ah 2d 3c 4s",
+            "2s 2h 3d 4c 5s",
+            "As Ah 2d 2c 3s",
+            "Ks Kh Kd Qc Qs",
+        ];
+
+        for (i, hand) in hands.iter().enumerate() {
+            let hand: Hand = Hand::new(cards_from_str(hand));
+            for other in hands.iter().skip(i + 1) {
+                let other: Hand = Hand::new(cards_from_str(other));
+                assert_eq!(hand.ace_to_five_cmp(&other), std::cmp::Ordering::Greater);
+                assert_eq!(other.ace_to_five_cmp(&hand), std::cmp::Ordering::Less);
+            }
+        }
+        assert_eq!(
+            Hand::new(cards_from_str("As Ah 2d 3c 4s")).ace_to_five_low(),
+            [Rank::Ace, Rank::Ace, Rank::Four, Rank::Three, Rank::Two]
+        );
+        // with more cards, the lowest five count, pairs and all
+        assert_eq!(
+            Hand::new(cards_from_str("Ks Kh 9d 9c 7s 7h 2d")).ace_to_five_low(),
+            [Rank::Seven, Rank::Seven, Rank::King, Rank::Nine, Rank::Two]
+        );
+    }
+
+    #[test]
+    fn hi_lo_only_has_a_low_when_one_qualifies() {
+        let hi_lo: HiLo = Hand::hi_lo(&cards_from_str("Ah 2h 3h 4h 5h Kd Kc"));
+        assert_eq!(hi_lo.high, HandKind::StraightFlush(Rank::Five));
+        assert_eq!(
+            hi_lo.low,
+            Some([Rank::Five, Rank::Four, Rank::Three, Rank::Two, Rank::Ace])
+        );
+
+        // there's no five different ranks of eight or lower here
+        let hi_lo: HiLo = Hand::hi_lo(&cards_from_str("As 2d 3c 9h Ts Kd Kc"));
+        assert_eq!(
+            hi_lo.high,
+            HandKind::Pair {
+                pair: Rank::King,
+                high_cards: [Rank::Ace, Rank::Ten, Rank::Nine],
+            }
+        );
+        assert_eq!(hi_lo.low, None);
+
+        // nor here, with only four ranks to go around
+        let hi_lo: HiLo = Hand::hi_lo(&cards_from_str("As Ad 2c 2h 3s 4d 4c"));
+        assert_eq!(hi_lo.low, None);
+
+        let hi_lo: HiLo = Hand::hi_lo(&cards_from_str("8s 7d 6c 4h 3s Qd Jc"));
+        assert_eq!(
+            hi_lo.low,
+            Some([Rank::Eight, Rank::Seven, Rank::Six, Rank::Four, Rank::Three])
+        );
+    }
+
+    #[test]
+    fn short_deck_hands_have_their_own_straights_and_flushes() {
+        let short =
+            |hand: &str| -> Hand { Hand::new(cards_from_str(hand)).with_rules(Rules::ShortDeck) };
+
+        assert_eq!(
+            short("As 6h 7d 8c 9s").kind(),
+            HandKind::Straight(Rank::Nine)
+        );
+        assert_eq!(
+            short("Ah 6h 7h 8h 9h").kind(),
+            HandKind::StraightFlush(Rank::Nine)
+        );
+        assert_eq!(
+            Hand::new(cards_from_str("As 6h 7d 8c 9s")).kind(),
+            HandKind::HighCard([Rank::Ace, Rank::Nine, Rank::Eight, Rank::Seven, Rank::Six])
+        );
+        assert!(short("As 6h 7d 8c 9s") > short("Kh Qd Jc Ts 8s"));
+        assert!(short("As 6h 7d 8c 9s") < short("Ts 9h 8d 7c 6s"));
+
+        // a flush beats a full house, but only with a short deck
+        assert!(short("Kh Jh 9h 7h 6h") > short("As Ah Ad Kc Ks"));
+        assert!(
+            Hand::new(cards_from_str("Kh Jh 9h 7h 6h"))
+                < Hand::new(cards_from_str("As Ah Ad Kc Ks"))
+        );
+        // and so it's the flush that's the best five of seven
+        assert_eq!(
+            short("Kh Jh 9h 7h 6h Kc Ks").kind(),
+            HandKind::Flush([Rank::King, Rank::Jack, Rank::Nine, Rank::Seven, Rank::Six])
+        );
+
+        let mut deck: Deck = Deck::short();
+        assert_eq!(deck.remaining(), 36);
+        assert!(deck
+            .deal(36)
+            .unwrap()
+            .iter()
+            .all(|card| card.rank() >= Rank::Six));
+    }
+
+    #[test]
+    fn three_card_hands_evaluate_and_compare_correctly() {
+        // from highest to lowest
+        let hands: Vec<(&str, ThreeCardKind)> = vec![
+            ("Qh Kh Ah", ThreeCardKind::StraightFlush(Rank::Ace)),
+            ("2s As 3s", ThreeCardKind::StraightFlush(Rank::Three)),
+            ("7d 7c 7s", ThreeCardKind::ThreeOfAKind(Rank::Seven)),
+            ("Jd Qc Ks", ThreeCardKind::Straight(Rank::King)),
+            ("3d Ac 2s", ThreeCardKind::Straight(Rank::Three)),
+            (
+                "Ac 9c 4c",
+                ThreeCardKind::Flush([Rank::Ace, Rank::Nine, Rank::Four]),
+            ),
+            (
+                "5h 2h 3h",
+                ThreeCardKind::Flush([Rank::Five, Rank::Three, Rank::Two]),
+            ),
+            (
+                "4d Ks Kh",
+                ThreeCardKind::Pair {
+                    pair: Rank::King,
+                    high_card: Rank::Four,
+                },
+            ),
+            (
+                "As 2h 2d",
+                ThreeCardKind::Pair {
+                    pair: Rank::Two,
+                    high_card: Rank::Ace,
+                },
+            ),
+            (
+                "As Kd 9c",
+                ThreeCardKind::HighCard([Rank::Ace, Rank::King, Rank::Nine]),
+            ),
+            (
+                "Ah 4d 2c",
+                ThreeCardKind::HighCard([Rank::Ace, Rank::Four, Rank::Two]),
+            ),
+        ];
+
+        let kind = |hand: &str| -> ThreeCardKind {
+            Hand::three_card_kind(&cards_from_str(hand).try_into().unwrap())
+        };
+        for (i, (hand, hand_kind)) in hands.iter().enumerate() {
+            assert_eq!(kind(hand), *hand_kind);
+            for (other, _) in hands.iter().skip(i + 1) {
+                assert!(kind(hand) > kind(other));
+            }
+        }
+    }
+
+    #[test]
+    fn strengths_pack_the_category_above_the_ranks() {
+        assert_eq!(HandKind::RoyalFlush.strength(), 9 << 20);
+        assert_eq!(
+            HandKind::HighCard([Rank::Seven, Rank::Five, Rank::Four, Rank::Three, Rank::Two])
+                .strength(),
+            0x053210
+        );
+        assert_eq!(
+            HandKind::TwoPair {
+                pair_high: Rank::Ace,
+                pair_low: Rank::King,
+                high_card: Rank::Two,
+            }
+            .strength(),
+            0x2cb000
+        );
+        // no straight flush is as good as the royal flush
+        assert!(HandKind::StraightFlush(Rank::King).strength() < HandKind::RoyalFlush.strength());
+    }
+
+    #[test]
+    fn hands_and_kinds_are_written_for_people() {
+        let written = |hand: &str| -> String { Hand::new(cards_from_str(hand)).kind().to_string() };
+
+        assert_eq!(written("Kc Kd Ks 2h 2s"), "Full House, Kings over Twos");
+        assert_eq!(written("As Ah Ks Kc Qd"), "Two Pair, Aces and Kings");
+        assert_eq!(written("6s 6h 3d 4c 9d"), "Pair, Sixes");
+        assert_eq!(written("7d 7h 7s 7c 2d"), "Four of a Kind, Sevens");
+        assert_eq!(written("As 2c 3d 4h 5s"), "Straight, Five high");
+        assert_eq!(written("Kd Jd 9d 5d 3d"), "Flush, King high");
+        assert_eq!(written("Ts Js Qs Ks As"), "Royal Flush");
+        assert_eq!(written("Kh Ad 2s 3c 4h"), "High Card, Ace high");
+
+        let hand: Hand = Hand::new(cards_from_str("2s 7h As 7c Kd"));
+        assert_eq!(hand.to_string(), "As Kd 7c 7h 2s");
+        assert_eq!(format!("{:#}", hand), "A♠ K♦ 7♣ 7♥ 2♠");
+    }
+
+    #[test]
+    fn the_best_five_of_more_cards_count() {
+        let hands: Vec<(&str, HandKind)> = vec![
+            ("Ah Kh Qh Jh Th 2c 3d", HandKind::RoyalFlush),
+            (
+                "As Ad 7c 7d 7h 2s 3c",
+                HandKind::FullHouse {
+                    trips: Rank::Seven,
+                    pair: Rank::Ace,
+                },
+            ),
+            (
+                "Kd Jd 9d 5d 3d 2d As",
+                HandKind::Flush([Rank::King, Rank::Jack, Rank::Nine, Rank::Five, Rank::Three]),
+            ),
+            ("9s 8d 7c 6h 5s 4d 2c", HandKind::Straight(Rank::Nine)),
+            ("Ad 2c 3h 4s 5d Kc Qh", HandKind::Straight(Rank::Five)),
+            (
+                "Ah Jd 9c 7s 5h 3d 2c",
+                HandKind::HighCard([Rank::Ace, Rank::Jack, Rank::Nine, Rank::Seven, Rank::Five]),
+            ),
+            (
+                "Qs Qd 8c 6h 4s 3d",
+                HandKind::Pair {
+                    pair: Rank::Queen,
+                    high_cards: [Rank::Eight, Rank::Six, Rank::Four],
+                },
+            ),
+        ];
+
+        for (hand_str, hand_kind) in hands {
+            assert_eq!(Hand::best_of(&cards_from_str(hand_str)), hand_kind);
+            assert_eq!(Hand::new(cards_from_str(hand_str)).kind(), hand_kind);
+        }
+    }
+
+    #[test]
+    fn hands_sort_and_key_maps_by_how_good_they_are() {
+        let mut hands: Vec<Hand> = ["7h 7d 7c 6s 6c", "2s 2c 4d 5d 6d", "Td 9s 8h 7d 6c"]
+            .iter()
+            .map(|hand| Hand::new(cards_from_str(hand)))
+            .collect();
+        hands.sort();
+        let kinds: Vec<HandKind> = hands.iter().map(Hand::kind).collect();
+        assert_eq!(
+            kinds,
+            vec![
+                HandKind::Pair {
+                    pair: Rank::Two,
+                    high_cards: [Rank::Six, Rank::Five, Rank::Four],
+                },
+                HandKind::Straight(Rank::Ten),
+                HandKind::FullHouse {
+                    trips: Rank::Seven,
+                    pair: Rank::Six,
+                },
+            ]
+        );
+
+        // the same hand in other suits is the same key
+        let mut wins: std::collections::BTreeMap<Hand, u32> = std::collections::BTreeMap::new();
+        for hand in ["Ts Th 8s 7h 4c", "Tc Td 8c 7d 4h", "Kd Qd 7s 4s 3h"] {
+            *wins.entry(Hand::new(cards_from_str(hand))).or_insert(0) += 1;
+        }
+        assert_eq!(wins.values().copied().collect::<Vec<u32>>(), vec![1, 2]);
+    }
+
+    #[test]
+    fn seeded_decks_deal_the_same_cards_every_time() {
+        let deal = |seed: u64| -> Vec<(i32, i32)> {
+            let mut deck: Deck = Deck::new();
+            deck.shuffle(&mut GameRng::new(seed));
+            deck.deal(52)
+                .unwrap()
+                .iter()
+                .map(|card| (card.rank().into(), card.suit().into()))
+                .collect()
+        };
+
+        assert_eq!(deal(7), deal(7));
+        assert_ne!(deal(7), deal(8));
+        let mut cards: Vec<(i32, i32)> = deal(7);
+        cards.sort();
+        cards.dedup();
+        assert_eq!(cards.len(), 52);
+
+        let mut deck: Deck = Deck::new();
+        assert_eq!(deck.draw().map(|card| card.suit()), Some(Suit::Spade));
+        assert_eq!(deck.deal(52), None);
+        assert_eq!(deck.remaining(), 51);
+    }
+
+    #[test]
+    fn hands_need_five_cards_to_be_made() {
+        assert!(Hand::try_from(cards_from_str("As Kd 7c 7h")).is_err());
+        let hand: Hand = Hand::try_from(cards_from_str("As Kd 7c 7h 2s")).unwrap();
+        assert_eq!(Vec::<Card>::from(hand).len(), 5);
+    }
+
+    #[cfg(feature = "serde")]
+    #[test]
+    fn poker_types_round_trip_through_serde() {
+        let hand: Hand = Hand::new(cards_from_str("Kc Kd Ks 2h 2s"));
+        let written: String = serde_json::to_string(&hand).unwrap();
+        let read: Hand = serde_json::from_str(&written).unwrap();
+        assert_eq!(read.to_string(), hand.to_string());
+
+        let kind: HandKind = hand.kind();
+        let written: String = serde_json::to_string(&kind).unwrap();
+        assert_eq!(serde_json::from_str::<HandKind>(&written).unwrap(), kind);
+
+        let too_few: String = serde_json::to_string(&cards_from_str("As Kd 7c 7h")).unwrap();
+        assert!(serde_json::from_str::<Hand>(&too_few).is_err());
+    }
+
+    proptest::proptest! {
+        #[test]
+        fn hands_compare_consistently(hand0: Hand, hand1: Hand) {
+            crate::arbitrary::check_hand_order(&hand0, &hand1)?;
+        }
+
+        #[test]
+        fn strengths_compare_like_kinds(hand0: Hand, hand1: Hand) {
+            let (kind0, kind1): (HandKind, HandKind) = (hand0.kind(), hand1.kind());
+            proptest::prop_assert_eq!(kind0.strength().cmp(&kind1.strength()), kind0.cmp(&kind1));
+        }
+    }
 }
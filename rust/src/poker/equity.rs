@@ -0,0 +1,388 @@
+//! Estimating how often a hand will win by dealing out the rest at random
+//!
+//! Rather than counting every way the board and the opponents' cards
+//! could fall, which is far too many with several opponents, hands are
+//! played out to showdown over and over with cards drawn from a
+//! [`GameRng`], and the results are tallied.  The more iterations, the
+//! closer the tally gets to the true odds.
+//!
+//! With the `lookup` feature, showdowns are valued with
+//! [`super::lookup`], which makes simulations several times faster.
+
+use super::range::Range;
+use super::{is_same_card, Card, Deck};
+use crate::rng::GameRng;
+
+/// How often a hand wins, ties, and loses, each as a percentage
+///
+/// The three always add up to 100, give or take rounding.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Equity {
+    /// How often the hand beats every opponent
+    pub win: f64,
+    /// How often the hand is the best but shares that with an opponent
+    pub tie: f64,
+    /// How often an opponent has a better hand
+    pub lose: f64,
+}
+
+impl Equity {
+    /// The percentages from counts of wins and ties out of `iterations`
+    fn from_counts(wins: u32, ties: u32, iterations: u32) -> Equity {
+        let percent = |count: u32| -> f64 { 100.0 * count as f64 / iterations as f64 };
+        Equity {
+            win: percent(wins),
+            tie: percent(ties),
+            lose: percent(iterations - wins - ties),
+        }
+    }
+}
+
+/// Estimate the odds of `hole_cards` against some `opponents` by
+/// dealing out the rest of the hand `iterations` times
+///
+/// `board` holds the community cards dealt so far, from none before the
+/// flop to all five on the river, and the rest of it is dealt at random
+/// along with two cards for each opponent.  Each opponent is assumed to
+/// stay in to showdown with whatever they're dealt.
+///
+/// This is an error if a card is dealt twice, if there are more than
+/// five cards on the board, if there are no opponents or iterations, or
+/// if there aren't enough cards left in the deck to deal everyone in.
+///
+/// # Examples
+///
+/// ```
+/// let aces: [Card; 2] = ["As".parse().unwrap(), "Ah".parse().unwrap()];
+/// let equity: Equity = equity::simulate(&aces, &[], 1, 10000, &mut GameRng::new(7)).unwrap();
+///
+/// // pocket aces win about 85% of the time heads up
+/// assert!((80.0..90.0).contains(&equity.win));
+/// ```
+pub fn simulate(
+    hole_cards: &[Card; 2],
+    board: &[Card],
+    opponents: usize,
+    iterations: u32,
+    rng: &mut GameRng,
+) -> Result<Equity, &'static str> {
+    if board.len() > 5 {
+        return Err("a board can't have more than 5 cards");
+    }
+    if opponents == 0 {
+        return Err("there must be at least one opponent");
+    }
+    if iterations == 0 {
+        return Err("there must be at least one iteration");
+    }
+    let known: Vec<Card> = hole_cards.iter().chain(board).cloned().collect();
+    let unseen: Deck = unseen_cards(&known)?;
+    if unseen.remaining() < 5 - board.len() + 2 * opponents {
+        return Err("there aren't enough cards to deal in that many opponents");
+    }
+
+    let mut wins: u32 = 0;
+    let mut ties: u32 = 0;
+    for _ in 0..iterations {
+        let mut deck: Deck = unseen.clone();
+        deck.shuffle(rng);
+        let mut full_board: Vec<Card> = board.to_vec();
+        full_board.extend(deck.deal(5 - board.len()).unwrap());
+
+        let strength: u32 = best_strength(hole_cards, &full_board);
+        let best_opponent: u32 = (0..opponents)
+            .map(|_| {
+                let opponent: [Card; 2] = deck.deal(2).unwrap().try_into().unwrap();
+                best_strength(&opponent, &full_board)
+            })
+            .max()
+            .unwrap();
+        match strength.cmp(&best_opponent) {
+            std::cmp::Ordering::Greater => wins += 1,
+            std::cmp::Ordering::Equal => ties += 1,
+            std::cmp::Ordering::Less => (),
+        }
+    }
+    Ok(Equity::from_counts(wins, ties, iterations))
+}
+
+/// Estimate the odds of a hand from `range` against one from
+/// `opponent_range` by dealing out the rest of the hand `iterations`
+/// times
+///
+/// Each time, a hand is picked from each range, leaving out any that
+/// share a card with `board` or with each other, so that every pair of
+/// hands that could really be dealt together is as likely as any other.
+/// The rest of the board is dealt from what's left.
+///
+/// This is an error if a card is on the board twice, if there are more
+/// than five cards on the board, if there are no iterations, or if no
+/// hands from the two ranges can be dealt alongside the board and each
+/// other.
+///
+/// # Examples
+///
+/// ```
+/// let premiums: Range = "QQ+, AKs".parse().unwrap();
+/// let suited_connectors: Range = "54s, 65s, 76s, 87s, 98s".parse().unwrap();
+/// let board: Vec<Card> = "Ah 7d 2c".split(' ').map(|card| card.parse().unwrap()).collect();
+///
+/// let equity: Equity =
+///     equity::range_vs_range(&premiums, &suited_connectors, &board, 10000, &mut GameRng::new(7)).unwrap();
+/// assert!(equity.win > equity.lose);
+/// ```
+pub fn range_vs_range(
+    range: &Range,
+    opponent_range: &Range,
+    board: &[Card],
+    iterations: u32,
+    rng: &mut GameRng,
+) -> Result<Equity, &'static str> {
+    if board.len() > 5 {
+        return Err("a board can't have more than 5 cards");
+    }
+    if iterations == 0 {
+        return Err("there must be at least one iteration");
+    }
+    let unseen: Deck = unseen_cards(board)?;
+    let is_live = |combo: &&[Card; 2]| -> bool {
+        !combo
+            .iter()
+            .any(|card| board.iter().any(|dead| is_same_card(card, dead)))
+    };
+    let combos: Vec<&[Card; 2]> = range.combos().iter().filter(is_live).collect();
+    let opponent_combos: Vec<&[Card; 2]> = opponent_range.combos().iter().filter(is_live).collect();
+    let can_be_dealt: bool = combos.iter().any(|combo| {
+        opponent_combos
+            .iter()
+            .any(|opponent| !shares_a_card(combo, opponent))
+    });
+    if !can_be_dealt {
+        return Err("no hands in the ranges can be dealt together");
+    }
+
+    let mut wins: u32 = 0;
+    let mut ties: u32 = 0;
+    let mut dealt: u32 = 0;
+    while dealt < iterations {
+        // picking both hands again whenever they clash keeps every
+        // pair that can be dealt together equally likely
+        let hole_cards: &[Card; 2] = combos[rng.below(combos.len() as u32) as usize];
+        let opponent: &[Card; 2] =
+            opponent_combos[rng.below(opponent_combos.len() as u32) as usize];
+        if shares_a_card(hole_cards, opponent) {
+            continue;
+        }
+        dealt += 1;
+
+        let mut deck: Deck = Deck {
+            cards: unseen
+                .cards
+                .iter()
+                .filter(|card| {
+                    !hole_cards
+                        .iter()
+                        .chain(opponent)
+                        .any(|dead| is_same_card(card, dead))
+                })
+                .cloned()
+                .collect(),
+        };
+        deck.shuffle(rng);
+        let mut full_board: Vec<Card> = board.to_vec();
+        full_board.extend(deck.deal(5 - board.len()).unwrap());
+
+        let strength: u32 = best_strength(hole_cards, &full_board);
+        match strength.cmp(&best_strength(opponent, &full_board)) {
+            std::cmp::Ordering::Greater => wins += 1,
+            std::cmp::Ordering::Equal => ties += 1,
+            std::cmp::Ordering::Less => (),
+        }
+    }
+    Ok(Equity::from_counts(wins, ties, iterations))
+}
+
+/// Whether any card is in both `a` and `b`
+fn shares_a_card(a: &[Card; 2], b: &[Card; 2]) -> bool {
+    a.iter()
+        .any(|card| b.iter().any(|other| is_same_card(card, other)))
+}
+
+/// The deck without any of the `known` cards, or an error if one of
+/// them is there twice
+fn unseen_cards(known: &[Card]) -> Result<Deck, &'static str> {
+    for (i, card) in known.iter().enumerate() {
+        if known[..i].iter().any(|other| is_same_card(card, other)) {
+            return Err("a card can't be dealt twice");
+        }
+    }
+    Ok(Deck {
+        cards: Deck::new()
+            .cards
+            .into_iter()
+            .filter(|card| !known.iter().any(|other| is_same_card(card, other)))
+            .collect(),
+    })
+}
+
+/// The [`HandKind::strength`] of the best five of `hole_cards` and
+/// `board` together
+///
+/// [`HandKind::strength`]: super::HandKind::strength
+#[cfg(feature = "lookup")]
+fn best_strength(hole_cards: &[Card; 2], board: &[Card]) -> u32 {
+    let cards: Vec<Card> = hole_cards.iter().chain(board).cloned().collect();
+    super::lookup::best_strength(&cards)
+}
+
+/// The [`HandKind::strength`] of the best five of `hole_cards` and
+/// `board` together
+///
+/// [`HandKind::strength`]: super::HandKind::strength
+#[cfg(not(feature = "lookup"))]
+fn best_strength(hole_cards: &[Card; 2], board: &[Card]) -> u32 {
+    let cards: Vec<Card> = hole_cards.iter().chain(board).cloned().collect();
+    super::Hand::best_of(&cards).strength()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn cards(cards: &str) -> Vec<Card> {
+        cards.split(' ').map(|card| card.parse().unwrap()).collect()
+    }
+
+    fn hole_cards(hole_cards: &str) -> [Card; 2] {
+        cards(hole_cards).try_into().unwrap()
+    }
+
+    #[test]
+    fn pocket_aces_are_big_favourites_heads_up() {
+        let equity: Equity =
+            simulate(&hole_cards("As Ah"), &[], 1, 4000, &mut GameRng::new(1)).unwrap();
+
+        assert!((80.0..90.0).contains(&equity.win), "{:?}", equity);
+        assert!((99.9..100.1).contains(&(equity.win + equity.tie + equity.lose)));
+    }
+
+    #[test]
+    fn more_opponents_mean_fewer_wins() {
+        let heads_up: Equity =
+            simulate(&hole_cards("Kd Kc"), &[], 1, 2000, &mut GameRng::new(2)).unwrap();
+        let full_table: Equity =
+            simulate(&hole_cards("Kd Kc"), &[], 8, 2000, &mut GameRng::new(2)).unwrap();
+
+        assert!(full_table.win < heads_up.win);
+    }
+
+    #[test]
+    fn a_made_hand_on_the_river_is_certain() {
+        let royal_flush: Equity = simulate(
+            &hole_cards("As Ks"),
+            &cards("Qs Js Ts 2d 3c"),
+            3,
+            100,
+            &mut GameRng::new(3),
+        )
+        .unwrap();
+        assert_eq!(
+            royal_flush,
+            Equity {
+                win: 100.0,
+                tie: 0.0,
+                lose: 0.0
+            }
+        );
+
+        let playing_the_board: Equity = simulate(
+            &hole_cards("2d 3c"),
+            &cards("As Ks Qs Js Ts"),
+            3,
+            100,
+            &mut GameRng::new(3),
+        )
+        .unwrap();
+        assert_eq!(
+            playing_the_board,
+            Equity {
+                win: 0.0,
+                tie: 100.0,
+                lose: 0.0
+            }
+        );
+    }
+
+    #[test]
+    fn the_same_seed_gives_the_same_odds() {
+        let board: Vec<Card> = cards("9h Th 2c");
+        let first: Equity =
+            simulate(&hole_cards("Jh Qd"), &board, 2, 500, &mut GameRng::new(4)).unwrap();
+        let replayed: Equity =
+            simulate(&hole_cards("Jh Qd"), &board, 2, 500, &mut GameRng::new(4)).unwrap();
+
+        assert_eq!(first, replayed);
+    }
+
+    #[test]
+    fn ranges_of_one_hand_match_simulating_that_hand() {
+        let board: Vec<Card> = cards("Ks 8d 3c");
+        let aces: Range = "AhAd".parse().unwrap();
+        let kings: Range = "KhKd".parse().unwrap();
+
+        let equity: Equity =
+            range_vs_range(&aces, &kings, &board, 2000, &mut GameRng::new(6)).unwrap();
+        // aces need one of the two Aces left in 45 cards on each street
+        assert!((3.0..13.0).contains(&equity.win), "{:?}", equity);
+        assert!((99.9..100.1).contains(&(equity.win + equity.tie + equity.lose)));
+    }
+
+    #[test]
+    fn hands_sharing_cards_with_the_board_or_each_other_are_never_dealt() {
+        // the only Ace left that isn't on the board is the Ace of Spades,
+        // so every AK the opponent can have holds a King
+        let board: Vec<Card> = cards("Ah Ad Ac 7h 2d");
+        let sets: Range = "KK".parse().unwrap();
+        let aces: Range = "AK".parse().unwrap();
+
+        let equity: Equity =
+            range_vs_range(&sets, &aces, &board, 500, &mut GameRng::new(7)).unwrap();
+        assert_eq!(equity.win, 0.0);
+
+        let no_aces_left: Range = "AsKs".parse().unwrap();
+        assert!(
+            range_vs_range(&no_aces_left, &no_aces_left, &[], 10, &mut GameRng::new(7)).is_err()
+        );
+        assert!(range_vs_range(
+            &sets,
+            &no_aces_left,
+            &cards("As 2d 3c"),
+            10,
+            &mut GameRng::new(7)
+        )
+        .is_err());
+        assert!(
+            range_vs_range(&sets, &aces, &cards("As As 3c"), 10, &mut GameRng::new(7)).is_err()
+        );
+    }
+
+    #[test]
+    fn impossible_deals_are_errors() {
+        let mut rng: GameRng = GameRng::new(5);
+
+        assert!(simulate(&hole_cards("As Ah"), &cards("As 2d 3c"), 1, 10, &mut rng).is_err());
+        // same rank, different suits, so not the same card
+        assert!(simulate(&hole_cards("As Ah"), &cards("Ad 2d 3c"), 1, 10, &mut rng).is_ok());
+        assert!(simulate(
+            &hole_cards("As Ah"),
+            &cards("2d 3c 4c 5c 6c 7c"),
+            1,
+            10,
+            &mut rng
+        )
+        .is_err());
+        assert!(simulate(&hole_cards("As Ah"), &[], 0, 10, &mut rng).is_err());
+        assert!(simulate(&hole_cards("As Ah"), &[], 1, 0, &mut rng).is_err());
+        assert!(simulate(&hole_cards("As Ah"), &[], 23, 10, &mut rng).is_err());
+    }
+}
@@ -0,0 +1,213 @@
+//! Evaluating hands by looking them up in precomputed tables
+//!
+//! [`Hand::kind`] works a hand out from scratch every time, which is
+//! plenty for a game but far too slow to value millions of hands for
+//! an equity simulation.  The functions here give the same
+//! [`HandKind::strength`] without allocating anything, by looking
+//! hands up in tables that are built the first time they're needed.
+//!
+//! Hands of five different ranks are looked up by which ranks they
+//! have, in one table for flushes and another for everything else.
+//! Hands with a pair or better can't be flushes, so they're looked up
+//! by the product of a prime for each of their ranks, which is the same
+//! for every order of the same ranks.  This is the scheme of Kevin
+//! Suffecool's "Cactus Kev" evaluator.
+//!
+//! It is only compiled with the `lookup` feature enabled.
+
+use std::sync::OnceLock;
+
+use super::{Card, Hand, Rank, Suit};
+
+/// A prime for each rank, from Two up to Ace
+const PRIMES: [u32; 13] = [2, 3, 5, 7, 11, 13, 17, 19, 23, 29, 31, 37, 41];
+
+/// Strengths by which ranks a hand has and which ranks multiply to
+struct Tables {
+    /// Strengths of flushes by the bit mask of their five ranks
+    flushes: Vec<u32>,
+    /// Strengths of other hands of five ranks by their bit mask
+    unique: Vec<u32>,
+    /// Strengths of hands with a pair or better by the product of
+    /// their ranks' primes, sorted by product
+    paired: Vec<(u32, u32)>,
+}
+
+static TABLES: OnceLock<Tables> = OnceLock::new();
+
+/// The [`HandKind::strength`] of the hand `cards` make
+///
+/// [`HandKind::strength`]: super::HandKind::strength
+///
+/// # Panics
+///
+/// Panics if there are five cards of the same rank, which can't happen
+/// with cards from a single deck.
+///
+/// # Examples
+///
+/// ```
+/// let cards: [Card; 5] = ["As", "Ks", "Qs", "Js", "Ts"].map(|card| card.parse().unwrap());
+///
+/// assert_eq!(lookup::strength(&cards), HandKind::RoyalFlush.strength());
+/// ```
+pub fn strength(cards: &[Card; 5]) -> u32 {
+    let tables: &Tables = TABLES.get_or_init(Tables::new);
+    let mask: usize = cards
+        .iter()
+        .fold(0, |mask, card| mask | 1 << i32::from(card.rank()));
+    if mask.count_ones() == 5 {
+        let is_flush: bool = cards.iter().all(|card| card.suit() == cards[0].suit());
+        return match is_flush {
+            true => tables.flushes[mask],
+            false => tables.unique[mask],
+        };
+    }
+    let product: u32 = cards
+        .iter()
+        .map(|card| PRIMES[i32::from(card.rank()) as usize])
+        .product();
+    match tables
+        .paired
+        .binary_search_by_key(&product, |(product, _)| *product)
+    {
+        Ok(i) => tables.paired[i].1,
+        Err(_) => panic!("there can't be five cards of the same rank"),
+    }
+}
+
+/// The greatest [`strength`] of any five of `cards`
+///
+/// This is what [`Hand::best_of`] values, so it's for games like Texas
+/// Hold'em where the best five of seven cards count.
+///
+/// # Panics
+///
+/// Panics if fewer than five cards are passed in, or if [`strength`]
+/// would.
+pub fn best_strength(cards: &[Card]) -> u32 {
+    assert!(cards.len() >= 5, "there must be 5 or more cards in a hand");
+    let count: usize = cards.len();
+
+    // step through the indices of every five cards in order, like
+    // Hand::fives, but without collecting them
+    let mut indices: [usize; 5] = [0, 1, 2, 3, 4];
+    let mut best: u32 = 0;
+    loop {
+        let five: [Card; 5] = indices.map(|i| cards[i].clone());
+        best = best.max(strength(&five));
+
+        let Some(i) = (0..5).rev().find(|&i| indices[i] < count - 5 + i) else {
+            break;
+        };
+        indices[i] += 1;
+        for j in i + 1..5 {
+            indices[j] = indices[j - 1] + 1;
+        }
+    }
+    best
+}
+
+impl Tables {
+    /// Work out every table by valuing one hand for each entry the
+    /// slow way
+    fn new() -> Tables {
+        let value = |ranks: &[i32], suits: &[i32]| -> u32 {
+            let cards: Vec<Card> = ranks
+                .iter()
+                .zip(suits)
+                .map(|(&rank, &suit)| {
+                    Card::new(Rank::try_from(rank).unwrap(), Suit::try_from(suit).unwrap())
+                })
+                .collect();
+            Hand::new(cards).kind().strength()
+        };
+
+        let mut flushes: Vec<u32> = vec![0; 1 << 13];
+        let mut unique: Vec<u32> = vec![0; 1 << 13];
+        for mask in 0..1_usize << 13 {
+            if mask.count_ones() != 5 {
+                continue;
+            }
+            let ranks: Vec<i32> = (0..13).filter(|rank| mask & 1 << rank != 0).collect();
+            flushes[mask] = value(&ranks, &[0; 5]);
+            unique[mask] = value(&ranks, &[0, 0, 0, 0, 1]);
+        }
+
+        let mut paired: Vec<(u32, u32)> = vec![];
+        let mut ranks: Vec<i32> = vec![];
+        Tables::add_paired(&mut ranks, 0, &mut paired, &value);
+        paired.sort();
+
+        Tables {
+            flushes,
+            unique,
+            paired,
+        }
+    }
+
+    /// Add every hand with a pair or better to `paired` whose ranks
+    /// start with `ranks`, going on with ranks no lower than `lowest`
+    fn add_paired(
+        ranks: &mut Vec<i32>,
+        lowest: i32,
+        paired: &mut Vec<(u32, u32)>,
+        value: &dyn Fn(&[i32], &[i32]) -> u32,
+    ) {
+        if ranks.len() == 5 {
+            let is_paired: bool = ranks.windows(2).any(|pair| pair[0] == pair[1]);
+            if is_paired {
+                // each copy of a rank gets its own suit, which can't
+                // all be the same with a pair in there
+                let suits: Vec<i32> = ranks
+                    .iter()
+                    .enumerate()
+                    .map(|(i, rank)| {
+                        ranks[..i].iter().filter(|other| *other == rank).count() as i32
+                    })
+                    .collect();
+                let product: u32 = ranks.iter().map(|&rank| PRIMES[rank as usize]).product();
+                paired.push((product, value(ranks, &suits)));
+            }
+            return;
+        }
+        for rank in lowest..13 {
+            if ranks.iter().filter(|other| **other == rank).count() == 4 {
+                continue;
+            }
+            ranks.push(rank);
+            Tables::add_paired(ranks, rank, paired, value);
+            ranks.pop();
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    proptest::proptest! {
+        #[test]
+        fn lookups_agree_with_working_it_out(hand: Hand) {
+            let cards: [Card; 5] = hand.cards.clone().try_into().unwrap();
+            proptest::prop_assert_eq!(strength(&cards), hand.kind().strength());
+        }
+
+        #[test]
+        fn the_best_five_of_seven_agree_with_working_it_out(
+            cards in proptest::sample::subsequence(crate::arbitrary::deck(), 7)
+        ) {
+            proptest::prop_assert_eq!(best_strength(&cards), Hand::best_of(&cards).strength());
+        }
+    }
+
+    #[test]
+    fn every_kind_of_paired_hand_is_in_the_table() {
+        let tables: &Tables = TABLES.get_or_init(Tables::new);
+
+        // there are 7462 kinds of five card hands, and 1287 of them
+        // are both flushes and not, with five different ranks
+        assert_eq!(tables.paired.len(), 7462 - 2 * 1287);
+        assert!(tables.paired.windows(2).all(|pair| pair[0].0 < pair[1].0));
+    }
+}
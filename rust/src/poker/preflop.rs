@@ -0,0 +1,104 @@
+//! Judging hole cards before any community cards are dealt
+//!
+//! Nothing here deals anything out, so it's quick enough to call for
+//! every decision a simple opponent makes, and simple enough to explain
+//! in a tutorial.
+
+use super::{Card, Rank};
+
+/// How strong `hole_cards` are to start with, by Bill Chen's formula
+///
+/// Scores go from `-1` for a Seven and a Two of different suits up to
+/// `20` for a pair of Aces.  The formula is
+///
+/// 1. score the higher card: 10 for an Ace, 8 for a King, 7 for a
+///    Queen, 6 for a Jack, and half its number for anything lower
+/// 2. double that for a pair, making it at least 5
+/// 3. add 2 if the cards are suited
+/// 4. take 1, 2, 4, or 5 off for a gap of 1, 2, 3, or more ranks
+///    between them
+/// 5. add 1 if there's a gap of 1 or less and both cards are lower than
+///    a Queen, since those make the most straights
+/// 6. round half points up
+///
+/// Chen suggests raising with 10 or more, and folding almost anything
+/// under 7 outside of late position.
+///
+/// # Examples
+///
+/// ```
+/// let suited_connectors: [Card; 2] = ["Th".parse().unwrap(), "9h".parse().unwrap()];
+///
+/// assert_eq!(preflop::chen_score(&suited_connectors), 8);
+/// ```
+pub fn chen_score(hole_cards: &[Card; 2]) -> i32 {
+    let high: Rank = hole_cards[0].rank().max(hole_cards[1].rank());
+    let low: Rank = hole_cards[0].rank().min(hole_cards[1].rank());
+
+    let mut score: f64 = match high {
+        Rank::Ace => 10.0,
+        Rank::King => 8.0,
+        Rank::Queen => 7.0,
+        Rank::Jack => 6.0,
+        // ordinals start from 0 for Two
+        rank => (i32::from(rank) + 2) as f64 / 2.0,
+    };
+    if high == low {
+        return (score * 2.0).max(5.0).ceil() as i32;
+    }
+
+    if hole_cards[0].suit() == hole_cards[1].suit() {
+        score += 2.0;
+    }
+    let gap: i32 = i32::from(high) - i32::from(low) - 1;
+    score -= match gap {
+        0 => 0.0,
+        1 => 1.0,
+        2 => 2.0,
+        3 => 4.0,
+        _ => 5.0,
+    };
+    if gap <= 1 && high < Rank::Queen {
+        score += 1.0;
+    }
+    score.ceil() as i32
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn score(hole_cards: &str) -> i32 {
+        let hole_cards: Vec<Card> = hole_cards
+            .split(' ')
+            .map(|card| card.parse().unwrap())
+            .collect();
+        chen_score(&hole_cards.try_into().unwrap())
+    }
+
+    #[test]
+    fn pairs_score_double_their_rank() {
+        assert_eq!(score("As Ah"), 20);
+        assert_eq!(score("Kd Kc"), 16);
+        assert_eq!(score("Th Tc"), 10);
+        assert_eq!(score("5h 5c"), 5);
+        assert_eq!(score("2h 2c"), 5);
+    }
+
+    #[test]
+    fn suits_and_gaps_move_the_score() {
+        assert_eq!(score("As Ks"), 12);
+        assert_eq!(score("Ks Ad"), 10);
+        assert_eq!(score("Jh Th"), 9);
+        assert_eq!(score("Th 9h"), 8);
+        assert_eq!(score("5c 7c"), 6);
+        assert_eq!(score("Qd 9c"), 5);
+        assert_eq!(score("7s 2h"), -1);
+    }
+
+    #[test]
+    fn the_order_of_the_cards_does_not_matter() {
+        assert_eq!(score("9d Jc"), score("Jc 9d"));
+        assert_eq!(score("3s 4s"), score("4s 3s"));
+    }
+}
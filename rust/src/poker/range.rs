@@ -0,0 +1,234 @@
+//! Ranges of hole cards, as players write down what an opponent might hold
+//!
+//! A range is written as a comma separated list of any of
+//!
+//! - a pair, like `QQ`, or two ranks, like `AK`, which is every way of
+//!   being dealt them, with `s` after for only suited ones, `AKs`, or `o`
+//!   for only offsuit ones, `AKo`
+//! - one of those with `+` after, for it and everything up to the next
+//!   rank, so `TT+` is every pair from Tens to Aces and `A9s+` is every
+//!   suited Ace with a Nine or better
+//! - two of those with a `-` between, for everything in between, like
+//!   `22-55` or `A5s-A2s`
+//! - exact hole cards, like `AsKs`
+//!
+//! Ranks and suits are written as they are for [`Card`]s.
+
+use super::{is_same_card, Card, Rank, Suit};
+
+/// Every pair of hole cards a player might hold, each just once
+///
+/// # Examples
+///
+/// ```
+/// let range: Range = "QQ+, AKs, AsQd".parse().unwrap();
+///
+/// // 6 ways each for three pairs, 4 for the suited hands, and 1 exactly
+/// assert_eq!(range.combos().len(), 3 * 6 + 4 + 1);
+/// ```
+#[derive(Debug, Clone)]
+pub struct Range {
+    combos: Vec<[Card; 2]>,
+}
+
+impl Range {
+    /// Every pair of hole cards in the range
+    pub fn combos(&self) -> &[[Card; 2]] {
+        &self.combos
+    }
+}
+
+impl std::str::FromStr for Range {
+    type Err = &'static str;
+
+    /// Reads a range written as described in the [module](self)
+    /// documentation
+    ///
+    /// Anything else, or a range with no hands in it, will `Err`.
+    fn from_str(range: &str) -> Result<Self, Self::Err> {
+        let mut combos: Vec<[Card; 2]> = vec![];
+        for part in range.split(',').map(str::trim) {
+            for combo in part_combos(part)? {
+                let is_new: bool = !combos.iter().any(|other| is_same_combo(&combo, other));
+                if is_new {
+                    combos.push(combo);
+                }
+            }
+        }
+        Ok(Range { combos })
+    }
+}
+
+/// Hole cards by their ranks and whether they're suited, like `AKs`
+#[derive(Debug, Clone, Copy, PartialEq)]
+struct Class {
+    high: Rank,
+    low: Rank,
+    /// Whether only suited hands count, only offsuit ones do, or both,
+    /// which is always both for pairs
+    suited: Option<bool>,
+}
+
+impl std::str::FromStr for Class {
+    type Err = &'static str;
+
+    fn from_str(class: &str) -> Result<Self, Self::Err> {
+        let mut chars = class.chars();
+        let (Some(first), Some(second)) = (chars.next(), chars.next()) else {
+            return Err("Not a Range");
+        };
+        let first: Rank = first.encode_utf8(&mut [0; 4]).parse()?;
+        let second: Rank = second.encode_utf8(&mut [0; 4]).parse()?;
+        let suited: Option<bool> = match chars.as_str() {
+            "" => None,
+            "s" if first != second => Some(true),
+            "o" if first != second => Some(false),
+            _ => return Err("Not a Range"),
+        };
+        Ok(Class {
+            high: first.max(second),
+            low: first.min(second),
+            suited,
+        })
+    }
+}
+
+impl Class {
+    fn is_pair(&self) -> bool {
+        self.high == self.low
+    }
+
+    /// The same class with both ranks set to `rank` if it's a pair, or
+    /// with just the lower one set to it otherwise
+    fn with_low(self, rank: Rank) -> Class {
+        Class {
+            high: if self.is_pair() { rank } else { self.high },
+            low: rank,
+            ..self
+        }
+    }
+
+    /// Every pair of hole cards in the class
+    fn combos(&self) -> Vec<[Card; 2]> {
+        let suits: Vec<Suit> = (0..4).map(|suit| Suit::try_from(suit).unwrap()).collect();
+        let mut combos: Vec<[Card; 2]> = vec![];
+        for (i, high_suit) in suits.iter().enumerate() {
+            for (j, low_suit) in suits.iter().enumerate() {
+                let counts: bool = match (self.is_pair(), self.suited) {
+                    (true, _) => i < j,
+                    (false, Some(true)) => i == j,
+                    (false, Some(false)) => i != j,
+                    (false, None) => true,
+                };
+                if counts {
+                    combos.push([
+                        Card::new(self.high, *high_suit),
+                        Card::new(self.low, *low_suit),
+                    ]);
+                }
+            }
+        }
+        combos
+    }
+}
+
+/// Every pair of hole cards in one comma separated part of a range
+fn part_combos(part: &str) -> Result<Vec<[Card; 2]>, &'static str> {
+    let chars: Vec<char> = part.chars().collect();
+    if chars.len() == 4 && !part.contains(['+', '-']) {
+        let first: Card = chars[..2].iter().collect::<String>().parse()?;
+        let second: Card = chars[2..].iter().collect::<String>().parse()?;
+        if is_same_card(&first, &second) {
+            return Err("Not a Range");
+        }
+        return Ok(vec![[first, second]]);
+    }
+
+    let classes: Vec<Class> = if let Some(class) = part.strip_suffix('+') {
+        let class: Class = class.parse()?;
+        let top: Rank = match class.is_pair() {
+            true => Rank::Ace,
+            false => Rank::try_from(i32::from(class.high) - 1)?,
+        };
+        ranks_between(class.low, top)
+            .map(|rank| class.with_low(rank))
+            .collect()
+    } else if let Some((first, last)) = part.split_once('-') {
+        let first: Class = first.parse()?;
+        let last: Class = last.parse()?;
+        let is_pairs: bool = first.is_pair() && last.is_pair();
+        let is_kickers: bool = !first.is_pair()
+            && !last.is_pair()
+            && first.high == last.high
+            && first.suited == last.suited;
+        if !is_pairs && !is_kickers {
+            return Err("Not a Range");
+        }
+        ranks_between(first.low.min(last.low), first.low.max(last.low))
+            .map(|rank| first.with_low(rank))
+            .collect()
+    } else {
+        vec![part.parse()?]
+    };
+    Ok(classes.iter().flat_map(Class::combos).collect())
+}
+
+/// Every rank from `low` to `high`, both included
+fn ranks_between(low: Rank, high: Rank) -> impl Iterator<Item = Rank> {
+    (i32::from(low)..=i32::from(high)).map(|rank| Rank::try_from(rank).unwrap())
+}
+
+/// Whether `a` and `b` are the same hole cards, in either order
+fn is_same_combo(a: &[Card; 2], b: &[Card; 2]) -> bool {
+    (is_same_card(&a[0], &b[0]) && is_same_card(&a[1], &b[1]))
+        || (is_same_card(&a[0], &b[1]) && is_same_card(&a[1], &b[0]))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn combo_count(range: &str) -> usize {
+        range.parse::<Range>().unwrap().combos().len()
+    }
+
+    #[test]
+    fn hands_are_counted_every_way_they_can_be_dealt() {
+        assert_eq!(combo_count("AA"), 6);
+        assert_eq!(combo_count("AKs"), 4);
+        assert_eq!(combo_count("AKo"), 12);
+        assert_eq!(combo_count("AK"), 16);
+        assert_eq!(combo_count("KA"), 16);
+        assert_eq!(combo_count("AsKs"), 1);
+        assert_eq!(combo_count("A♠K♠"), 1);
+    }
+
+    #[test]
+    fn pluses_and_dashes_cover_everything_in_between() {
+        assert_eq!(combo_count("QQ+"), 3 * 6);
+        assert_eq!(combo_count("22+"), 13 * 6);
+        assert_eq!(combo_count("A2s+"), 12 * 4);
+        assert_eq!(combo_count("KTo+"), 3 * 12);
+        assert_eq!(combo_count("22-55"), 4 * 6);
+        assert_eq!(combo_count("A5s-A2s"), 4 * 4);
+    }
+
+    #[test]
+    fn overlapping_parts_only_count_once() {
+        assert_eq!(combo_count("AA, KK, AA"), 2 * 6);
+        assert_eq!(combo_count("AK, AKs, AhKh"), 16);
+        assert_eq!(combo_count("AsKs,KsAs"), 1);
+    }
+
+    #[test]
+    fn ranges_that_make_no_sense_are_errors() {
+        assert!("".parse::<Range>().is_err());
+        assert!("AAs".parse::<Range>().is_err());
+        assert!("AKx".parse::<Range>().is_err());
+        assert!("AsAs".parse::<Range>().is_err());
+        assert!("AK-QJ".parse::<Range>().is_err());
+        assert!("AKs-A2o".parse::<Range>().is_err());
+        assert!("22-AKs".parse::<Range>().is_err());
+        assert!("AA,,KK".parse::<Range>().is_err());
+    }
+}
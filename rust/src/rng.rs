@@ -0,0 +1,196 @@
+//! Deterministic random numbers for everything stochastic in the game
+//!
+//! Anything that needs randomness should draw it from a [`GameRng`]
+//! that's been seeded, or split off of one that has, so that a whole
+//! session can be replayed exactly from a single seed.
+//!
+//! The generator is PCG-XSH-RR, as described by Melissa O'Neill in
+//! https://www.pcg-random.org/paper.html, which is small, fast, and
+//! supports many independent streams from one seed.
+
+/// A seedable random number generator whose state can be saved
+///
+/// # Examples
+///
+/// ```
+/// let mut rng: GameRng = GameRng::new(1234);
+/// let saved: [u64; 2] = rng.state();
+/// let first_roll: u32 = rng.below(6);
+///
+/// let mut replayed: GameRng = GameRng::from_state(saved);
+/// assert_eq!(replayed.below(6), first_roll);
+/// ```
+#[derive(Debug, Clone, PartialEq)]
+pub struct GameRng {
+    state: u64,
+    increment: u64,
+}
+
+impl GameRng {
+    const MULTIPLIER: u64 = 6364136223846793005;
+
+    /// Create a generator from `seed` on the default stream
+    pub fn new(seed: u64) -> GameRng {
+        GameRng::with_stream(seed, 0)
+    }
+
+    /// Create a generator from `seed` on one of many independent streams
+    ///
+    /// Generators made with the same seed but different streams will
+    /// produce unrelated sequences.
+    pub fn with_stream(seed: u64, stream: u64) -> GameRng {
+        let mut rng: GameRng = GameRng {
+            state: 0,
+            increment: (stream << 1) | 1,
+        };
+        rng.next_u32();
+        rng.state = rng.state.wrapping_add(seed);
+        rng.next_u32();
+        rng
+    }
+
+    /// Recreate a generator from the output of [`GameRng::state`]
+    pub fn from_state(state: [u64; 2]) -> GameRng {
+        GameRng {
+            state: state[0],
+            // the increment has to be odd for the generator to have a
+            // full period
+            increment: state[1] | 1,
+        }
+    }
+
+    /// Everything needed to pick the sequence back up where it is now
+    pub fn state(&self) -> [u64; 2] {
+        [self.state, self.increment]
+    }
+
+    /// Make a new generator on a stream independent of this one
+    ///
+    /// This advances `self`, so splitting the same generator twice
+    /// gives two different generators.  It's useful for handing each
+    /// system its own generator so that one drawing more numbers than
+    /// usual doesn't disturb the others.
+    pub fn split(&mut self) -> GameRng {
+        let seed: u64 = self.next_u64();
+        let stream: u64 = self.next_u64();
+        GameRng::with_stream(seed, stream)
+    }
+
+    /// A uniformly random `u32`
+    pub fn next_u32(&mut self) -> u32 {
+        let old_state: u64 = self.state;
+        self.state = old_state
+            .wrapping_mul(GameRng::MULTIPLIER)
+            .wrapping_add(self.increment);
+        let xorshifted: u32 = (((old_state >> 18) ^ old_state) >> 27) as u32;
+        xorshifted.rotate_right((old_state >> 59) as u32)
+    }
+
+    /// A uniformly random `u64`
+    pub fn next_u64(&mut self) -> u64 {
+        ((self.next_u32() as u64) << 32) | self.next_u32() as u64
+    }
+
+    /// A uniformly random number from `0` up to, but not including, `bound`
+    ///
+    /// # Panics
+    ///
+    /// Panics if `bound` is zero.
+    pub fn below(&mut self, bound: u32) -> u32 {
+        assert!(bound > 0, "the bound must be positive");
+        // throw out the top few numbers that would make the lower
+        // results more likely than the upper ones
+        let threshold: u32 = bound.wrapping_neg() % bound;
+        loop {
+            let number: u32 = self.next_u32();
+            if number >= threshold {
+                return number % bound;
+            }
+        }
+    }
+
+    /// Put `items` in a uniformly random order
+    pub fn shuffle<T>(&mut self, items: &mut [T]) {
+        for i in (1..items.len()).rev() {
+            let j: usize = self.below(i as u32 + 1) as usize;
+            items.swap(i, j);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn same_seed_gives_same_sequence() {
+        let mut rng0: GameRng = GameRng::new(42);
+        let mut rng1: GameRng = GameRng::new(42);
+
+        for _ in 0..100 {
+            assert_eq!(rng0.next_u32(), rng1.next_u32());
+        }
+    }
+
+    #[test]
+    fn different_seeds_and_streams_give_different_sequences() {
+        let sequence = |mut rng: GameRng| (0..8).map(|_| rng.next_u32()).collect::<Vec<u32>>();
+
+        assert_ne!(sequence(GameRng::new(1)), sequence(GameRng::new(2)));
+        assert_ne!(
+            sequence(GameRng::with_stream(1, 1)),
+            sequence(GameRng::with_stream(1, 2))
+        );
+    }
+
+    #[test]
+    fn restoring_state_resumes_the_sequence() {
+        let mut rng: GameRng = GameRng::new(7);
+        rng.next_u64();
+        let mut restored: GameRng = GameRng::from_state(rng.state());
+
+        assert_eq!(restored, rng);
+        for _ in 0..100 {
+            assert_eq!(restored.next_u32(), rng.next_u32());
+        }
+    }
+
+    #[test]
+    fn split_generators_are_independent_but_reproducible() {
+        let mut rng: GameRng = GameRng::new(99);
+        let mut child0: GameRng = rng.split();
+        let mut child1: GameRng = rng.split();
+        assert_ne!(child0.next_u64(), child1.next_u64());
+
+        let mut rng: GameRng = GameRng::new(99);
+        let mut child0_again: GameRng = rng.split();
+        let mut child0: GameRng = GameRng::new(99).split();
+        assert_eq!(child0_again.next_u64(), child0.next_u64());
+    }
+
+    #[test]
+    fn below_stays_in_bounds_and_covers_them() {
+        let mut rng: GameRng = GameRng::new(3);
+        let mut seen: [bool; 6] = [false; 6];
+
+        for _ in 0..1000 {
+            let roll: u32 = rng.below(6);
+            assert!(roll < 6);
+            seen[roll as usize] = true;
+        }
+        assert!(seen.iter().all(|&seen| seen));
+    }
+
+    #[test]
+    fn shuffle_is_a_reproducible_permutation() {
+        let mut items: Vec<u32> = (0..52).collect();
+        GameRng::new(5).shuffle(&mut items);
+        let mut again: Vec<u32> = (0..52).collect();
+        GameRng::new(5).shuffle(&mut again);
+
+        assert_eq!(items, again);
+        assert_ne!(items, (0..52).collect::<Vec<u32>>());
+        items.sort();
+        assert_eq!(items, (0..52).collect::<Vec<u32>>());
+    }
+}
@@ -11,15 +11,373 @@
 // that used in the game Baba is You, developed by Arvi Teikari.  You
 // should play it https://store.steampowered.com/app/736260/Baba_Is_You/
 
+use std::collections::HashSet;
+use std::rc::Rc;
+
 use crate::coordinate;
 
-/// The primary interface for querying and updating the game state
+pub mod bitboard;
+pub mod history;
+pub mod layers;
+pub mod solver;
+
+use layers::{EntityKind, Layers};
+
+pub(crate) const DIRECTIONS: [coordinate::Direction; 4] = [
+    coordinate::Direction::Up,
+    coordinate::Direction::Left,
+    coordinate::Direction::Down,
+    coordinate::Direction::Right,
+];
+
+/// Something that happened as the result of a move
+///
+/// These are meant for the renderer, so it can show the player what
+/// changed beyond things just sliding around.
+#[derive(Debug, Clone, PartialEq)]
+pub enum Event {
+    /// A push with limited durability was pushed to `at`, and can only
+    /// be pushed `remaining` more times
+    ///
+    /// Once `remaining` is zero, the push is gone and has been dealt
+    /// with as the board's [`Breakage`] says.
+    Worn { at: coordinate::I2, remaining: u32 },
+    /// A cracked floor tile at `at` was walked off of and broke,
+    /// leaving a stop in its place
+    Cracked { at: coordinate::I2 },
+    /// You, your partner, or a push stepped onto a teleporter at `from`
+    /// and came out of its twin at `to`
+    Teleported {
+        from: coordinate::I2,
+        to: coordinate::I2,
+    },
+    /// A magnet pulled a push from `from` one tile over to `to`
+    Pulled {
+        from: coordinate::I2,
+        to: coordinate::I2,
+    },
+    /// You, your partner, or a clone stepped onto `item` and picked it
+    /// up
+    PickedUp { item: Item },
+    /// A mergeable push was pushed into another at `at`, and the two
+    /// are now one push made of `size` pushes, colored `color`
+    Merged {
+        at: coordinate::I2,
+        size: u32,
+        color: Option<u32>,
+    },
+    /// A merged push was pushed onto a splitter at `from`, and one of
+    /// the pushes it's made of came out of it at `to`
+    Split {
+        from: coordinate::I2,
+        to: coordinate::I2,
+    },
+    /// A push that was pushed onto ice at `from` slid across it to `to`
+    Slid {
+        from: coordinate::I2,
+        to: coordinate::I2,
+    },
+}
+
+/// Something that happened during a turn, for keeping track of how a
+/// game is going
+///
+/// Unlike [`Event`]s, these are about the plain rules of the game, so
+/// analytics and achievements can follow along without working them
+/// out from the board.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum TurnEvent {
+    /// A push was shoved from `from` to `to`
+    ///
+    /// `to` is where the shove put it, before it slid over ice or went
+    /// through a teleporter.
+    Pushed {
+        from: coordinate::I2,
+        to: coordinate::I2,
+    },
+    /// The target at `at` went from empty to having a push on it
+    Triggered { at: coordinate::I2 },
+    /// The target at `at` had its push taken off of it
+    Untriggered { at: coordinate::I2 },
+    /// Moving toward `direction` didn't go anywhere
+    Blocked { direction: coordinate::Direction },
+}
+
+/// How a move went, in broad strokes
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum MoveOutcome {
+    /// The avatar stepped onto an empty tile
+    Moved,
+    /// The avatar shoved a line of this many pushes along with it
+    Pushed(usize),
+    /// Something was in the way, so nothing changed
+    Blocked,
+    /// The move triggered the last of the targets
+    Won,
+    /// An enemy caught up with you after the move
+    Lost,
+    /// The move used up the last of the board's moves without winning
+    Failed,
+    /// The board was already won, lost, or failed, so nothing changed
+    GameOver,
+}
+
+/// A running tally of how a game on a board has gone
+///
+/// Unlike the board itself, this isn't taken back by
+/// [`Sokoban::undo`], [`Sokoban::rewind`], or [`Sokoban::reset`];
+/// they're counted instead.
+#[derive(Debug, Clone, Copy, Default, PartialEq)]
+pub struct GameStats {
+    /// How many moves went somewhere, by anyone
+    pub moves: u32,
+    /// How many pushes have been shoved along, counting each push in a
+    /// line
+    pub pushes: u32,
+    /// How many moves have been undone
+    pub undos: u32,
+    /// How many times the board has been rewound, however far
+    pub rewinds: u32,
+    /// How many times the board has been reset
+    pub resets: u32,
+    /// How many moves have been tried, including ones that were blocked
+    pub turns: u32,
+    /// How long the game has been played for, as counted by
+    /// [`Sokoban::tick`]
+    pub elapsed: std::time::Duration,
+}
+
+/// A board saved by [`Sokoban::snapshot`] to go back to with
+/// [`Sokoban::restore`]
+///
+/// Only what moves can change is saved, and none of the moves that
+/// could be undone, so a snapshot is no bigger late in a game than
+/// early on.  Taking one is about as cheap as a move: whatever layers
+/// the board shares with the boards before it are shared with the
+/// snapshot too, rather than copied.
+#[derive(Debug, Clone)]
+pub struct Snapshot {
+    you: coordinate::I2,
+    layers: Layers,
+    partner: Option<coordinate::I2>,
+    clones: coordinate::I2Array,
+    durability: Vec<(coordinate::I2, u32)>,
+    colors: Vec<(coordinate::I2, u32)>,
+    sizes: Vec<(coordinate::I2, u32)>,
+    triggered: usize,
+    enemies: Vec<Enemy>,
+    items: Vec<Item>,
+    collected: Vec<Item>,
+    moves: u32,
+    history: Option<Vec<coordinate::Direction>>,
+}
+
+/// What becomes of a push that has been pushed as much as it can be
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum Breakage {
+    /// It turns into a stop where it is
+    Stop,
+    /// It's taken off the board entirely
+    Remove,
+}
+
+/// What mergeable pushes of each color merge into, like 2048, where
+/// two Twos make a Four
+///
+/// Under these rules, a mergeable push only merges into one of the
+/// same color, and only if that color is in the table.
+/// Pushes of any other color, or of none, are shoved as usual.
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct MergeRules {
+    /// Each color that merges, paired with the color it merges into
+    pub table: Vec<(u32, u32)>,
+}
+
+impl MergeRules {
+    /// The color two pushes of `color` merge into, if they merge
+    pub fn merged_color(&self, color: u32) -> Option<u32> {
+        self.table
+            .iter()
+            .find(|(from, _)| *from == color)
+            .map(|(_, to)| *to)
+    }
+}
+
+/// A pressure switch and the walls it opens while held down
+#[derive(Debug, Clone, PartialEq)]
+pub struct Switch {
+    /// Where the switch is
+    pub at: coordinate::I2,
+    /// The walls that are open for as long as something is on the switch
+    pub walls: coordinate::I2Array,
+}
+
+/// A magnet that pulls pushes in front of it toward itself
+#[derive(Debug, Clone, PartialEq)]
+pub struct Magnet {
+    /// Where the magnet is
+    pub at: coordinate::I2,
+    /// The way the magnet faces, which is the way it looks for pushes
+    pub facing: coordinate::Direction,
+}
+
+/// Something lying on the floor, underneath everything else
+///
+/// Items are on a layer of their own, so they never block anything or
+/// get in the way.  Pushes slide over them and can sit on top of them
+/// without harming them, and whatever's on top of an item hides it
+/// without taking its place.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct Item {
+    /// Where the item is
+    pub at: coordinate::I2,
+    /// What sort of item it is, which is up to the game to decide
+    pub kind: u32,
+    /// Whether stepping onto the item picks it up, or else it's only
+    /// there to look at, like a decal
+    pub collectible: bool,
+}
+
+/// A push that's been taken off of one board to be put on another,
+/// with everything about it besides where it is
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub(crate) struct LoosePush {
+    durability: Option<u32>,
+    color: Option<u32>,
+    heavy: bool,
+    sticky: bool,
+    mergeable: bool,
+    size: Option<u32>,
+}
+
+/// Something that roams the board, trying to catch you
+#[derive(Debug, Clone, PartialEq)]
+pub struct Enemy {
+    /// Where the enemy is
+    pub at: coordinate::I2,
+    /// How the enemy gets around
+    pub movement: Movement,
+}
+
+/// The way an [`Enemy`] takes its step after each of your moves
 #[derive(Debug, Clone, PartialEq)]
+pub enum Movement {
+    /// Step toward you along whichever axis you're further away on,
+    /// falling back to the other axis if that's blocked
+    Chase,
+    /// Walk the `route` over and over, where `next` is the index of the
+    /// step to take next
+    ///
+    /// Blocked steps are skipped rather than waited out.
+    Patrol {
+        route: Vec<coordinate::Direction>,
+        next: usize,
+    },
+}
+
+/// Whether a board can be won, as far as could be told
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum Solvability {
+    /// There's a way to trigger all the targets
+    Solvable,
+    /// There's no way to trigger all the targets
+    Unsolvable,
+    /// It couldn't be worked out either way in time
+    Unknown,
+}
+
+/// Why a sequence of moves couldn't be played back on a board
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum ReplayError {
+    /// The move at this index into the sequence was blocked
+    Blocked(usize),
+}
+
+/// Why a board couldn't be made out of what it was given
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum SokobanError {
+    /// You're on a stop
+    YouOnStop,
+    /// You're on the push at this position
+    YouOnPush(coordinate::I2),
+    /// The push at this position is on a stop
+    PushOnStop(coordinate::I2),
+    /// There's more than one push at this position
+    DuplicatePush(coordinate::I2),
+}
+
+/// The kind of thing found at a cell of the board
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum Entity {
+    /// Icy floor that pushes slide over
+    Ice,
+    /// Cracked floor that breaks once walked off of
+    Crack,
+    /// A pressure switch
+    Switch,
+    /// One end of a pair of teleporters
+    Teleporter,
+    /// An item lying on the floor, of the given kind
+    Item(u32),
+    /// Stopping collision
+    Stop,
+    /// A switch wall that's closed
+    Wall,
+    /// A magnet, facing the given way
+    Magnet(coordinate::Direction),
+    /// A target with no push on it, or the wrong color of push
+    Target,
+    /// A pushable object
+    Push,
+    /// A target with a push of its color on it
+    TriggeredTarget,
+    /// Something trying to catch you
+    Enemy,
+    /// A copy of you that moves when you do
+    Clone,
+    /// The second player
+    Partner,
+    /// The player
+    You,
+}
+
+/// The primary interface for querying and updating the game state
+///
+/// Everything that's only ever somewhere, like stops, pushes, and
+/// targets, is kept in [`Layers`] by its [`EntityKind`].  Layers that
+/// rarely change from one move to the next are shared between a board
+/// and the boards made from it until one of them needs to change them.
+#[derive(Debug, Clone)]
 pub struct Sokoban {
     you: coordinate::I2,
-    stops: coordinate::I2Array,
-    pushes: coordinate::I2Array,
-    targets: coordinate::I2Array,
+    layers: Layers,
+    partner: Option<coordinate::I2>,
+    clones: coordinate::I2Array,
+    costs: Vec<(coordinate::I2, u32)>,
+    durability: Vec<(coordinate::I2, u32)>,
+    colors: Vec<(coordinate::I2, u32)>,
+    sizes: Vec<(coordinate::I2, u32)>,
+    target_colors: Vec<(coordinate::I2, u32)>,
+    triggered: usize,
+    breakage: Breakage,
+    push_limit: Option<usize>,
+    merge_rules: Option<MergeRules>,
+    switches: Vec<Switch>,
+    magnets: Vec<Magnet>,
+    teleporters: Vec<(coordinate::I2, coordinate::I2)>,
+    enemies: Vec<Enemy>,
+    items: Vec<Item>,
+    collected: Vec<Item>,
+    move_limit: Option<u32>,
+    moves: u32,
+    stats: GameStats,
+    par: Option<u32>,
+    rewind_depth: usize,
+    rewinds_left: u32,
+    history: Option<Vec<coordinate::Direction>>,
+    events: Vec<Event>,
+    turn_events: Vec<TurnEvent>,
+    previous: Option<Rc<Sokoban>>,
 }
 
 impl Sokoban {
@@ -27,7 +385,9 @@ impl Sokoban {
     ///
     /// In cases where a `coordinate::I2` is used, the first value is
     /// understood as the horizontal coordinate to the right, and the
-    /// second value is the vertical coordinate down.
+    /// second value is the vertical coordinate down.  A coordinate
+    /// listed more than once in `stops`, `pushes`, or `targets` is only
+    /// kept the first time.
     ///
     /// # Examples
     ///
@@ -92,12 +452,106 @@ impl Sokoban {
         pushes: coordinate::I2Array,
         targets: coordinate::I2Array,
     ) -> Self {
-        Sokoban {
+        // repeats would be counted twice over when checking for a win
+        let without_repeats = |cells: coordinate::I2Array| -> coordinate::I2Array {
+            let mut seen: HashSet<coordinate::I2> = HashSet::new();
+            cells
+                .iter()
+                .filter(|cell| seen.insert(**cell))
+                .copied()
+                .collect()
+        };
+        let mut layers: Layers = Layers::new();
+        layers.set(EntityKind::Stop, without_repeats(stops));
+        layers.set(EntityKind::Push, without_repeats(pushes));
+        layers.set(EntityKind::Target, without_repeats(targets));
+        let mut board: Sokoban = Sokoban {
             you,
-            stops,
-            pushes,
-            targets,
+            layers,
+            partner: None,
+            clones: coordinate::I2Array::from(vec![]),
+            costs: vec![],
+            durability: vec![],
+            colors: vec![],
+            sizes: vec![],
+            target_colors: vec![],
+            triggered: 0,
+            breakage: Breakage::Stop,
+            push_limit: None,
+            merge_rules: None,
+            switches: vec![],
+            magnets: vec![],
+            teleporters: vec![],
+            enemies: vec![],
+            items: vec![],
+            collected: vec![],
+            move_limit: None,
+            moves: 0,
+            stats: GameStats::default(),
+            par: None,
+            rewind_depth: 0,
+            rewinds_left: 0,
+            history: None,
+            events: vec![],
+            turn_events: vec![],
+            previous: None,
+        };
+        board.triggered = board.count_triggered();
+        board
+    }
+
+    /// Create a board like [`Sokoban::new`], checking that nothing is
+    /// on top of anything it can't share a tile with
+    ///
+    /// That's you or a push on a stop, you on a push, or two pushes on
+    /// the same tile, none of which make sense to play.  The first
+    /// problem found is the error.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// let stops: coordinate::I2Array = coordinate::I2Array::from(vec![[2, 0]]);
+    /// let targets: coordinate::I2Array = coordinate::I2Array::from(vec![]);
+    ///
+    /// assert_eq!(
+    ///     Sokoban::try_new(
+    ///         coordinate::I2::new(0, 0),
+    ///         stops.clone(),
+    ///         coordinate::I2Array::from(vec![[1, 0], [1, 0]]),
+    ///         targets.clone(),
+    ///     ),
+    ///     Err(SokobanError::DuplicatePush(coordinate::I2::new(1, 0)))
+    /// );
+    /// assert!(Sokoban::try_new(
+    ///     coordinate::I2::new(0, 0),
+    ///     stops,
+    ///     coordinate::I2Array::from(vec![[1, 0]]),
+    ///     targets,
+    /// )
+    /// .is_ok());
+    /// ```
+    pub fn try_new(
+        you: coordinate::I2,
+        stops: coordinate::I2Array,
+        pushes: coordinate::I2Array,
+        targets: coordinate::I2Array,
+    ) -> Result<Self, SokobanError> {
+        if stops.contains(&you) {
+            return Err(SokobanError::YouOnStop);
+        }
+        let mut seen: HashSet<coordinate::I2> = HashSet::new();
+        for push in pushes.iter() {
+            if *push == you {
+                return Err(SokobanError::YouOnPush(*push));
+            }
+            if stops.contains(push) {
+                return Err(SokobanError::PushOnStop(*push));
+            }
+            if !seen.insert(*push) {
+                return Err(SokobanError::DuplicatePush(*push));
+            }
         }
+        Ok(Sokoban::new(you, stops, pushes, targets))
     }
 
     /// Move the player one tile over toward direction
@@ -169,671 +623,5051 @@ impl Sokoban {
     /// #     .all_targets_triggered());
     /// ```
     pub fn you_move(&self, direction: coordinate::Direction) -> Sokoban {
-        let mut moving_pushes: coordinate::I2Array = coordinate::I2Array::from(vec![]);
-        for i in 1.. {
-            let test_coordinate: Option<coordinate::I2> = self.you.nudge_by(i, direction);
-            if test_coordinate.is_none() || self.stops.contains(&test_coordinate.unwrap()) {
-                return Sokoban::new(
-                    self.you,
-                    self.stops.clone(),
-                    self.pushes.clone(),
-                    self.targets.clone(),
-                );
-            }
-
-            let test_coordinate: coordinate::I2 = test_coordinate.unwrap();
-
-            if self.pushes.contains(&test_coordinate) {
-                moving_pushes.push(test_coordinate);
-            } else {
-                break;
-            }
-        }
-
-        let new_you: coordinate::I2 = self.you.nudge(direction).unwrap();
-        let new_pushes: coordinate::I2Array = self
-            .pushes
-            .iter()
-            .map(|push| {
-                if moving_pushes.contains(push) {
-                    push.nudge(direction).unwrap()
-                } else {
-                    *push
-                }
-            })
-            .collect();
-
-        Sokoban::new(
-            new_you,
-            self.stops.clone(),
-            new_pushes,
-            self.targets.clone(),
-        )
+        self.you_move_outcome(direction).0
     }
 
-    /// The positions of all the targets that have a push on them
+    /// Move the player toward `direction` for as long as there's
+    /// nothing in the way, stopping short of pushing anything
+    ///
+    /// The whole dash is undone at once by [`Sokoban::undo`].  Should
+    /// teleporters carry you around in a loop, you stop once you'd come
+    /// back to somewhere you've already been.
     ///
     /// # Examples
     ///
     /// ```
-    /// # // Let's create this board, where @: you, 0: push, -|: stop, and ^: target
-    /// # //
-    /// # //   ---
-    /// # //   |^|
-    /// # //   | ----
-    /// # // ---0 0^|
-    /// # // |^ 0@---
-    /// # // ----0|
-    /// # //    |^|
-    /// # //    ---
-    /// #
-    /// # let you: coordinate::I2 = [4, 4];
-    /// # let stops: coordinate::I2Array = coordinate::I2Array::from(vec![
-    /// #     [2, 0], [3, 0], [4, 0], [2, 1], [4, 1], [2, 2], [4, 2],
-    /// #     [5, 2], [6, 2], [7, 2], [0, 3], [1, 3], [2, 3], [7, 3],
-    /// #     [0, 4], [5, 4], [6, 4], [7, 4], [0, 5], [1, 5], [2, 5],
-    /// #     [3, 5], [5, 5], [3, 6], [5, 6], [3, 7], [4, 7], [5, 7],
-    /// # ]);
-    /// # let pushes: coordinate::I2Array = coordinate::I2Array::from(vec![[3, 3], [5, 3], [3, 4], [4, 5]]);
-    /// let targets: coordinate::I2Array = coordinate::I2Array::from(vec![[3, 1], [6, 3], [1, 4], [4, 6]]);
-    /// // ...
-    /// #
-    /// let board: Sokoban = Sokoban::new(you, stops.clone(), pushes.clone(), targets.clone());
+    /// let board: Sokoban = "@   0^".parse().unwrap();
     ///
-    /// # assert_eq!(
-    /// #     board.you_move(coordinate::Direction::Up),
-    /// #     Sokoban::new([4, 3], stops, pushes, targets)
-    /// # );
-    /// #
     /// assert_eq!(
-    ///     board
-    ///         .you_move(coordinate::Direction::Down)
-    ///         .you_move(coordinate::Direction::Up)
-    ///         .triggered_targets(),
-    ///     vec![&[4, 6]]
+    ///     board.you_dash(coordinate::Direction::Right).you(),
+    ///     coordinate::I2::new(3, 0)
     /// );
-    /// #
-    /// # assert!(!board
-    /// #     .you_move(coordinate::Direction::Down)
-    /// #     .you_move(coordinate::Direction::Up)
-    /// #     .all_targets_triggered());
-    /// #
-    /// # assert!(board
-    /// #     .you_move(coordinate::Direction::Down)
-    /// #     .you_move(coordinate::Direction::Up)
-    /// #     .you_move(coordinate::Direction::Left)
-    /// #     .you_move(coordinate::Direction::Left)
-    /// #     .you_move(coordinate::Direction::Right)
-    /// #     .you_move(coordinate::Direction::Up)
-    /// #     .you_move(coordinate::Direction::Up)
-    /// #     .you_move(coordinate::Direction::Down)
-    /// #     .you_move(coordinate::Direction::Right)
-    /// #     .you_move(coordinate::Direction::Right)
-    /// #     .all_targets_triggered());
     /// ```
-    pub fn triggered_targets(&self) -> Vec<&coordinate::I2> {
-        self.targets
-            .iter()
-            .filter(|target| self.pushes.contains(target))
-            .collect::<Vec<&coordinate::I2>>()
+    pub fn you_dash(&self, direction: coordinate::Direction) -> Sokoban {
+        let mut board: Sokoban = self.clone();
+        let mut visited: HashSet<coordinate::I2> = HashSet::from([self.you]);
+        let mut moved: bool = false;
+        while board.can_move(direction) && !board.can_push(direction) {
+            let outcome: MoveOutcome = board.you_move_mut(direction);
+            moved |= !matches!(outcome, MoveOutcome::Blocked | MoveOutcome::GameOver);
+            if outcome != MoveOutcome::Moved || !visited.insert(board.you) {
+                break;
+            }
+        }
+        if moved {
+            board.previous = Some(Rc::new(self.stay()));
+        }
+        board
     }
 
-    /// Checks if all the targets have been triggered
+    /// Checks if [`Sokoban::you_move`] toward `direction` would move you
     ///
-    /// # Examples
+    /// This doesn't build the board you'd end up with, so it's cheap
+    /// enough to call every frame.  That is, unless you have clones,
+    /// who could be in each other's way.
+    pub fn can_move(&self, direction: coordinate::Direction) -> bool {
+        if self.is_over() {
+            return false;
+        }
+        if self.clones.iter().next().is_some() {
+            return self.you_move_outcome(direction).1 != MoveOutcome::Blocked;
+        }
+        self.moving_pushes(self.you, &Vec::from_iter(self.partner), direction)
+            .is_some()
+    }
+
+    /// Checks if [`Sokoban::you_move`] toward `direction` would move
+    /// you and push at least one push along with you
+    pub fn can_push(&self, direction: coordinate::Direction) -> bool {
+        if self.is_over() {
+            return false;
+        }
+        if self.clones.iter().next().is_some() {
+            return matches!(
+                self.you_move_outcome(direction).1,
+                MoveOutcome::Pushed(_) | MoveOutcome::Won
+            );
+        }
+        self.moving_pushes(self.you, &Vec::from_iter(self.partner), direction)
+            .is_some_and(|pushes| pushes.iter().next().is_some())
+    }
+
+    /// Checks if you can't move anywhere at all, being walled in by
+    /// stops and pushes that can't be pushed
     ///
-    /// ```
-    /// # // Let's create this board, where @: you, 0: push, -|: stop, and ^: target
-    /// # //
-    /// //   ---
-    /// //   |^|
-    /// //   | ----
-    /// // ---0 0^|
-    /// // |^ 0@---
-    /// // ----0|
-    /// //    |^|
-    /// //    ---
+    /// Boards that are won, lost, or failed don't take moves anyway, so
+    /// they don't count as stuck.  Your partner might still be able to
+    /// get you out, but they aren't taken into account.
     ///
-    /// # let you: coordinate::I2 = [4, 4];
-    /// # let stops: coordinate::I2Array = coordinate::I2Array::from(vec![
-    /// #     [2, 0], [3, 0], [4, 0], [2, 1], [4, 1], [2, 2], [4, 2],
-    /// #     [5, 2], [6, 2], [7, 2], [0, 3], [1, 3], [2, 3], [7, 3],
-    /// #     [0, 4], [5, 4], [6, 4], [7, 4], [0, 5], [1, 5], [2, 5],
-    /// #     [3, 5], [5, 5], [3, 6], [5, 6], [3, 7], [4, 7], [5, 7],
-    /// # ]);
-    /// # let pushes: coordinate::I2Array = coordinate::I2Array::from(vec![[3, 3], [5, 3], [3, 4], [4, 5]]);
-    /// # let targets: coordinate::I2Array = coordinate::I2Array::from(vec![[3, 1], [6, 3], [1, 4], [4, 6]]);
-    /// #
-    /// let board: Sokoban = Sokoban::new(you, stops.clone(), pushes.clone(), targets.clone());
+    /// # Examples
     ///
-    /// # assert_eq!(
-    /// #     board.you_move(coordinate::Direction::Up),
-    /// #     Sokoban::new([4, 3], stops, pushes, targets)
-    /// # );
-    /// #
-    /// # assert_eq!(
-    /// #     board
-    /// #         .you_move(coordinate::Direction::Down)
-    /// #         .you_move(coordinate::Direction::Up)
-    /// #         .triggered_targets(),
-    /// #     vec![&[4, 6]]
-    /// # );
-    /// #
-    /// assert!(!board
-    ///     .you_move(coordinate::Direction::Down)
-    ///     .you_move(coordinate::Direction::Up)
-    ///     .all_targets_triggered());
+    /// ```
+    /// let board: Sokoban = " -\n|@0-\n -".parse().unwrap();
     ///
-    /// assert!(board
-    ///     .you_move(coordinate::Direction::Down)
-    ///     .you_move(coordinate::Direction::Up)
-    ///     .you_move(coordinate::Direction::Left)
-    ///     .you_move(coordinate::Direction::Left)
-    ///     .you_move(coordinate::Direction::Right)
-    ///     .you_move(coordinate::Direction::Up)
-    ///     .you_move(coordinate::Direction::Up)
-    ///     .you_move(coordinate::Direction::Down)
-    ///     .you_move(coordinate::Direction::Right)
-    ///     .you_move(coordinate::Direction::Right)
-    ///     .all_targets_triggered());
+    /// assert!(board.is_stuck());
     /// ```
-    pub fn all_targets_triggered(&self) -> bool {
-        self.targets
-            .iter()
-            .all(|target| self.pushes.contains(target))
+    pub fn is_stuck(&self) -> bool {
+        !self.is_over() && !DIRECTIONS.iter().any(|direction| self.can_move(*direction))
     }
 
-    /// Gets the position of the player
-    pub fn you(&self) -> coordinate::I2 {
-        self.you
+    /// Move the player like [`Sokoban::you_move`], also saying how it went
+    pub fn you_move_outcome(&self, direction: coordinate::Direction) -> (Sokoban, MoveOutcome) {
+        let mut board: Sokoban = self.clone();
+        let outcome: MoveOutcome = board.you_move_mut(direction);
+        (board, outcome)
     }
 
-    /// Gets the positions of all the stopping collision
-    pub fn stops(&self) -> coordinate::I2Array {
-        self.stops.clone()
-    }
+    /// Move the player like [`Sokoban::you_move`], but change this
+    /// board rather than making a new one
+    ///
+    /// This saves copying the board more than the once it takes to be
+    /// able to [`Sokoban::undo`] the move, which adds up when there's
+    /// only ever one board being played on.
+    pub fn you_move_mut(&mut self, direction: coordinate::Direction) -> MoveOutcome {
+        self.events.clear();
+        self.turn_events.clear();
+        self.stats.turns = self.stats.turns.saturating_add(1);
+        if self.is_over() {
+            return MoveOutcome::GameOver;
+        }
+        let mut yous: Vec<coordinate::I2> = std::iter::once(self.you)
+            .chain(self.clones.iter().copied())
+            .collect();
+        // whoever is furthest along goes first, so the ones behind them
+        // can follow into the tile they left
+        let along = |cell: &coordinate::I2| -> i64 {
+            match direction {
+                coordinate::Direction::Up => -(cell.y() as i64),
+                coordinate::Direction::Left => -(cell.x() as i64),
+                coordinate::Direction::Down => cell.y() as i64,
+                coordinate::Direction::Right => cell.x() as i64,
+            }
+        };
+        let mut order: Vec<usize> = (0..yous.len()).collect();
+        order.sort_by_key(|i| std::cmp::Reverse(along(&yous[*i])));
 
-    /// Gets the positions of all the pushable objects
-    pub fn pushes(&self) -> coordinate::I2Array {
-        self.pushes.clone()
+        let before: Sokoban = self.clone();
+        let mut pushed: Option<usize> = None;
+        for i in order {
+            let obstacles: Vec<coordinate::I2> = yous
+                .iter()
+                .enumerate()
+                .filter(|(j, _)| *j != i)
+                .map(|(_, other)| *other)
+                .chain(self.partner)
+                .collect();
+            if let Some((to, count)) = self.step(yous[i], &obstacles, direction) {
+                yous[i] = to;
+                pushed = Some(pushed.unwrap_or(0) + count);
+            }
+        }
+        let Some(pushed) = pushed else {
+            self.turn_events.push(TurnEvent::Blocked { direction });
+            return MoveOutcome::Blocked;
+        };
+
+        self.you = yous[0];
+        self.clones = yous[1..].iter().copied().collect();
+        self.moves = self.moves.saturating_add(self.cost(self.you));
+        self.count_move(pushed);
+        if let Some(history) = self.history.as_mut() {
+            history.push(direction);
+        }
+        self.attract();
+        self.hunt();
+        self.note_retriggered(&before);
+        let outcome: MoveOutcome = before.outcome(self, pushed);
+        self.previous = Some(Rc::new(before));
+        outcome
     }
 
-    /// Gets the positions of all the targets for the pushable objects
-    pub fn targets(&self) -> coordinate::I2Array {
-        self.targets.clone()
+    /// Move the second player like [`Sokoban::partner_move`], also
+    /// saying how it went
+    pub fn partner_move_outcome(&self, direction: coordinate::Direction) -> (Sokoban, MoveOutcome) {
+        let mut board: Sokoban = self.clone();
+        let outcome: MoveOutcome = board.partner_move_mut(direction);
+        (board, outcome)
     }
-}
 
-#[cfg(test)]
-mod tests {
-    use super::*;
+    /// Move the second player like [`Sokoban::partner_move`], but
+    /// change this board rather than making a new one
+    pub fn partner_move_mut(&mut self, direction: coordinate::Direction) -> MoveOutcome {
+        self.events.clear();
+        self.turn_events.clear();
+        self.stats.turns = self.stats.turns.saturating_add(1);
+        if self.is_over() {
+            return MoveOutcome::GameOver;
+        }
+        let Some(partner) = self.partner else {
+            self.turn_events.push(TurnEvent::Blocked { direction });
+            return MoveOutcome::Blocked;
+        };
+        let yous: Vec<coordinate::I2> = std::iter::once(self.you)
+            .chain(self.clones.iter().copied())
+            .collect();
 
-    #[test]
-    fn you_move_in_all_cardinal_directions_directions() {
-        // .....
-        // .@.0^
-        // ..+..
-        // .0.+.
-        // .^...
-        let you: coordinate::I2 = coordinate::I2::new(1, 1);
-        let stops: coordinate::I2Array = coordinate::I2Array::from(vec![[2, 2], [3, 3]]);
-        let pushes: coordinate::I2Array = coordinate::I2Array::from(vec![[3, 1], [1, 3]]);
-        let targets: coordinate::I2Array = coordinate::I2Array::from(vec![[4, 1], [1, 4]]);
+        let before: Sokoban = self.clone();
+        let Some((partner, pushed)) = self.step(partner, &yous, direction) else {
+            self.turn_events.push(TurnEvent::Blocked { direction });
+            return MoveOutcome::Blocked;
+        };
+        self.partner = Some(partner);
+        self.moves = self.moves.saturating_add(self.cost(partner));
+        self.count_move(pushed);
+        self.attract();
+        self.hunt();
+        self.note_retriggered(&before);
+        let outcome: MoveOutcome = before.outcome(self, pushed);
+        self.previous = Some(Rc::new(before));
+        outcome
+    }
+
+    /// Make each of your `moves` in turn, like a recorded
+    /// [`Sokoban::history`]
+    ///
+    /// Every move has to go somewhere, so a sequence that's blocked
+    /// partway, including by the game being over, is an error giving
+    /// the index of the move that was blocked.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// let board: Sokoban = "@0 ^".parse().unwrap();
+    /// let right: coordinate::Direction = coordinate::Direction::Right;
+    ///
+    /// assert!(board.apply_moves(&[right, right]).unwrap().all_targets_triggered());
+    /// assert_eq!(board.apply_moves(&[right, right, right]), Err(ReplayError::Blocked(2)));
+    /// ```
+    pub fn apply_moves(&self, moves: &[coordinate::Direction]) -> Result<Sokoban, ReplayError> {
+        let mut board: Sokoban = self.clone();
+        for (index, direction) in moves.iter().enumerate() {
+            if matches!(
+                board.you_move_mut(*direction),
+                MoveOutcome::Blocked | MoveOutcome::GameOver
+            ) {
+                return Err(ReplayError::Blocked(index));
+            }
+        }
+        Ok(board)
+    }
+
+    /// Take back the last move that changed the board
+    ///
+    /// Moves that were blocked aren't remembered, so they're skipped
+    /// over.  Undoing back past the start of the board does nothing.
+    pub fn undo(&self) -> Sokoban {
+        match &self.previous {
+            Some(previous) => Sokoban {
+                events: vec![],
+                turn_events: vec![],
+                stats: GameStats {
+                    undos: self.stats.undos.saturating_add(1),
+                    ..self.stats
+                },
+                rewinds_left: self.rewinds_left,
+                ..Sokoban::clone(previous)
+            },
+            None => self.stay(),
+        }
+    }
+
+    /// Take back every move that can be undone, going back to the
+    /// start of the board
+    pub fn reset(&self) -> Sokoban {
+        let mut start: &Sokoban = self;
+        while let Some(previous) = &start.previous {
+            start = previous;
+        }
+        Sokoban {
+            events: vec![],
+            turn_events: vec![],
+            stats: GameStats {
+                resets: self.stats.resets.saturating_add(1),
+                ..self.stats
+            },
+            rewinds_left: self.rewinds_left,
+            ..start.clone()
+        }
+    }
+
+    /// Let the board be rewound `uses` times, each time by up to `depth`
+    /// moves
+    ///
+    /// This is a power for game modes to hand out, separate from
+    /// [`Sokoban::undo`], which is always there.  Rewinds that have been
+    /// used aren't given back by undoing or resetting.
+    pub fn with_rewinds(self, uses: u32, depth: usize) -> Sokoban {
+        Sokoban {
+            rewind_depth: depth,
+            rewinds_left: uses,
+            ..self
+        }
+    }
+
+    /// Take back the last `moves` moves all at once, using up one of the
+    /// board's rewinds
+    ///
+    /// No more than the depth given to [`Sokoban::with_rewinds`] are
+    /// taken back, nor any before the start of the board.  Should there
+    /// be no rewinds left, a depth of zero, or nothing to take back,
+    /// this does nothing and no rewind is used.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// let board: Sokoban = "@...".parse::<Sokoban>().unwrap().with_rewinds(1, 2);
+    /// let right: coordinate::Direction = coordinate::Direction::Right;
+    /// let walked: Sokoban = board.apply_moves(&[right, right, right]).unwrap();
+    ///
+    /// assert_eq!(walked.rewind(5).you(), coordinate::I2::new(1, 0));
+    /// assert_eq!(walked.rewind(5).rewind(5), walked.rewind(5));
+    /// ```
+    pub fn rewind(&self, moves: usize) -> Sokoban {
+        if self.rewinds_left == 0 || self.rewind_depth == 0 || moves == 0 || self.previous.is_none()
+        {
+            return self.stay();
+        }
+        let mut start: &Sokoban = self;
+        for _ in 0..moves.min(self.rewind_depth) {
+            match &start.previous {
+                Some(previous) => start = previous,
+                None => break,
+            }
+        }
+        Sokoban {
+            events: vec![],
+            turn_events: vec![],
+            stats: GameStats {
+                rewinds: self.stats.rewinds.saturating_add(1),
+                ..self.stats
+            },
+            rewinds_left: self.rewinds_left - 1,
+            ..start.clone()
+        }
+    }
+
+    /// Gets how many more times the board can be [`Sokoban::rewind`]ed
+    pub fn rewinds_left(&self) -> u32 {
+        self.rewinds_left
+    }
+
+    /// Save the board as it is, to [`Sokoban::restore`] later
+    ///
+    /// This is for checkpoints, like one each time a target is
+    /// triggered, that can be gone back to however far the game has
+    /// gone since.
+    pub fn snapshot(&self) -> Snapshot {
+        Snapshot {
+            you: self.you,
+            layers: self.layers.clone(),
+            partner: self.partner,
+            clones: self.clones.clone(),
+            durability: self.durability.clone(),
+            colors: self.colors.clone(),
+            sizes: self.sizes.clone(),
+            triggered: self.triggered,
+            enemies: self.enemies.clone(),
+            items: self.items.clone(),
+            collected: self.collected.clone(),
+            moves: self.moves,
+            history: self.history.clone(),
+        }
+    }
+
+    /// Go back to the board as it was when `snapshot` was taken
+    ///
+    /// Restoring can be undone like a move, going back to this board.
+    /// The moves that could be undone when the snapshot was taken
+    /// weren't saved with it, so they can't be.  Like with
+    /// [`Sokoban::undo`], the [`GameStats`] and rewinds left carry on
+    /// from this board rather than the snapshot.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// let board: Sokoban = "@0^.".parse().unwrap();
+    /// let right: coordinate::Direction = coordinate::Direction::Right;
+    /// let checkpoint: Snapshot = board.you_move(right).snapshot();
+    /// let restored: Sokoban = board.apply_moves(&[right, right]).unwrap().restore(checkpoint);
+    ///
+    /// assert_eq!(restored, board.you_move(right));
+    /// assert_eq!(restored.stats().moves, 2);
+    /// ```
+    pub fn restore(&self, snapshot: Snapshot) -> Sokoban {
+        Sokoban {
+            you: snapshot.you,
+            layers: snapshot.layers,
+            partner: snapshot.partner,
+            clones: snapshot.clones,
+            durability: snapshot.durability,
+            colors: snapshot.colors,
+            sizes: snapshot.sizes,
+            triggered: snapshot.triggered,
+            enemies: snapshot.enemies,
+            items: snapshot.items,
+            collected: snapshot.collected,
+            moves: snapshot.moves,
+            history: snapshot.history,
+            previous: Some(Rc::new(self.stay())),
+            ..self.stay()
+        }
+    }
+
+    /// Gets the tally of how the game on this board has gone
+    pub fn stats(&self) -> GameStats {
+        self.stats
+    }
+
+    /// Count `delta` more time played toward [`GameStats::elapsed`]
+    ///
+    /// It's up to the game to call this as time passes, and to stop
+    /// calling it while paused, so the clock is the same one that moves
+    /// are made on.  Time stops counting once the board is won, lost,
+    /// or failed, leaving how long it took.
+    pub fn tick(&mut self, delta: std::time::Duration) {
+        if !self.is_over() {
+            self.stats.elapsed = self.stats.elapsed.saturating_add(delta);
+        }
+    }
+
+    /// Checks if there are any moves to [`Sokoban::undo`]
+    pub fn can_undo(&self) -> bool {
+        self.previous.is_some()
+    }
+
+    /// Put you at `you`, leaving everything else where it is
+    pub fn with_you(self, you: coordinate::I2) -> Sokoban {
+        Sokoban { you, ..self }
+    }
+
+    /// Give the board a second player, `partner`, for hot-seat play
+    ///
+    /// Your partner moves independently of you with
+    /// [`Sokoban::partner_move`], and the two of you are each as solid
+    /// as a stop to the other.
+    pub fn with_partner(self, partner: coordinate::I2) -> Sokoban {
+        Sokoban {
+            partner: Some(partner),
+            ..self
+        }
+    }
+
+    /// Move the second player one tile over toward direction
+    ///
+    /// This follows all the same rules as [`Sokoban::you_move`], with
+    /// you standing in for a stop.  Should there be no second player,
+    /// then nothing happens.
+    pub fn partner_move(&self, direction: coordinate::Direction) -> Sokoban {
+        self.partner_move_outcome(direction).0
+    }
+
+    /// Add `clones` of you that all move at the same time as you do
+    ///
+    /// Everyone moves the same way at once, shoving their own pushes
+    /// along.  Whoever's furthest along goes first, so a clone right
+    /// behind you follows you rather than being blocked by you, and
+    /// you're each as solid as a stop to the others otherwise.  The
+    /// move only counts as blocked if nobody could move.
+    pub fn with_clones(self, clones: coordinate::I2Array) -> Sokoban {
+        Sokoban { clones, ..self }
+    }
+
+    /// Gets the positions of all your clones
+    pub fn clones(&self) -> coordinate::I2Array {
+        self.clones.clone()
+    }
+
+    /// Limit how many pushes can be shoved along in a line at once
+    ///
+    /// With a `push_limit` of `Some(1)`, you can only push one push at
+    /// a time, as in classic Sokoban, and a line of two is as good as a
+    /// stop.  `None`, the default, lets you shove lines of any length.
+    pub fn with_push_limit(self, push_limit: Option<usize>) -> Sokoban {
+        Sokoban { push_limit, ..self }
+    }
+
+    /// Gets how many pushes can be shoved along at once, if it's limited
+    pub fn push_limit(&self) -> Option<usize> {
+        self.push_limit
+    }
+
+    /// Give the board a budget of `move_limit` moves to be won in
+    ///
+    /// Each move counts for the [`Sokoban::cost`] of the tile it lands
+    /// on, and moves by your partner count too.  Once the budget's
+    /// spent without winning, the board [`Sokoban::is_failed`] and
+    /// won't let anybody move until it's undone.
+    pub fn with_move_limit(self, move_limit: Option<u32>) -> Sokoban {
+        Sokoban { move_limit, ..self }
+    }
+
+    /// Gets how many moves the board can be won in, if it's limited
+    pub fn move_limit(&self) -> Option<u32> {
+        self.move_limit
+    }
+
+    /// Gets how many moves have been made on the board so far
+    pub fn moves(&self) -> u32 {
+        self.moves
+    }
+
+    /// Checks if the move budget ran out before the board was won
+    pub fn is_failed(&self) -> bool {
+        self.move_limit.is_some_and(|limit| self.moves >= limit) && !self.all_targets_triggered()
+    }
+
+    /// Say that the board can be won in `par` moves
+    ///
+    /// This is usually the fewest moves it can be won in, or else the
+    /// best its author managed.  It doesn't limit anything; it's for
+    /// grading how many [`Sokoban::moves`] the player took.
+    pub fn with_par(self, par: Option<u32>) -> Sokoban {
+        Sokoban { par, ..self }
+    }
+
+    /// Gets how many moves the board is meant to be won in, if it's known
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// let board: Sokoban = "@ 0^".parse::<Sokoban>().unwrap().with_par(Some(2));
+    /// let won: Sokoban = board
+    ///     .you_move(coordinate::Direction::Right)
+    ///     .you_move(coordinate::Direction::Right);
+    ///
+    /// assert!(won.all_targets_triggered());
+    /// assert_eq!(won.par(), Some(won.moves()));
+    /// ```
+    pub fn par(&self) -> Option<u32> {
+        self.par
+    }
+
+    /// Start recording the moves you make on the board
+    ///
+    /// Every move of yours that isn't blocked is added to
+    /// [`Sokoban::history`], so a finished game can be stored and played
+    /// back later.  Undoing a move takes it back out of the history.
+    /// Moves by your partner aren't recorded.
+    pub fn with_recording(self) -> Sokoban {
+        Sokoban {
+            history: Some(vec![]),
+            ..self
+        }
+    }
+
+    /// Gets the moves you've made since recording started, if it has
+    pub fn history(&self) -> Option<&[coordinate::Direction]> {
+        self.history.as_deref()
+    }
+
+    /// Give some tiles a cost other than one move to step onto
+    ///
+    /// Each of `costs` pairs a coordinate with how many moves it counts
+    /// as to walk onto it, e.g. mud that costs two.  Every other tile
+    /// costs one.  Should a coordinate be listed more than once, the
+    /// first listing wins.
+    pub fn with_costs(self, costs: Vec<(coordinate::I2, u32)>) -> Sokoban {
+        Sokoban { costs, ..self }
+    }
+
+    /// How many moves it costs to step onto `coordinate`
+    pub fn cost(&self, coordinate: coordinate::I2) -> u32 {
+        self.costs
+            .iter()
+            .find(|(costly, _)| *costly == coordinate)
+            .map_or(1, |(_, cost)| *cost)
+    }
+
+    /// How many moves [`Sokoban::you_move`] toward `direction` costs
+    ///
+    /// This is the cost of the tile you'd end up on, or zero if you
+    /// can't move that way at all.
+    pub fn you_move_cost(&self, direction: coordinate::Direction) -> u32 {
+        match self.you_move_outcome(direction) {
+            (_, MoveOutcome::Blocked | MoveOutcome::GameOver) => 0,
+            (board, _) => self.cost(board.you),
+        }
+    }
+
+    /// Limit how many times some of the pushes can be pushed
+    ///
+    /// Each of `durability` pairs the coordinate of a push with how many
+    /// times it can be pushed.  Once it's been pushed that many times,
+    /// it breaks as `breakage` says.  Pushes that aren't listed can be
+    /// pushed forever.
+    pub fn with_durability(
+        self,
+        durability: Vec<(coordinate::I2, u32)>,
+        breakage: Breakage,
+    ) -> Sokoban {
+        Sokoban {
+            durability,
+            breakage,
+            ..self
+        }
+    }
+
+    /// How many more times the push at `coordinate` can be pushed
+    ///
+    /// This is `None` if there's no push there with limited durability.
+    pub fn durability(&self, coordinate: coordinate::I2) -> Option<u32> {
+        self.durability
+            .iter()
+            .find(|(push, _)| *push == coordinate)
+            .map(|(_, durability)| *durability)
+    }
+
+    /// Paint some of the pushes and targets
+    ///
+    /// Each of `pushes` and `targets` pairs the coordinate of a push or
+    /// target with its color.  A colored target is only triggered by a
+    /// push of the same color, while a target with no color is happy
+    /// with any push.
+    pub fn with_colors(
+        self,
+        pushes: Vec<(coordinate::I2, u32)>,
+        targets: Vec<(coordinate::I2, u32)>,
+    ) -> Sokoban {
+        let mut board: Sokoban = Sokoban {
+            colors: pushes,
+            target_colors: targets,
+            ..self
+        };
+        board.triggered = board.count_triggered();
+        board
+    }
+
+    /// The color of the push at `coordinate`, if it has one
+    pub fn push_color(&self, coordinate: coordinate::I2) -> Option<u32> {
+        self.colors
+            .iter()
+            .find(|(push, _)| *push == coordinate)
+            .map(|(_, color)| *color)
+    }
+
+    /// The color of the target at `coordinate`, if it has one
+    pub fn target_color(&self, coordinate: coordinate::I2) -> Option<u32> {
+        self.target_colors
+            .iter()
+            .find(|(target, _)| *target == coordinate)
+            .map(|(_, color)| *color)
+    }
+
+    /// Put everything of `kind` at `cells`, replacing wherever they were
+    ///
+    /// This is what the likes of [`Sokoban::with_heavies`] do, and it's
+    /// for kinds that don't have one of their own.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// let board: Sokoban = "@0^"
+    ///     .parse::<Sokoban>()
+    ///     .unwrap()
+    ///     .with_layer(EntityKind::Target, coordinate::I2Array::from(vec![[1, 0]]));
+    ///
+    /// assert!(board.all_targets_triggered());
+    /// ```
+    pub fn with_layer(self, kind: EntityKind, cells: coordinate::I2Array) -> Sokoban {
+        let mut board: Sokoban = self;
+        board.layers.set(kind, cells);
+        board.triggered = board.count_triggered();
+        board
+    }
+
+    /// Gets the positions of everything of `kind`
+    pub fn layer(&self, kind: EntityKind) -> &coordinate::I2Array {
+        &self.layers[kind]
+    }
+
+    /// Make the pushes at `heavies` too heavy to push in a line
+    ///
+    /// A heavy push can still be pushed on its own, but any line of
+    /// pushes with a heavy one in it is as good as a stop.
+    pub fn with_heavies(self, heavies: coordinate::I2Array) -> Sokoban {
+        self.with_layer(EntityKind::Heavy, heavies)
+    }
+
+    /// Gets the positions of all the heavy pushes
+    pub fn heavies(&self) -> coordinate::I2Array {
+        self.layers[EntityKind::Heavy].clone()
+    }
+
+    /// Make the pushes at `stickies` stick to each other
+    ///
+    /// Pushing a sticky push drags along every sticky push touching it
+    /// on a side, and every one touching those, and so on.  Anything in
+    /// the way of any of them gets pushed too, and if any of them is
+    /// blocked, they all are.
+    pub fn with_stickies(self, stickies: coordinate::I2Array) -> Sokoban {
+        self.with_layer(EntityKind::Sticky, stickies)
+    }
+
+    /// Gets the positions of all the sticky pushes
+    pub fn stickies(&self) -> coordinate::I2Array {
+        self.layers[EntityKind::Sticky].clone()
+    }
+
+    /// Make the pushes at `mergeables` merge into each other
+    ///
+    /// Pushing a mergeable push into another one doesn't shove it along
+    /// but merges the two into one push where the other one was, which
+    /// keeps everything about the push that was merged into besides its
+    /// [`Sokoban::size`].  Merged pushes are split back apart by
+    /// [`Sokoban::with_splitters`].
+    pub fn with_mergeables(self, mergeables: coordinate::I2Array) -> Sokoban {
+        self.with_layer(EntityKind::Mergeable, mergeables)
+    }
+
+    /// Gets the positions of all the mergeable pushes
+    pub fn mergeables(&self) -> coordinate::I2Array {
+        self.layers[EntityKind::Mergeable].clone()
+    }
+
+    /// Merge mergeable pushes by color under `rules`, rather than any
+    /// two of them merging
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// let board: Sokoban = "@0 0"
+    ///     .parse::<Sokoban>()
+    ///     .unwrap()
+    ///     .with_mergeables(coordinate::I2Array::from(vec![[1, 0], [3, 0]]))
+    ///     .with_colors(vec![(coordinate::I2::new(1, 0), 2), (coordinate::I2::new(3, 0), 2)], vec![])
+    ///     .with_merge_rules(MergeRules { table: vec![(2, 4)] });
+    /// let merged: Sokoban = board
+    ///     .you_move(coordinate::Direction::Right)
+    ///     .you_move(coordinate::Direction::Right);
+    ///
+    /// assert_eq!(merged.push_color(coordinate::I2::new(3, 0)), Some(4));
+    /// ```
+    pub fn with_merge_rules(self, rules: MergeRules) -> Sokoban {
+        Sokoban {
+            merge_rules: Some(rules),
+            ..self
+        }
+    }
+
+    /// Gets the rules mergeable pushes merge by, if they're limited
+    pub fn merge_rules(&self) -> Option<&MergeRules> {
+        self.merge_rules.as_ref()
+    }
+
+    /// Checks if the push at `push` would merge into one at `into`,
+    /// rather than shoving it, under the board's merge rules
+    fn merges_into(&self, push: coordinate::I2, into: coordinate::I2) -> bool {
+        if !self.layers[EntityKind::Mergeable].contains(&push)
+            || !self.layers[EntityKind::Mergeable].contains(&into)
+        {
+            return false;
+        }
+        match &self.merge_rules {
+            Some(rules) => match (self.push_color(push), self.push_color(into)) {
+                (Some(color), Some(other)) => color == other && rules.merged_color(color).is_some(),
+                _ => false,
+            },
+            None => true,
+        }
+    }
+
+    /// How many pushes have merged into the push at `coordinate`
+    ///
+    /// This is `1` for pushes that haven't merged with anything, and
+    /// `None` if there's no push there.
+    pub fn size(&self, coordinate: coordinate::I2) -> Option<u32> {
+        if !self.layers[EntityKind::Push].contains(&coordinate) {
+            return None;
+        }
+        let size: Option<u32> = self
+            .sizes
+            .iter()
+            .find(|(push, _)| *push == coordinate)
+            .map(|(_, size)| *size);
+        Some(size.unwrap_or(1))
+    }
+
+    /// Add tiles at `splitters` that split merged pushes back apart
+    ///
+    /// When a push made of more than one merged push is pushed onto a
+    /// splitter, one of them breaks off and carries on one tile
+    /// further, so long as there's room for it.  The rest stay on the
+    /// splitter.
+    pub fn with_splitters(self, splitters: coordinate::I2Array) -> Sokoban {
+        self.with_layer(EntityKind::Splitter, splitters)
+    }
+
+    /// Gets the positions of all the splitters
+    pub fn splitters(&self) -> coordinate::I2Array {
+        self.layers[EntityKind::Splitter].clone()
+    }
+
+    /// Crack the floor at `cracks` so it breaks once walked over
+    ///
+    /// A cracked tile can be stood on like any other, but as soon as
+    /// you or your partner step off of it, it becomes a stop.  Pushes
+    /// sliding over it don't break it.
+    pub fn with_cracks(self, cracks: coordinate::I2Array) -> Sokoban {
+        self.with_layer(EntityKind::Crack, cracks)
+    }
+
+    /// Gets the positions of all the cracked floor that's yet to break
+    pub fn cracks(&self) -> coordinate::I2Array {
+        self.layers[EntityKind::Crack].clone()
+    }
+
+    /// Add pressure switches that open walls while something is on them
+    ///
+    /// You, your partner, or a push standing on a switch holds its
+    /// walls open, and when the switch is let go they close again.  A
+    /// wall won't close on top of something standing in it, though; it
+    /// stays open until it's clear.
+    pub fn with_switches(self, switches: Vec<Switch>) -> Sokoban {
+        Sokoban { switches, ..self }
+    }
+
+    /// Gets all the switches and the walls they're linked to
+    pub fn switches(&self) -> &[Switch] {
+        &self.switches
+    }
+
+    /// Gets the positions of all the switch walls that are closed
+    ///
+    /// These block movement just like stops.
+    pub fn closed_walls(&self) -> coordinate::I2Array {
+        let is_occupied = |coordinate: &coordinate::I2| {
+            self.you == *coordinate
+                || self.clones.contains(coordinate)
+                || self.partner == Some(*coordinate)
+                || self.layers[EntityKind::Push].contains(coordinate)
+        };
+        self.switches
+            .iter()
+            .filter(|switch| !is_occupied(&switch.at))
+            .flat_map(|switch| switch.walls.iter())
+            .filter(|wall| !is_occupied(wall))
+            .copied()
+            .collect()
+    }
+
+    /// Add magnets that pull pushes toward themselves after every move
+    ///
+    /// After each move that goes anywhere, each magnet in turn looks
+    /// down the line it's facing for the nearest push.  If nothing is
+    /// in between them, the push is pulled one tile closer, possibly
+    /// onto the magnet itself, where it stays and shields any pushes
+    /// further along.
+    pub fn with_magnets(self, magnets: Vec<Magnet>) -> Sokoban {
+        Sokoban { magnets, ..self }
+    }
+
+    /// Gets all the magnets and which ways they face
+    pub fn magnets(&self) -> &[Magnet] {
+        &self.magnets
+    }
+
+    /// Cover the floor at `ices` in ice that pushes slide over
+    ///
+    /// A push that's pushed onto ice keeps going the same way until it's
+    /// off the ice or runs into something.  You don't slide, though.
+    pub fn with_ices(self, ices: coordinate::I2Array) -> Sokoban {
+        self.with_layer(EntityKind::Ice, ices)
+    }
+
+    /// Gets the positions of all the icy floor
+    pub fn ices(&self) -> coordinate::I2Array {
+        self.layers[EntityKind::Ice].clone()
+    }
+
+    /// Link up pairs of `teleporters` that carry things between them
+    ///
+    /// Stepping onto one end of a pair, or pushing a push onto it, takes
+    /// you or the push to the other end, so long as there's room there.
+    /// Standing on the far end doesn't send you back; you have to step
+    /// off and back on again.
+    pub fn with_teleporters(self, teleporters: Vec<(coordinate::I2, coordinate::I2)>) -> Sokoban {
+        Sokoban {
+            teleporters,
+            ..self
+        }
+    }
+
+    /// Gets all the pairs of linked teleporters
+    pub fn teleporters(&self) -> &[(coordinate::I2, coordinate::I2)] {
+        &self.teleporters
+    }
+
+    /// Add `enemies` that each take a step after every move you make
+    ///
+    /// Enemies are as solid as stops to you and your pushes, so the only
+    /// way to be caught is for one of them to step onto you, your
+    /// clones, or your partner.  Once that happens, the board is lost
+    /// and won't let anybody move until it's undone.
+    pub fn with_enemies(self, enemies: Vec<Enemy>) -> Sokoban {
+        Sokoban { enemies, ..self }
+    }
+
+    /// Gets all the enemies
+    pub fn enemies(&self) -> &[Enemy] {
+        &self.enemies
+    }
+
+    /// Lay `items` on the floor underneath everything else
+    ///
+    /// Collectible items are picked up by you, your partner, or a clone
+    /// stepping onto them, but not by a push, which only covers them
+    /// up.  Undoing a move that picked something up puts it back.
+    pub fn with_items(self, items: Vec<Item>) -> Sokoban {
+        Sokoban { items, ..self }
+    }
+
+    /// Gets all the items still lying on the floor
+    pub fn items(&self) -> &[Item] {
+        &self.items
+    }
+
+    /// Gets all the items that have been picked up, in the order they
+    /// were picked up
+    pub fn collected(&self) -> &[Item] {
+        &self.collected
+    }
+
+    /// Checks if an enemy has caught you, a clone, or your partner
+    pub fn is_lost(&self) -> bool {
+        self.enemies.iter().any(|enemy| {
+            enemy.at == self.you
+                || self.clones.contains(&enemy.at)
+                || self.partner == Some(enemy.at)
+        })
+    }
+
+    /// What happened on the last move besides things moving
+    pub fn events(&self) -> &[Event] {
+        &self.events
+    }
+
+    /// What happened on the last turn, going by the plain rules
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// let board: Sokoban = "@0^|".parse().unwrap();
+    /// let walled: Sokoban = "@|".parse().unwrap();
+    /// let right: coordinate::Direction = coordinate::Direction::Right;
+    ///
+    /// assert_eq!(
+    ///     board.you_move(right).turn_events(),
+    ///     &[
+    ///         TurnEvent::Pushed { from: coordinate::I2::new(1, 0), to: coordinate::I2::new(2, 0) },
+    ///         TurnEvent::Triggered { at: coordinate::I2::new(2, 0) },
+    ///     ]
+    /// );
+    /// assert_eq!(
+    ///     walled.you_move(right).turn_events(),
+    ///     &[TurnEvent::Blocked { direction: right }]
+    /// );
+    /// ```
+    pub fn turn_events(&self) -> &[TurnEvent] {
+        &self.turn_events
+    }
+
+    /// Note each target that was triggered or untriggered since `before`
+    fn note_retriggered(&mut self, before: &Sokoban) {
+        let changed: Vec<TurnEvent> = self.layers[EntityKind::Target]
+            .iter()
+            .filter_map(
+                |target| match (before.is_triggered(target), self.is_triggered(target)) {
+                    (false, true) => Some(TurnEvent::Triggered { at: *target }),
+                    (true, false) => Some(TurnEvent::Untriggered { at: *target }),
+                    _ => None,
+                },
+            )
+            .collect();
+        self.turn_events.extend(changed);
+    }
+
+    /// Tally up a move that went somewhere, shoving `pushed` pushes
+    fn count_move(&mut self, pushed: usize) {
+        self.stats.moves = self.stats.moves.saturating_add(1);
+        self.stats.pushes = self
+            .stats
+            .pushes
+            .saturating_add(pushed.try_into().unwrap_or(u32::MAX));
+    }
+
+    /// This board after a move that went nowhere
+    fn stay(&self) -> Sokoban {
+        Sokoban {
+            events: vec![],
+            turn_events: vec![],
+            ..self.clone()
+        }
+    }
+
+    /// Move `avatar` toward `direction` along with whatever it pushes,
+    /// giving where it ends up and how many pushes it shoved
+    ///
+    /// This is `None`, and the board is left alone, if the move is
+    /// blocked, where `obstacles` block just like stops would.
+    fn step(
+        &mut self,
+        avatar: coordinate::I2,
+        obstacles: &[coordinate::I2],
+        direction: coordinate::Direction,
+    ) -> Option<(coordinate::I2, usize)> {
+        let moving_pushes: coordinate::I2Array =
+            self.moving_pushes(avatar, obstacles, direction)?;
+        let pushed: usize = moving_pushes.iter().count();
+        self.turn_events
+            .extend(moving_pushes.iter().map(|push| TurnEvent::Pushed {
+                from: *push,
+                to: push.nudge(direction).unwrap(),
+            }));
+        let moving_pushes: coordinate::I2Array = self.merge(&moving_pushes, direction);
+
+        let new_avatar: coordinate::I2 = avatar.nudge(direction).unwrap();
+        let changed: Vec<coordinate::I2> = moving_pushes
+            .iter()
+            .flat_map(|push| [*push, push.nudge(direction).unwrap()])
+            .collect();
+        self.retrigger(&changed, |board| {
+            board.layers.map(EntityKind::Push, |push| {
+                if moving_pushes.contains(push) {
+                    push.nudge(direction).unwrap()
+                } else {
+                    *push
+                }
+            });
+            for (push, _) in board.colors.iter_mut() {
+                if moving_pushes.contains(push) {
+                    *push = push.nudge(direction).unwrap();
+                }
+            }
+        });
+        let nudged = |push: &coordinate::I2| {
+            if moving_pushes.contains(push) {
+                push.nudge(direction).unwrap()
+            } else {
+                *push
+            }
+        };
+        for kind in EntityKind::ATTACHED {
+            self.layers.map(kind, nudged);
+        }
+        for (push, _) in self.sizes.iter_mut() {
+            *push = nudged(push);
+        }
+
+        let mut avatars: Vec<coordinate::I2> = obstacles.to_vec();
+        avatars.push(new_avatar);
+        self.wear(&moving_pushes, direction);
+        self.split(&moving_pushes, direction, &avatars);
+        let landed: Vec<coordinate::I2> = self.slide(&moving_pushes, direction, &avatars);
+        self.teleport_pushes(&landed, &avatars);
+        let new_avatar: coordinate::I2 = self.teleport(new_avatar, obstacles);
+        self.crack(avatar);
+        self.pick_up(new_avatar);
+
+        Some((new_avatar, pushed))
+    }
+
+    /// The pushes an avatar at `avatar` would shove moving toward
+    /// `direction`, or `None` if it's blocked
+    ///
+    /// They're in order of how far along they are, nearest first.
+    fn moving_pushes(
+        &self,
+        avatar: coordinate::I2,
+        obstacles: &[coordinate::I2],
+        direction: coordinate::Direction,
+    ) -> Option<coordinate::I2Array> {
+        let closed_walls: coordinate::I2Array = self.closed_walls();
+        let is_blocked = |cell: &coordinate::I2| {
+            self.layers[EntityKind::Stop].contains(cell)
+                || closed_walls.contains(cell)
+                || obstacles.contains(cell)
+                || self.enemies.iter().any(|enemy| enemy.at == *cell)
+        };
+        let front: coordinate::I2 = avatar.nudge(direction)?;
+        if is_blocked(&front) {
+            return None;
+        }
+
+        // each push that moves shoves whatever's in front of it and
+        // drags along any sticky pushes stuck to it
+        let mut moving_pushes: Vec<coordinate::I2> = vec![];
+        let mut pending: Vec<coordinate::I2> = Vec::from_iter(
+            Some(front).filter(|front| self.layers[EntityKind::Push].contains(front)),
+        );
+        let mut seen: HashSet<coordinate::I2> = pending.iter().copied().collect();
+        while let Some(push) = pending.pop() {
+            moving_pushes.push(push);
+            let ahead: coordinate::I2 = push.nudge(direction)?;
+            let merges: bool = self.merges_into(push, ahead) && !seen.contains(&ahead);
+            if is_blocked(&ahead) {
+                return None;
+            }
+            let stuck: Vec<coordinate::I2> = if self.layers[EntityKind::Sticky].contains(&push) {
+                DIRECTIONS
+                    .iter()
+                    .filter_map(|side| push.nudge(*side))
+                    .filter(|side| self.layers[EntityKind::Sticky].contains(side))
+                    .collect()
+            } else {
+                vec![]
+            };
+            let shoved: Option<coordinate::I2> = Some(ahead)
+                .filter(|ahead| self.layers[EntityKind::Push].contains(ahead) && !merges);
+            for next in shoved.into_iter().chain(stuck) {
+                if seen.insert(next) {
+                    pending.push(next);
+                }
+            }
+        }
+        if self
+            .push_limit
+            .is_some_and(|limit| moving_pushes.len() > limit)
+        {
+            return None;
+        }
+        if moving_pushes.len() > 1
+            && moving_pushes
+                .iter()
+                .any(|push| self.layers[EntityKind::Heavy].contains(push))
+        {
+            return None;
+        }
+        let along = |cell: &coordinate::I2| -> i64 {
+            match direction {
+                coordinate::Direction::Up => -(cell.y() as i64),
+                coordinate::Direction::Left => -(cell.x() as i64),
+                coordinate::Direction::Down => cell.y() as i64,
+                coordinate::Direction::Right => cell.x() as i64,
+            }
+        };
+        moving_pushes.sort_by_key(along);
+        Some(moving_pushes.into_iter().collect())
+    }
+
+    /// How a move that got `board` went, having shoved `pushed` pushes
+    fn outcome(&self, board: &Sokoban, pushed: usize) -> MoveOutcome {
+        if board.is_lost() {
+            return MoveOutcome::Lost;
+        }
+        if board.is_failed() {
+            return MoveOutcome::Failed;
+        }
+        if board.all_targets_triggered() && !self.all_targets_triggered() {
+            return MoveOutcome::Won;
+        }
+        match pushed {
+            0 => MoveOutcome::Moved,
+            pushed => MoveOutcome::Pushed(pushed),
+        }
+    }
+
+    /// Have each enemy in turn take its step
+    fn hunt(&mut self) {
+        let closed_walls: coordinate::I2Array = self.closed_walls();
+        for i in 0..self.enemies.len() {
+            let at: coordinate::I2 = self.enemies[i].at;
+            let directions: Vec<coordinate::Direction> = match &mut self.enemies[i].movement {
+                Movement::Chase => {
+                    let (dx, dy): (i64, i64) = (
+                        self.you.x() as i64 - at.x() as i64,
+                        self.you.y() as i64 - at.y() as i64,
+                    );
+                    let horizontal: Option<coordinate::Direction> = match dx {
+                        0 => None,
+                        _ if dx < 0 => Some(coordinate::Direction::Left),
+                        _ => Some(coordinate::Direction::Right),
+                    };
+                    let vertical: Option<coordinate::Direction> = match dy {
+                        0 => None,
+                        _ if dy < 0 => Some(coordinate::Direction::Up),
+                        _ => Some(coordinate::Direction::Down),
+                    };
+                    if dx.abs() >= dy.abs() {
+                        horizontal.into_iter().chain(vertical).collect()
+                    } else {
+                        vertical.into_iter().chain(horizontal).collect()
+                    }
+                }
+                Movement::Patrol { route, next } => {
+                    let direction: Option<coordinate::Direction> = route.get(*next).copied();
+                    *next = (*next + 1) % route.len().max(1);
+                    direction.into_iter().collect()
+                }
+            };
+            let is_open = |cell: &coordinate::I2| {
+                !self.layers[EntityKind::Stop].contains(cell)
+                    && !closed_walls.contains(cell)
+                    && !self.layers[EntityKind::Push].contains(cell)
+                    && !self.enemies.iter().any(|other| other.at == *cell)
+            };
+            if let Some(to) = directions
+                .into_iter()
+                .filter_map(|direction| at.nudge(direction))
+                .find(is_open)
+            {
+                self.enemies[i].at = to;
+            }
+        }
+    }
+
+    /// Pick up any collectible items at `at`
+    fn pick_up(&mut self, at: coordinate::I2) {
+        let (picked_up, items): (Vec<Item>, Vec<Item>) = self
+            .items
+            .iter()
+            .partition(|item| item.at == at && item.collectible);
+        for item in picked_up {
+            self.events.push(Event::PickedUp { item });
+            self.collected.push(item);
+        }
+        self.items = items;
+    }
+
+    /// Let each magnet pull the nearest push in front of it one tile
+    fn attract(&mut self) {
+        for magnet in self.magnets.clone() {
+            let distance = |push: &coordinate::I2| -> Option<i32> {
+                let (dx, dy): (i64, i64) = (
+                    push.x() as i64 - magnet.at.x() as i64,
+                    push.y() as i64 - magnet.at.y() as i64,
+                );
+                let (along, across): (i64, i64) = match magnet.facing {
+                    coordinate::Direction::Up => (-dy, dx),
+                    coordinate::Direction::Left => (-dx, dy),
+                    coordinate::Direction::Down => (dy, dx),
+                    coordinate::Direction::Right => (dx, dy),
+                };
+                // magnets have a long but finite reach
+                (across == 0 && along >= 0)
+                    .then_some(along)?
+                    .try_into()
+                    .ok()
+            };
+            let Some((push, distance)) = self.layers[EntityKind::Push]
+                .iter()
+                .filter_map(|push| Some((*push, distance(push)?)))
+                .min_by_key(|(_, distance)| *distance)
+            else {
+                continue;
+            };
+            if distance == 0 {
+                // it's already stuck to the magnet
+                continue;
+            }
+
+            let closed_walls: coordinate::I2Array = self.closed_walls();
+            let is_blocked = |coordinate: coordinate::I2| {
+                self.layers[EntityKind::Stop].contains(&coordinate)
+                    || closed_walls.contains(&coordinate)
+                    || self.layers[EntityKind::Push].contains(&coordinate)
+                    || self.you == coordinate
+                    || self.clones.contains(&coordinate)
+                    || self.enemies.iter().any(|enemy| enemy.at == coordinate)
+                    || self.partner == Some(coordinate)
+            };
+            // everything between the magnet and the push is a valid
+            // coordinate, so none of these nudges overflow
+            let path: Vec<coordinate::I2> = (distance.min(2) - 1..distance)
+                .map(|i| magnet.at.nudge_by(i, magnet.facing).unwrap())
+                .collect();
+            if path.iter().any(|coordinate| is_blocked(*coordinate)) {
+                continue;
+            }
+
+            let pulled: coordinate::I2 = magnet.at.nudge_by(distance - 1, magnet.facing).unwrap();
+            self.relocate(push, pulled);
+            self.events.push(Event::Pulled {
+                from: push,
+                to: pulled,
+            });
+        }
+    }
+
+    /// Break the floor at `left`, if it's cracked, as it's walked off of
+    fn crack(&mut self, left: coordinate::I2) {
+        if !self.layers[EntityKind::Crack].contains(&left) {
+            return;
+        }
+        self.layers
+            .retain(EntityKind::Crack, |crack| *crack != left);
+        self.layers.get_mut(EntityKind::Stop).push(left);
+        self.events.push(Event::Cracked { at: left });
+    }
+
+    /// Merge any of the `moving` pushes that are about to move into a
+    /// mergeable push that isn't moving into it, giving the pushes
+    /// that are left to move
+    fn merge(
+        &mut self,
+        moving: &coordinate::I2Array,
+        direction: coordinate::Direction,
+    ) -> coordinate::I2Array {
+        let mut left: coordinate::I2Array = coordinate::I2Array::from(vec![]);
+        for push in moving.iter() {
+            let into: coordinate::I2 = push.nudge(direction).unwrap();
+            let merges: bool = self.merges_into(*push, into) && !moving.contains(&into);
+            if !merges {
+                left.push(*push);
+                continue;
+            }
+            let size: u32 = self.size(*push).unwrap_or(1) + self.size(into).unwrap_or(1);
+            self.take_push(*push);
+            self.sizes.retain(|(push, _)| *push != into);
+            self.sizes.push((into, size));
+            let upgraded: Option<u32> = self
+                .merge_rules
+                .as_ref()
+                .zip(self.push_color(into))
+                .and_then(|(rules, color)| rules.merged_color(color));
+            if let Some(upgraded) = upgraded {
+                self.retrigger(&[into], |board| {
+                    board.colors.retain(|(push, _)| *push != into);
+                    board.colors.push((into, upgraded));
+                });
+            }
+            self.events.push(Event::Merged {
+                at: into,
+                size,
+                color: self.push_color(into),
+            });
+        }
+        left
+    }
+
+    /// Split a push off of each merged push that was at `moved` before
+    /// moving toward `direction` and is now on a splitter
+    ///
+    /// The split off push carries on a tile further if it's clear,
+    /// counting `avatars`, or else nothing splits.
+    fn split(
+        &mut self,
+        moved: &coordinate::I2Array,
+        direction: coordinate::Direction,
+        avatars: &[coordinate::I2],
+    ) {
+        for push in moved.iter().filter_map(|push| push.nudge(direction)) {
+            if !self.layers[EntityKind::Splitter].contains(&push)
+                || self.size(push).unwrap_or(0) < 2
+            {
+                continue;
+            }
+            let Some(to) = push
+                .nudge(direction)
+                .filter(|to| self.is_clear(*to, avatars))
+            else {
+                continue;
+            };
+            let size: u32 = self.size(push).unwrap() - 1;
+            self.sizes.retain(|(sized, _)| *sized != push);
+            if size > 1 {
+                self.sizes.push((push, size));
+            }
+            self.retrigger(&[to], |board| {
+                board.layers.get_mut(EntityKind::Push).push(to)
+            });
+            self.layers.get_mut(EntityKind::Mergeable).push(to);
+            self.events.push(Event::Split { from: push, to });
+        }
+    }
+
+    /// Wear down the pushes that were at `moved` before moving toward
+    /// `direction`, breaking any that are worn out
+    fn wear(&mut self, moved: &coordinate::I2Array, direction: coordinate::Direction) {
+        let mut worn_out: coordinate::I2Array = coordinate::I2Array::from(vec![]);
+        for (push, durability) in self.durability.iter_mut() {
+            if !moved.contains(push) {
+                continue;
+            }
+            *push = push.nudge(direction).unwrap();
+            *durability = durability.saturating_sub(1);
+            self.events.push(Event::Worn {
+                at: *push,
+                remaining: *durability,
+            });
+            if *durability == 0 {
+                worn_out.push(*push);
+            }
+        }
+
+        self.durability.retain(|(push, _)| !worn_out.contains(push));
+        for kind in EntityKind::ATTACHED {
+            self.layers.retain(kind, |push| !worn_out.contains(push));
+        }
+        self.sizes.retain(|(push, _)| !worn_out.contains(push));
+        let changed: Vec<coordinate::I2> = worn_out.iter().copied().collect();
+        self.retrigger(&changed, |board| {
+            board.colors.retain(|(push, _)| !worn_out.contains(push));
+            board
+                .layers
+                .retain(EntityKind::Push, |push| !worn_out.contains(push));
+        });
+        if self.breakage == Breakage::Stop {
+            for push in worn_out.iter() {
+                self.layers.get_mut(EntityKind::Stop).push(*push);
+            }
+        }
+    }
+
+    /// Slide the pushes that were at `moved` before moving toward
+    /// `direction` along any ice they landed on, giving where they
+    /// ended up
+    ///
+    /// The push furthest along goes first so the ones behind it can
+    /// follow it down the ice.  Nothing slides into `avatars`.
+    fn slide(
+        &mut self,
+        moved: &coordinate::I2Array,
+        direction: coordinate::Direction,
+        avatars: &[coordinate::I2],
+    ) -> Vec<coordinate::I2> {
+        let moved: Vec<coordinate::I2> = moved.iter().copied().collect();
+        let mut landed: Vec<coordinate::I2> = vec![];
+        for push in moved.iter().rev().filter_map(|push| push.nudge(direction)) {
+            if !self.layers[EntityKind::Push].contains(&push) {
+                // it wore out on the way
+                continue;
+            }
+            let mut at: coordinate::I2 = push;
+            while self.layers[EntityKind::Ice].contains(&at) {
+                match at.nudge(direction) {
+                    Some(next) if self.is_clear(next, avatars) => at = next,
+                    _ => break,
+                }
+            }
+            if at != push {
+                self.events.push(Event::Slid { from: push, to: at });
+            }
+            self.relocate(push, at);
+            landed.push(at);
+        }
+        landed
+    }
+
+    /// Send the pushes that `landed` on a teleporter over to its twin
+    fn teleport_pushes(&mut self, landed: &[coordinate::I2], avatars: &[coordinate::I2]) {
+        for push in landed {
+            let to: coordinate::I2 = self.teleport(*push, avatars);
+            self.relocate(*push, to);
+        }
+    }
+
+    /// Where something stepping onto `at` ends up, which is the twin
+    /// of the teleporter there if there is one and it's clear
+    fn teleport(&mut self, at: coordinate::I2, avatars: &[coordinate::I2]) -> coordinate::I2 {
+        let twin: Option<coordinate::I2> = self.teleporters.iter().find_map(|(a, b)| match at {
+            _ if at == *a => Some(*b),
+            _ if at == *b => Some(*a),
+            _ => None,
+        });
+        match twin {
+            Some(twin) if self.is_clear(twin, avatars) => {
+                self.events.push(Event::Teleported { from: at, to: twin });
+                twin
+            }
+            _ => at,
+        }
+    }
+
+    /// Checks if nothing is standing in `cell`, counting your partner
+    /// and clones, but not you
+    ///
+    /// This is for boards you might have left, which still remember
+    /// where you were.
+    pub(crate) fn is_vacant(&self, cell: coordinate::I2) -> bool {
+        let avatars: Vec<coordinate::I2> = self
+            .partner
+            .into_iter()
+            .chain(self.clones.iter().copied())
+            .collect();
+        self.is_clear(cell, &avatars)
+    }
+
+    /// Take the push at `at` off the board, if there is one
+    pub(crate) fn take_push(&mut self, at: coordinate::I2) -> Option<LoosePush> {
+        if !self.layers[EntityKind::Push].contains(&at) {
+            return None;
+        }
+        let push: LoosePush = LoosePush {
+            durability: self.durability(at),
+            color: self.push_color(at),
+            heavy: self.layers[EntityKind::Heavy].contains(&at),
+            sticky: self.layers[EntityKind::Sticky].contains(&at),
+            mergeable: self.layers[EntityKind::Mergeable].contains(&at),
+            size: self
+                .sizes
+                .iter()
+                .find(|(push, _)| *push == at)
+                .map(|(_, size)| *size),
+        };
+        self.retrigger(&[at], |board| {
+            board.layers.retain(EntityKind::Push, |push| *push != at);
+            board.colors.retain(|(push, _)| *push != at);
+        });
+        self.durability.retain(|(push, _)| *push != at);
+        for kind in EntityKind::ATTACHED {
+            self.layers.retain(kind, |push| *push != at);
+        }
+        self.sizes.retain(|(push, _)| *push != at);
+        Some(push)
+    }
+
+    /// Put down a `push` taken off of a board at `at`
+    pub(crate) fn put_push(&mut self, at: coordinate::I2, push: LoosePush) {
+        self.retrigger(&[at], |board| {
+            board.layers.get_mut(EntityKind::Push).push(at);
+            if let Some(color) = push.color {
+                board.colors.push((at, color));
+            }
+        });
+        if let Some(durability) = push.durability {
+            self.durability.push((at, durability));
+        }
+        if push.heavy {
+            self.layers.get_mut(EntityKind::Heavy).push(at);
+        }
+        if push.sticky {
+            self.layers.get_mut(EntityKind::Sticky).push(at);
+        }
+        if push.mergeable {
+            self.layers.get_mut(EntityKind::Mergeable).push(at);
+        }
+        if let Some(size) = push.size {
+            self.sizes.push((at, size));
+        }
+    }
+
+    /// Checks if nothing is standing in `cell`, counting `avatars`
+    fn is_clear(&self, cell: coordinate::I2, avatars: &[coordinate::I2]) -> bool {
+        !self.layers[EntityKind::Stop].contains(&cell)
+            && !self.closed_walls().contains(&cell)
+            && !self.layers[EntityKind::Push].contains(&cell)
+            && !avatars.contains(&cell)
+            && !self.enemies.iter().any(|enemy| enemy.at == cell)
+    }
+
+    /// Move the push at `from` to `to`, along with everything else
+    /// about it
+    fn relocate(&mut self, from: coordinate::I2, to: coordinate::I2) {
+        if from == to {
+            return;
+        }
+        let moved = |push: &coordinate::I2| if *push == from { to } else { *push };
+        self.retrigger(&[from, to], |board| {
+            board.layers.map(EntityKind::Push, moved);
+            for (push, _) in board.colors.iter_mut() {
+                if *push == from {
+                    *push = to;
+                }
+            }
+        });
+        for kind in EntityKind::ATTACHED {
+            self.layers.map(kind, moved);
+        }
+        for (push, _) in self.durability.iter_mut().chain(self.sizes.iter_mut()) {
+            if *push == from {
+                *push = to;
+            }
+        }
+    }
+
+    /// Make a `change` to the pushes that could only have triggered or
+    /// untriggered targets at `cells`, keeping the count of triggered
+    /// targets up to date without looking at the rest of the board
+    fn retrigger(&mut self, cells: &[coordinate::I2], change: impl FnOnce(&mut Sokoban)) {
+        let targets: HashSet<coordinate::I2> = cells
+            .iter()
+            .filter(|cell| self.layers[EntityKind::Target].contains(cell))
+            .copied()
+            .collect();
+        let before: usize = targets
+            .iter()
+            .filter(|target| self.is_triggered(target))
+            .count();
+        change(self);
+        let after: usize = targets
+            .iter()
+            .filter(|target| self.is_triggered(target))
+            .count();
+        self.triggered = self.triggered - before + after;
+    }
+
+    /// Checks if the target at `target` has a push of its color on it
+    fn is_triggered(&self, target: &coordinate::I2) -> bool {
+        self.layers[EntityKind::Push].contains(target)
+            && match self.target_color(*target) {
+                Some(color) => self.push_color(*target) == Some(color),
+                None => true,
+            }
+    }
+
+    /// Counts the triggered targets by looking at every one of them
+    fn count_triggered(&self) -> usize {
+        self.triggered_targets_iter().count()
+    }
+
+    /// The positions of all the targets that have a push on them
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # // Let's create this board, where @: you, 0: push, -|: stop, and ^: target
+    /// # //
+    /// # //   ---
+    /// # //   |^|
+    /// # //   | ----
+    /// # // ---0 0^|
+    /// # // |^ 0@---
+    /// # // ----0|
+    /// # //    |^|
+    /// # //    ---
+    /// #
+    /// # let you: coordinate::I2 = [4, 4];
+    /// # let stops: coordinate::I2Array = coordinate::I2Array::from(vec![
+    /// #     [2, 0], [3, 0], [4, 0], [2, 1], [4, 1], [2, 2], [4, 2],
+    /// #     [5, 2], [6, 2], [7, 2], [0, 3], [1, 3], [2, 3], [7, 3],
+    /// #     [0, 4], [5, 4], [6, 4], [7, 4], [0, 5], [1, 5], [2, 5],
+    /// #     [3, 5], [5, 5], [3, 6], [5, 6], [3, 7], [4, 7], [5, 7],
+    /// # ]);
+    /// # let pushes: coordinate::I2Array = coordinate::I2Array::from(vec![[3, 3], [5, 3], [3, 4], [4, 5]]);
+    /// let targets: coordinate::I2Array = coordinate::I2Array::from(vec![[3, 1], [6, 3], [1, 4], [4, 6]]);
+    /// // ...
+    /// #
+    /// let board: Sokoban = Sokoban::new(you, stops.clone(), pushes.clone(), targets.clone());
+    ///
+    /// # assert_eq!(
+    /// #     board.you_move(coordinate::Direction::Up),
+    /// #     Sokoban::new([4, 3], stops, pushes, targets)
+    /// # );
+    /// #
+    /// assert_eq!(
+    ///     board
+    ///         .you_move(coordinate::Direction::Down)
+    ///         .you_move(coordinate::Direction::Up)
+    ///         .triggered_targets(),
+    ///     vec![&[4, 6]]
+    /// );
+    /// #
+    /// # assert!(!board
+    /// #     .you_move(coordinate::Direction::Down)
+    /// #     .you_move(coordinate::Direction::Up)
+    /// #     .all_targets_triggered());
+    /// #
+    /// # assert!(board
+    /// #     .you_move(coordinate::Direction::Down)
+    /// #     .you_move(coordinate::Direction::Up)
+    /// #     .you_move(coordinate::Direction::Left)
+    /// #     .you_move(coordinate::Direction::Left)
+    /// #     .you_move(coordinate::Direction::Right)
+    /// #     .you_move(coordinate::Direction::Up)
+    /// #     .you_move(coordinate::Direction::Up)
+    /// #     .you_move(coordinate::Direction::Down)
+    /// #     .you_move(coordinate::Direction::Right)
+    /// #     .you_move(coordinate::Direction::Right)
+    /// #     .all_targets_triggered());
+    /// ```
+    pub fn triggered_targets(&self) -> Vec<&coordinate::I2> {
+        self.triggered_targets_iter()
+            .collect::<Vec<&coordinate::I2>>()
+    }
+
+    /// The positions of all the targets that have a push on them, like
+    /// [`Sokoban::triggered_targets`] but without collecting them
+    ///
+    /// This is for polling every frame, where allocating adds up.
+    pub fn triggered_targets_iter(&self) -> impl Iterator<Item = &coordinate::I2> + '_ {
+        self.layers[EntityKind::Target]
+            .iter()
+            .filter(|target| self.is_triggered(target))
+    }
+
+    /// How many targets have a push on them
+    ///
+    /// This is kept track of as pushes move, so it doesn't have to look
+    /// at any of them.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// let board: Sokoban = "@0^ 0^".parse().unwrap();
+    ///
+    /// assert_eq!(board.triggered_count(), 0);
+    /// assert_eq!(board.you_move(coordinate::Direction::Right).triggered_count(), 1);
+    /// ```
+    pub fn triggered_count(&self) -> usize {
+        self.triggered
+    }
+
+    /// Checks if all the targets have been triggered
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # // Let's create this board, where @: you, 0: push, -|: stop, and ^: target
+    /// # //
+    /// //   ---
+    /// //   |^|
+    /// //   | ----
+    /// // ---0 0^|
+    /// // |^ 0@---
+    /// // ----0|
+    /// //    |^|
+    /// //    ---
+    ///
+    /// # let you: coordinate::I2 = [4, 4];
+    /// # let stops: coordinate::I2Array = coordinate::I2Array::from(vec![
+    /// #     [2, 0], [3, 0], [4, 0], [2, 1], [4, 1], [2, 2], [4, 2],
+    /// #     [5, 2], [6, 2], [7, 2], [0, 3], [1, 3], [2, 3], [7, 3],
+    /// #     [0, 4], [5, 4], [6, 4], [7, 4], [0, 5], [1, 5], [2, 5],
+    /// #     [3, 5], [5, 5], [3, 6], [5, 6], [3, 7], [4, 7], [5, 7],
+    /// # ]);
+    /// # let pushes: coordinate::I2Array = coordinate::I2Array::from(vec![[3, 3], [5, 3], [3, 4], [4, 5]]);
+    /// # let targets: coordinate::I2Array = coordinate::I2Array::from(vec![[3, 1], [6, 3], [1, 4], [4, 6]]);
+    /// #
+    /// let board: Sokoban = Sokoban::new(you, stops.clone(), pushes.clone(), targets.clone());
+    ///
+    /// # assert_eq!(
+    /// #     board.you_move(coordinate::Direction::Up),
+    /// #     Sokoban::new([4, 3], stops, pushes, targets)
+    /// # );
+    /// #
+    /// # assert_eq!(
+    /// #     board
+    /// #         .you_move(coordinate::Direction::Down)
+    /// #         .you_move(coordinate::Direction::Up)
+    /// #         .triggered_targets(),
+    /// #     vec![&[4, 6]]
+    /// # );
+    /// #
+    /// assert!(!board
+    ///     .you_move(coordinate::Direction::Down)
+    ///     .you_move(coordinate::Direction::Up)
+    ///     .all_targets_triggered());
+    ///
+    /// assert!(board
+    ///     .you_move(coordinate::Direction::Down)
+    ///     .you_move(coordinate::Direction::Up)
+    ///     .you_move(coordinate::Direction::Left)
+    ///     .you_move(coordinate::Direction::Left)
+    ///     .you_move(coordinate::Direction::Right)
+    ///     .you_move(coordinate::Direction::Up)
+    ///     .you_move(coordinate::Direction::Up)
+    ///     .you_move(coordinate::Direction::Down)
+    ///     .you_move(coordinate::Direction::Right)
+    ///     .you_move(coordinate::Direction::Right)
+    ///     .all_targets_triggered());
+    /// ```
+    pub fn all_targets_triggered(&self) -> bool {
+        self.triggered == self.layers[EntityKind::Target].iter().count()
+    }
+
+    /// Checks if the board has been won
+    ///
+    /// A board is won once it has targets and all of them are
+    /// triggered.  From then on, it won't take any more moves; they
+    /// come out as [`MoveOutcome::GameOver`] until the win is undone.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// let board: Sokoban = "@0^".parse().unwrap();
+    /// let (board, outcome) = board.you_move_outcome(coordinate::Direction::Right);
+    ///
+    /// assert_eq!(outcome, MoveOutcome::Won);
+    /// assert!(board.is_won());
+    /// assert_eq!(
+    ///     board.you_move_outcome(coordinate::Direction::Left).1,
+    ///     MoveOutcome::GameOver
+    /// );
+    /// ```
+    pub fn is_won(&self) -> bool {
+        self.layers[EntityKind::Target].iter().next().is_some() && self.all_targets_triggered()
+    }
+
+    /// Checks if the board is won, lost, or failed, so no more moves
+    /// can be made on it
+    fn is_over(&self) -> bool {
+        self.is_won() || self.is_lost() || self.is_failed()
+    }
+
+    /// The positions of all the pushes that can never reach a target
+    ///
+    /// A push is deadlocked when it isn't on a target and either
+    ///
+    /// - it's somewhere it could never be pushed onto a target from,
+    ///   like against a wall with no target along it, or
+    /// - it's frozen, so it can't be pushed up or down and can't be
+    ///   pushed left or right.  That happens when there's a stop on
+    ///   either side of it, it'd be pushed somewhere it could never
+    ///   reach a target from either way, or it's up against another
+    ///   push that's frozen itself.  A push stuck in a corner is the
+    ///   simplest case of this, but pushes can also freeze each other
+    ///   in pairs or clusters along walls.
+    ///
+    /// If there are any, the board can't be won anymore, at least when
+    /// there are as many targets as pushes.  Switch walls don't count,
+    /// since they might open, and neither do you or your partner, since
+    /// you can walk away.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// let board: Sokoban = "\
+    /// ------
+    /// |0 00^
+    /// |@   ^"
+    ///     .parse()
+    ///     .unwrap();
+    ///
+    /// // the one in the corner, and the pair frozen against the wall
+    /// assert_eq!(
+    ///     board.deadlocked_pushes(),
+    ///     coordinate::I2Array::from(vec![[1, 1], [3, 1], [4, 1]])
+    /// );
+    /// ```
+    pub fn deadlocked_pushes(&self) -> coordinate::I2Array {
+        let live_cells: HashSet<coordinate::I2> = self.live_cells();
+        self.layers[EntityKind::Push]
+            .iter()
+            .filter(|push| !self.layers[EntityKind::Target].contains(push))
+            .filter(|push| !live_cells.contains(push) || self.is_frozen(**push, &live_cells, &[]))
+            .copied()
+            .collect()
+    }
+
+    /// All the tiles you could walk to without pushing anything
+    ///
+    /// This includes the tile you're on.  On a board that's open to
+    /// the outside, you could walk forever, so this only goes a tile
+    /// past the outermost thing on the board.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// let board: Sokoban = "\
+    /// -----
+    /// |@ 0 |
+    /// -----"
+    ///     .parse()
+    ///     .unwrap();
+    ///
+    /// assert_eq!(board.reachable_tiles(), coordinate::I2Array::from(vec![[1, 1], [2, 1]]));
+    /// ```
+    pub fn reachable_tiles(&self) -> coordinate::I2Array {
+        let bounds: Bounds = Bounds::new(self);
+        let closed_walls: coordinate::I2Array = self.closed_walls();
+        let is_open = |cell: &coordinate::I2| {
+            bounds.contains(cell)
+                && !self.layers[EntityKind::Stop].contains(cell)
+                && !closed_walls.contains(cell)
+                && !self.layers[EntityKind::Push].contains(cell)
+                && !self.clones.contains(cell)
+                && self.partner != Some(*cell)
+        };
+
+        let mut reachable: Vec<coordinate::I2> = vec![self.you];
+        let mut seen: HashSet<coordinate::I2> = HashSet::from([self.you]);
+        let mut next: usize = 0;
+        while let Some(cell) = reachable.get(next).copied() {
+            next += 1;
+            for neighbor in DIRECTIONS
+                .iter()
+                .filter_map(|direction| cell.nudge(*direction))
+            {
+                if is_open(&neighbor) && seen.insert(neighbor) {
+                    reachable.push(neighbor);
+                }
+            }
+        }
+        coordinate::I2Array::from_iter(reachable)
+    }
+
+    /// Work out if the board can be won without going over `limits`
+    ///
+    /// This is meant for catching broken levels before they ship.
+    /// Levels that obviously can't be won, like ones with fewer pushes
+    /// that could reach a target than there are targets, are caught
+    /// straight away, and otherwise this runs
+    /// [`solver::iterative_deepening`].
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// let limits: solver::Limits = solver::Limits {
+    ///     nodes: None,
+    ///     time: Some(Duration::from_secs(1)),
+    /// };
+    ///
+    /// assert_eq!(
+    ///     "@0^".parse::<Sokoban>().unwrap().is_solvable(limits),
+    ///     Solvability::Solvable
+    /// );
+    /// assert_eq!(
+    ///     "@^^0".parse::<Sokoban>().unwrap().is_solvable(limits),
+    ///     Solvability::Unsolvable
+    /// );
+    /// ```
+    pub fn is_solvable(&self, limits: solver::Limits) -> Solvability {
+        // deadlocks are only found by following the plain rules of
+        // pushing, so anything else that moves or makes pushes could
+        // get one out of a place it'd otherwise be stuck in
+        // and looking for them means looking at every tile around the
+        // board, which would never finish on a board that's spread out
+        let deadlocked: usize = if self.has_more_than_pushing() || !Bounds::new(self).is_small() {
+            0
+        } else {
+            self.deadlocked_pushes().iter().count()
+        };
+        if self.layers[EntityKind::Push].iter().count() - deadlocked
+            < self.layers[EntityKind::Target].iter().count()
+        {
+            return Solvability::Unsolvable;
+        }
+
+        match solver::iterative_deepening(self, limits) {
+            solver::SolverResult::Solved(_) => Solvability::Solvable,
+            solver::SolverResult::Unsolvable => Solvability::Unsolvable,
+            solver::SolverResult::Timeout => Solvability::Unknown,
+        }
+    }
+
+    /// Find the first move of a way to win from here without going
+    /// over `limits`
+    ///
+    /// This is for showing a stuck player one move at a time.  It's
+    /// `None` if the board is already won, can't be won, or couldn't be
+    /// worked out in time.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// let board: Sokoban = "0@ ^".parse().unwrap();
+    ///
+    /// assert_eq!(
+    ///     board.hint(solver::Limits::default()),
+    ///     Some(coordinate::Direction::Right)
+    /// );
+    /// ```
+    pub fn hint(&self, limits: solver::Limits) -> Option<coordinate::Direction> {
+        match solver::iterative_deepening(self, limits) {
+            solver::SolverResult::Solved(moves) => moves.first().copied(),
+            solver::SolverResult::Unsolvable | solver::SolverResult::Timeout => None,
+        }
+    }
+
+    /// Whether anything on the board moves, makes, or changes pushes
+    /// other than you pushing them, which the deadlock checks don't
+    /// account for
+    fn has_more_than_pushing(&self) -> bool {
+        !self.magnets.is_empty()
+            || !self.teleporters.is_empty()
+            || !self.durability.is_empty()
+            || self.clones.iter().next().is_some()
+            || self.layers[EntityKind::Sticky].iter().next().is_some()
+            || self.layers[EntityKind::Mergeable].iter().next().is_some()
+            || self.layers[EntityKind::Splitter].iter().next().is_some()
+    }
+
+    /// The cells a push could be pushed from onto a target, if nothing
+    /// else were in the way
+    fn live_cells(&self) -> HashSet<coordinate::I2> {
+        let bounds: Bounds = Bounds::new(self);
+        let mut live_cells: HashSet<coordinate::I2> = self.layers[EntityKind::Target]
+            .iter()
+            .filter(|target| !self.layers[EntityKind::Stop].contains(target))
+            .copied()
+            .collect();
+        let mut frontier: Vec<coordinate::I2> = live_cells.iter().copied().collect();
+        // work backward from the targets, finding where a push could
+        // have come from and where you'd have stood to push it
+        while let Some(cell) = frontier.pop() {
+            for direction in DIRECTIONS {
+                let Some(from) = cell.nudge(direction) else {
+                    continue;
+                };
+                let Some(standing) = from.nudge(direction) else {
+                    continue;
+                };
+                let is_open = |cell: &coordinate::I2| {
+                    bounds.contains(cell) && !self.layers[EntityKind::Stop].contains(cell)
+                };
+                if is_open(&from) && is_open(&standing) && live_cells.insert(from) {
+                    frontier.push(from);
+                }
+            }
+        }
+        live_cells
+    }
+
+    /// Checks if the push at `push` can't be pushed along either axis
+    ///
+    /// The pushes in `walls` have already been found to be blocked
+    /// along one axis, so they're treated like stops.
+    fn is_frozen(
+        &self,
+        push: coordinate::I2,
+        live_cells: &HashSet<coordinate::I2>,
+        walls: &[coordinate::I2],
+    ) -> bool {
+        let mut walls: Vec<coordinate::I2> = walls.to_vec();
+        walls.push(push);
+        [
+            [coordinate::Direction::Up, coordinate::Direction::Down],
+            [coordinate::Direction::Left, coordinate::Direction::Right],
+        ]
+        .into_iter()
+        .all(|axis| {
+            let sides: [Option<coordinate::I2>; 2] = axis.map(|direction| push.nudge(direction));
+            sides.iter().any(|side| match side {
+                Some(side) => self.layers[EntityKind::Stop].contains(side) || walls.contains(side),
+                // nothing can get past the integer limits either
+                None => true,
+            }) || sides
+                .iter()
+                .all(|side| !side.is_some_and(|side| live_cells.contains(&side)))
+                || sides.iter().flatten().any(|side| {
+                    self.layers[EntityKind::Push].contains(side)
+                        && self.is_frozen(*side, live_cells, &walls)
+                })
+        })
+    }
+
+    /// Gets the position of the player
+    pub fn you(&self) -> coordinate::I2 {
+        self.you
+    }
+
+    /// Gets the position of the second player, if there is one
+    pub fn partner(&self) -> Option<coordinate::I2> {
+        self.partner
+    }
+
+    /// Gets the positions of all the stopping collision
+    pub fn stops(&self) -> coordinate::I2Array {
+        coordinate::I2Array::clone(&self.layers[EntityKind::Stop])
+    }
+
+    /// Gets the positions of all the pushable objects
+    pub fn pushes(&self) -> coordinate::I2Array {
+        self.layers[EntityKind::Push].clone()
+    }
+
+    /// Gets the positions of all the targets for the pushable objects
+    pub fn targets(&self) -> coordinate::I2Array {
+        coordinate::I2Array::clone(&self.layers[EntityKind::Target])
+    }
+
+    /// Gets everything on the board along with what kind of thing it is
+    ///
+    /// Floor comes first and players come last, so drawing each cell
+    /// over the last leaves the topmost thing showing.  A push on a
+    /// target it triggers is a [`Entity::TriggeredTarget`] rather than
+    /// both a push and a target.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// let board: Sokoban = "@0^".parse().unwrap();
+    ///
+    /// assert_eq!(
+    ///     board.cells().collect::<Vec<(coordinate::I2, Entity)>>(),
+    ///     vec![
+    ///         (coordinate::I2::new(2, 0), Entity::Target),
+    ///         (coordinate::I2::new(1, 0), Entity::Push),
+    ///         (coordinate::I2::new(0, 0), Entity::You),
+    ///     ]
+    /// );
+    /// ```
+    pub fn cells(&self) -> impl Iterator<Item = (coordinate::I2, Entity)> {
+        let triggered: Vec<coordinate::I2> =
+            self.triggered_targets().into_iter().copied().collect();
+        let mut cells: Vec<(coordinate::I2, Entity)> = Vec::new();
+        cells.extend(
+            self.layers[EntityKind::Ice]
+                .iter()
+                .map(|ice| (*ice, Entity::Ice)),
+        );
+        cells.extend(
+            self.items
+                .iter()
+                .map(|item| (item.at, Entity::Item(item.kind))),
+        );
+        cells.extend(
+            self.layers[EntityKind::Crack]
+                .iter()
+                .map(|crack| (*crack, Entity::Crack)),
+        );
+        cells.extend(
+            self.switches
+                .iter()
+                .map(|switch| (switch.at, Entity::Switch)),
+        );
+        cells.extend(
+            self.teleporters
+                .iter()
+                .flat_map(|(from, to)| [(*from, Entity::Teleporter), (*to, Entity::Teleporter)]),
+        );
+        cells.extend(
+            self.layers[EntityKind::Stop]
+                .iter()
+                .map(|stop| (*stop, Entity::Stop)),
+        );
+        cells.extend(self.closed_walls().iter().map(|wall| (*wall, Entity::Wall)));
+        cells.extend(
+            self.magnets
+                .iter()
+                .map(|magnet| (magnet.at, Entity::Magnet(magnet.facing))),
+        );
+        cells.extend(
+            self.layers[EntityKind::Target]
+                .iter()
+                .filter(|target| !triggered.contains(target))
+                .map(|target| (*target, Entity::Target)),
+        );
+        cells.extend(
+            self.layers[EntityKind::Push]
+                .iter()
+                .filter(|push| !triggered.contains(push))
+                .map(|push| (*push, Entity::Push)),
+        );
+        cells.extend(
+            triggered
+                .iter()
+                .map(|target| (*target, Entity::TriggeredTarget)),
+        );
+        cells.extend(self.enemies.iter().map(|enemy| (enemy.at, Entity::Enemy)));
+        cells.extend(self.clones.iter().map(|clone| (*clone, Entity::Clone)));
+        cells.extend(self.partner.map(|partner| (partner, Entity::Partner)));
+        cells.push((self.you, Entity::You));
+        cells.into_iter()
+    }
+
+    /// Draw the board as it is now in the XSB level format
+    ///
+    /// This is the same as formatting the board with [`std::fmt::Display`].
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// let board: Sokoban = Sokoban::new(
+    ///     coordinate::I2::new(1, 1),
+    ///     coordinate::I2Array::from(vec![[0, 0], [1, 0], [2, 0], [3, 0], [0, 1], [0, 2], [1, 2], [2, 2], [3, 2]]),
+    ///     coordinate::I2Array::from(vec![[2, 1]]),
+    ///     coordinate::I2Array::from(vec![[3, 1]]),
+    /// );
+    ///
+    /// assert_eq!(board.to_xsb(), "####\n#@$.\n####");
+    /// ```
+    pub fn to_xsb(&self) -> String {
+        self.to_string()
+    }
+
+    /// Read a board from the XSB level format
+    ///
+    /// That's `#` for stops, `@` for you, `$` for pushes, and `.` for
+    /// targets, with `+` and `*` for you and pushes on targets, and a
+    /// space, `-`, or `_` for empty floor.  Each line is a row, starting
+    /// from the origin in the top left.  There must be exactly one of
+    /// you, and any other characters will `Err`.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// let board: Sokoban = Sokoban::from_xsb("####\n#@$.\n####").unwrap();
+    ///
+    /// assert!(board.you_move(coordinate::Direction::Right).all_targets_triggered());
+    /// ```
+    pub fn from_xsb(xsb: &str) -> Result<Sokoban, &'static str> {
+        Sokoban::from_drawing(xsb, |character| match character {
+            '#' => Some(Glyph::STOP),
+            '@' => Some(Glyph::YOU),
+            '+' => Some(Glyph::YOU.on_target()),
+            '$' => Some(Glyph::PUSH),
+            '*' => Some(Glyph::PUSH.on_target()),
+            '.' => Some(Glyph::TARGET),
+            ' ' | '-' | '_' => Some(Glyph::FLOOR),
+            _ => None,
+        })
+    }
+
+    /// Read a board drawn with a character for each tile, which `glyph`
+    /// says what goes on, or `None` if it isn't a board character
+    ///
+    /// This is what both [`Sokoban::from_xsb`] and [`Sokoban::from_str`]
+    /// read with, so they check boards over and go wrong the same way.
+    fn from_drawing(
+        drawing: &str,
+        glyph: impl Fn(char) -> Option<Glyph>,
+    ) -> Result<Sokoban, &'static str> {
+        let mut you: Option<coordinate::I2> = None;
+        let mut stops: coordinate::I2Array = coordinate::I2Array::from(vec![]);
+        let mut pushes: coordinate::I2Array = coordinate::I2Array::from(vec![]);
+        let mut targets: coordinate::I2Array = coordinate::I2Array::from(vec![]);
+
+        for (y, row) in drawing.lines().enumerate() {
+            for (x, character) in row.chars().enumerate() {
+                let cell: coordinate::I2 = coordinate::I2::new(
+                    x.try_into().map_err(|_| "The board is too wide")?,
+                    y.try_into().map_err(|_| "The board is too tall")?,
+                );
+                let glyph: Glyph = glyph(character).ok_or("Not a board character")?;
+                if glyph.you {
+                    if you.is_some() {
+                        return Err("There's more than one of you");
+                    }
+                    you = Some(cell);
+                }
+                if glyph.stop {
+                    stops.push(cell);
+                }
+                if glyph.push {
+                    pushes.push(cell);
+                }
+                if glyph.target {
+                    targets.push(cell);
+                }
+            }
+        }
+
+        Ok(Sokoban::new(
+            you.ok_or("There's none of you")?,
+            stops,
+            pushes,
+            targets,
+        ))
+    }
+
+    /// The same board moved so the top left of everything on it is at
+    /// the origin
+    ///
+    /// That's the top left of the smallest rectangle containing you,
+    /// your clones and partner, stops, pushes, and targets.  This lets
+    /// boards be compared and hashed without caring where they happened
+    /// to be drawn.  The moves that can be undone and the events of the
+    /// last move are left behind.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// let board: Sokoban = "@0^".parse().unwrap();
+    ///
+    /// assert_eq!(
+    ///     board.clone().with_you([5, -3]).normalized(),
+    ///     board.with_you([0, 0]).normalized(),
+    /// );
+    /// ```
+    pub fn normalized(&self) -> Sokoban {
+        let everything: Vec<coordinate::I2> = std::iter::once(self.you)
+            .chain(self.partner)
+            .chain(self.clones.iter().copied())
+            .chain(self.layers[EntityKind::Stop].iter().copied())
+            .chain(self.layers[EntityKind::Push].iter().copied())
+            .chain(self.layers[EntityKind::Target].iter().copied())
+            .collect();
+        let left: i32 = everything.iter().map(|cell| cell.x()).min().unwrap();
+        let top: i32 = everything.iter().map(|cell| cell.y()).min().unwrap();
+        self.transform(
+            &|cell| {
+                coordinate::I2::new(cell.x().saturating_sub(left), cell.y().saturating_sub(top))
+            },
+            &|direction| direction,
+        )
+    }
+
+    /// A number that identifies the level this board started as
+    ///
+    /// It's worked out from the board before any of the moves that can
+    /// be undone, moved to the origin with [`Sokoban::normalized`], so
+    /// it's the same however far into the level you are and wherever
+    /// the level was drawn.  It's also the same on every platform and
+    /// every run, so it's fit for keying save files and leaderboards.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// let board: Sokoban = "@0 ^".parse().unwrap();
+    ///
+    /// assert_eq!(
+    ///     board.you_move(coordinate::Direction::Right).level_id(),
+    ///     board.level_id()
+    /// );
+    /// assert_ne!("@0^ ".parse::<Sokoban>().unwrap().level_id(), board.level_id());
+    /// ```
+    pub fn level_id(&self) -> u64 {
+        let mut start: &Sokoban = self;
+        while let Some(previous) = &start.previous {
+            start = previous;
+        }
+        let board: Sokoban = start.normalized();
+
+        let cell = |cell: &coordinate::I2| format!("{},{}", cell.x(), cell.y());
+        let pair = |(at, value): &(coordinate::I2, u32)| format!("{}={}", cell(at), value);
+        let mut layout: String = board.to_xsb();
+        let mut section = |name: &str, mut entries: Vec<String>| {
+            if entries.is_empty() {
+                return;
+            }
+            entries.sort();
+            layout.push_str(&format!("\n{}: {}", name, entries.join(" ")));
+        };
+        section("partner", board.partner.iter().map(cell).collect());
+        section("clones", board.clones.iter().map(cell).collect());
+        section("costs", board.costs.iter().map(pair).collect());
+        section("durability", board.durability.iter().map(pair).collect());
+        if !board.durability.is_empty() {
+            section("breakage", vec![format!("{:?}", board.breakage)]);
+        }
+        section("colors", board.colors.iter().map(pair).collect());
+        section(
+            "target colors",
+            board.target_colors.iter().map(pair).collect(),
+        );
+        section(
+            "heavies",
+            board.layers[EntityKind::Heavy].iter().map(cell).collect(),
+        );
+        section(
+            "stickies",
+            board.layers[EntityKind::Sticky].iter().map(cell).collect(),
+        );
+        section(
+            "mergeables",
+            board.layers[EntityKind::Mergeable]
+                .iter()
+                .map(cell)
+                .collect(),
+        );
+        section("sizes", board.sizes.iter().map(pair).collect());
+        section(
+            "merge table",
+            board
+                .merge_rules
+                .iter()
+                .flat_map(|rules| {
+                    rules
+                        .table
+                        .iter()
+                        .map(|(from, to)| format!("{}>{}", from, to))
+                })
+                .collect(),
+        );
+        section(
+            "splitters",
+            board.layers[EntityKind::Splitter]
+                .iter()
+                .map(cell)
+                .collect(),
+        );
+        section(
+            "push limit",
+            board.push_limit.iter().map(usize::to_string).collect(),
+        );
+        section(
+            "move limit",
+            board.move_limit.iter().map(u32::to_string).collect(),
+        );
+        section(
+            "cracks",
+            board.layers[EntityKind::Crack].iter().map(cell).collect(),
+        );
+        section(
+            "switches",
+            board
+                .switches
+                .iter()
+                .map(|switch| {
+                    let mut walls: Vec<String> = switch.walls.iter().map(cell).collect();
+                    walls.sort();
+                    format!("{}>{}", cell(&switch.at), walls.join("/"))
+                })
+                .collect(),
+        );
+        section(
+            "magnets",
+            board
+                .magnets
+                .iter()
+                .map(|magnet| format!("{}>{:?}", cell(&magnet.at), magnet.facing))
+                .collect(),
+        );
+        section(
+            "ices",
+            board.layers[EntityKind::Ice].iter().map(cell).collect(),
+        );
+        section(
+            "teleporters",
+            board
+                .teleporters
+                .iter()
+                .map(|(a, b)| format!("{}>{}", cell(a), cell(b)))
+                .collect(),
+        );
+        section(
+            "enemies",
+            board
+                .enemies
+                .iter()
+                .map(|enemy| format!("{}>{:?}", cell(&enemy.at), enemy.movement))
+                .collect(),
+        );
+        section(
+            "items",
+            board
+                .items
+                .iter()
+                .map(|item| format!("{}>{}/{}", cell(&item.at), item.kind, item.collectible))
+                .collect(),
+        );
+
+        // 64 bit FNV-1a, which unlike the standard library's hashers is
+        // the same everywhere and forever
+        layout
+            .bytes()
+            .fold(0xcbf2_9ce4_8422_2325, |hash: u64, byte: u8| {
+                (hash ^ u64::from(byte)).wrapping_mul(0x0100_0000_01b3)
+            })
+    }
+
+    /// The same board turned a quarter turn clockwise about the origin
+    ///
+    /// Everything that faces or moves some way is turned along with it,
+    /// so playing the turned board is just like playing the original
+    /// with the directions turned.  Like the other transforms, this
+    /// leaves history behind, and you may want to follow it with
+    /// [`Sokoban::normalized`].
+    pub fn rotate_cw(&self) -> Sokoban {
+        self.transform(
+            &|cell| coordinate::I2::new(cell.y().saturating_neg(), cell.x()),
+            &|direction| match direction {
+                coordinate::Direction::Up => coordinate::Direction::Right,
+                coordinate::Direction::Right => coordinate::Direction::Down,
+                coordinate::Direction::Down => coordinate::Direction::Left,
+                coordinate::Direction::Left => coordinate::Direction::Up,
+            },
+        )
+    }
+
+    /// The same board turned a quarter turn counterclockwise about the
+    /// origin
+    pub fn rotate_ccw(&self) -> Sokoban {
+        self.transform(
+            &|cell| coordinate::I2::new(cell.y(), cell.x().saturating_neg()),
+            &|direction| match direction {
+                coordinate::Direction::Up => coordinate::Direction::Left,
+                coordinate::Direction::Left => coordinate::Direction::Down,
+                coordinate::Direction::Down => coordinate::Direction::Right,
+                coordinate::Direction::Right => coordinate::Direction::Up,
+            },
+        )
+    }
+
+    /// The same board flipped left for right about the origin
+    pub fn mirror_horizontal(&self) -> Sokoban {
+        self.transform(
+            &|cell| coordinate::I2::new(cell.x().saturating_neg(), cell.y()),
+            &|direction| match direction {
+                coordinate::Direction::Left => coordinate::Direction::Right,
+                coordinate::Direction::Right => coordinate::Direction::Left,
+                vertical => vertical,
+            },
+        )
+    }
+
+    /// The same board flipped top for bottom about the origin
+    pub fn mirror_vertical(&self) -> Sokoban {
+        self.transform(
+            &|cell| coordinate::I2::new(cell.x(), cell.y().saturating_neg()),
+            &|direction| match direction {
+                coordinate::Direction::Up => coordinate::Direction::Down,
+                coordinate::Direction::Down => coordinate::Direction::Up,
+                horizontal => horizontal,
+            },
+        )
+    }
+
+    /// The same board with every coordinate passed through `cell` and
+    /// every direction through `direction`, leaving history behind
+    fn transform(
+        &self,
+        cell: &dyn Fn(coordinate::I2) -> coordinate::I2,
+        direction: &dyn Fn(coordinate::Direction) -> coordinate::Direction,
+    ) -> Sokoban {
+        let cells = |cells: &coordinate::I2Array| -> coordinate::I2Array {
+            cells.iter().map(|at| cell(*at)).collect()
+        };
+        let pairs = |pairs: &[(coordinate::I2, u32)]| -> Vec<(coordinate::I2, u32)> {
+            pairs
+                .iter()
+                .map(|(at, value)| (cell(*at), *value))
+                .collect()
+        };
+        Sokoban {
+            you: cell(self.you),
+            layers: self.layers.transformed(cell),
+            partner: self.partner.map(cell),
+            clones: cells(&self.clones),
+            costs: pairs(&self.costs),
+            durability: pairs(&self.durability),
+            colors: pairs(&self.colors),
+            sizes: pairs(&self.sizes),
+            target_colors: pairs(&self.target_colors),
+            switches: self
+                .switches
+                .iter()
+                .map(|switch| Switch {
+                    at: cell(switch.at),
+                    walls: cells(&switch.walls),
+                })
+                .collect(),
+            magnets: self
+                .magnets
+                .iter()
+                .map(|magnet| Magnet {
+                    at: cell(magnet.at),
+                    facing: direction(magnet.facing),
+                })
+                .collect(),
+            teleporters: self
+                .teleporters
+                .iter()
+                .map(|(a, b)| (cell(*a), cell(*b)))
+                .collect(),
+            enemies: self
+                .enemies
+                .iter()
+                .map(|enemy| Enemy {
+                    at: cell(enemy.at),
+                    movement: match &enemy.movement {
+                        Movement::Chase => Movement::Chase,
+                        Movement::Patrol { route, next } => Movement::Patrol {
+                            route: route.iter().map(|step| direction(*step)).collect(),
+                            next: *next,
+                        },
+                    },
+                })
+                .collect(),
+            items: self
+                .items
+                .iter()
+                .map(|item| Item {
+                    at: cell(item.at),
+                    ..*item
+                })
+                .collect(),
+            collected: self
+                .collected
+                .iter()
+                .map(|item| Item {
+                    at: cell(item.at),
+                    ..*item
+                })
+                .collect(),
+            history: self
+                .history
+                .as_ref()
+                .map(|history| history.iter().map(|step| direction(*step)).collect()),
+            events: vec![],
+            turn_events: vec![],
+            previous: None,
+            ..self.clone()
+        }
+    }
+}
+
+impl std::str::FromStr for Sokoban {
+    type Err = &'static str;
+
+    /// Reads a board from a diagram like the ones in the docs
+    ///
+    /// That's `@` for you, `0` for pushes, `-` or `|` for stops, `^` for
+    /// targets, and `.` or a space for empty floor.  Each line is a row,
+    /// starting from the origin in the top left.  There must be exactly
+    /// one of you, and any other characters will `Err`.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// let board: Sokoban = "\
+    /// -----
+    /// |@0^|
+    /// -----"
+    ///     .parse()
+    ///     .unwrap();
+    ///
+    /// assert!(board.you_move(coordinate::Direction::Right).all_targets_triggered());
+    /// ```
+    fn from_str(diagram: &str) -> Result<Self, Self::Err> {
+        Sokoban::from_drawing(diagram, |character| match character {
+            '@' => Some(Glyph::YOU),
+            '0' => Some(Glyph::PUSH),
+            '-' | '|' => Some(Glyph::STOP),
+            '^' => Some(Glyph::TARGET),
+            '.' | ' ' => Some(Glyph::FLOOR),
+            _ => None,
+        })
+    }
+}
+
+/// What one character of a drawn board puts on its tile
+#[derive(Debug, Clone, Copy)]
+struct Glyph {
+    you: bool,
+    stop: bool,
+    push: bool,
+    target: bool,
+}
+
+impl Glyph {
+    const FLOOR: Glyph = Glyph {
+        you: false,
+        stop: false,
+        push: false,
+        target: false,
+    };
+    const YOU: Glyph = Glyph {
+        you: true,
+        ..Glyph::FLOOR
+    };
+    const STOP: Glyph = Glyph {
+        stop: true,
+        ..Glyph::FLOOR
+    };
+    const PUSH: Glyph = Glyph {
+        push: true,
+        ..Glyph::FLOOR
+    };
+    const TARGET: Glyph = Glyph {
+        target: true,
+        ..Glyph::FLOOR
+    };
+
+    /// The same, but with a target underneath
+    const fn on_target(self) -> Glyph {
+        Glyph {
+            target: true,
+            ..self
+        }
+    }
+}
+
+impl std::fmt::Display for Sokoban {
+    /// Draws the board in the XSB level format
+    ///
+    /// That's `#` for stops, `@` for you, `$` for pushes, and `.` for
+    /// targets, with `+` and `*` for you and pushes on targets.  Rows
+    /// are separated by newlines and have their trailing floor trimmed.
+    /// The top left of the drawing is the top left of the smallest
+    /// rectangle containing everything on the board.
+    ///
+    /// XSB has no way to draw a second player or the newer kinds of
+    /// tiles, so they're left out, except for closed switch walls,
+    /// which are drawn as stops.
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let closed_walls: coordinate::I2Array = self.closed_walls();
+        let everything: Vec<coordinate::I2> = std::iter::once(self.you)
+            .chain(self.layers[EntityKind::Stop].iter().copied())
+            .chain(closed_walls.iter().copied())
+            .chain(self.layers[EntityKind::Push].iter().copied())
+            .chain(self.layers[EntityKind::Target].iter().copied())
+            .collect();
+        let left: i32 = everything.iter().map(|cell| cell.x()).min().unwrap();
+        let right: i32 = everything.iter().map(|cell| cell.x()).max().unwrap();
+        let top: i32 = everything.iter().map(|cell| cell.y()).min().unwrap();
+        let bottom: i32 = everything.iter().map(|cell| cell.y()).max().unwrap();
+
+        for y in top..=bottom {
+            let row: String = (left..=right)
+                .map(|x| {
+                    let cell: coordinate::I2 = coordinate::I2::new(x, y);
+                    let is_target: bool = self.layers[EntityKind::Target].contains(&cell);
+                    if self.layers[EntityKind::Stop].contains(&cell) || closed_walls.contains(&cell)
+                    {
+                        '#'
+                    } else if self.you == cell {
+                        if is_target {
+                            '+'
+                        } else {
+                            '@'
+                        }
+                    } else if self.layers[EntityKind::Push].contains(&cell) {
+                        if is_target {
+                            '*'
+                        } else {
+                            '$'
+                        }
+                    } else if is_target {
+                        '.'
+                    } else {
+                        ' '
+                    }
+                })
+                .collect();
+            f.write_str(row.trim_end())?;
+            if y != bottom {
+                f.write_str("\n")?;
+            }
+        }
+        Ok(())
+    }
+}
+
+/// The area around everything on a board, and a tile further out
+///
+/// Boards don't have edges, so this stands in for one when looking at
+/// where things could go, such as when solving.
+struct Bounds {
+    left: i32,
+    right: i32,
+    top: i32,
+    bottom: i32,
+}
+
+impl Bounds {
+    fn new(board: &Sokoban) -> Bounds {
+        let everything: Vec<coordinate::I2> = std::iter::once(board.you)
+            .chain(board.clones.iter().copied())
+            .chain(board.layers[EntityKind::Stop].iter().copied())
+            .chain(board.layers[EntityKind::Push].iter().copied())
+            .chain(board.layers[EntityKind::Target].iter().copied())
+            .collect();
+        let xs = || everything.iter().map(|cell| cell.x());
+        let ys = || everything.iter().map(|cell| cell.y());
+        Bounds {
+            left: xs().min().unwrap().saturating_sub(1),
+            right: xs().max().unwrap().saturating_add(1),
+            top: ys().min().unwrap().saturating_sub(1),
+            bottom: ys().max().unwrap().saturating_add(1),
+        }
+    }
+
+    /// Checks if there are few enough tiles in the area to look at
+    /// each of them, which is as many as a [`bitboard::Bitboard`] can
+    /// cover
+    fn is_small(&self) -> bool {
+        let width: u64 = u64::from(self.right.abs_diff(self.left)) + 1;
+        let height: u64 = u64::from(self.bottom.abs_diff(self.top)) + 1;
+        width * height <= bitboard::MAX_AREA as u64
+    }
+
+    fn contains(&self, cell: &coordinate::I2) -> bool {
+        (self.left..=self.right).contains(&cell.x()) && (self.top..=self.bottom).contains(&cell.y())
+    }
+
+    fn contains_board(&self, board: &Sokoban) -> bool {
+        self.contains(&board.you)
+            && board.clones.iter().all(|clone| self.contains(clone))
+            && board.layers[EntityKind::Push]
+                .iter()
+                .all(|push| self.contains(push))
+    }
+}
+
+impl PartialEq for Sokoban {
+    /// Checks that everything on the boards is the same
+    ///
+    /// The events of the last move, how many moves have been made, the
+    /// recorded history, and the moves that can be undone aren't part
+    /// of the board, so two boards reached in different ways are still
+    /// equal.
+    fn eq(&self, other: &Self) -> bool {
+        self.you == other.you
+            && self.layers == other.layers
+            && self.partner == other.partner
+            && self.clones == other.clones
+            && self.costs == other.costs
+            && self.durability == other.durability
+            && self.colors == other.colors
+            && self.sizes == other.sizes
+            && self.target_colors == other.target_colors
+            && self.breakage == other.breakage
+            && self.push_limit == other.push_limit
+            && self.merge_rules == other.merge_rules
+            && self.switches == other.switches
+            && self.magnets == other.magnets
+            && self.teleporters == other.teleporters
+            && self.enemies == other.enemies
+            && self.items == other.items
+            && self.collected == other.collected
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn you_move_in_all_cardinal_directions_directions() {
+        // .....
+        // .@.0^
+        // ..+..
+        // .0.+.
+        // .^...
+        let you: coordinate::I2 = coordinate::I2::new(1, 1);
+        let stops: coordinate::I2Array = coordinate::I2Array::from(vec![[2, 2], [3, 3]]);
+        let pushes: coordinate::I2Array = coordinate::I2Array::from(vec![[3, 1], [1, 3]]);
+        let targets: coordinate::I2Array = coordinate::I2Array::from(vec![[4, 1], [1, 4]]);
 
         let you_up: coordinate::I2 = coordinate::I2::new(1, 0);
         let you_left: coordinate::I2 = coordinate::I2::new(0, 1);
         let you_down: coordinate::I2 = coordinate::I2::new(1, 2);
         let you_right: coordinate::I2 = coordinate::I2::new(2, 1);
 
-        let board: Sokoban = Sokoban::new(you, stops.clone(), pushes.clone(), targets.clone());
+        let board: Sokoban = Sokoban::new(you, stops.clone(), pushes.clone(), targets.clone());
+
+        assert_eq!(
+            board.you_move(coordinate::Direction::Up),
+            Sokoban::new(you_up, stops.clone(), pushes.clone(), targets.clone())
+        );
+        assert_eq!(
+            board.you_move(coordinate::Direction::Left),
+            Sokoban::new(you_left, stops.clone(), pushes.clone(), targets.clone())
+        );
+        assert_eq!(
+            board.you_move(coordinate::Direction::Down),
+            Sokoban::new(you_down, stops.clone(), pushes.clone(), targets.clone())
+        );
+        assert_eq!(
+            board.you_move(coordinate::Direction::Right),
+            Sokoban::new(you_right, stops.clone(), pushes.clone(), targets.clone())
+        );
+    }
+
+    #[test]
+    fn you_dont_move_into_stop() {
+        // ...0.^^
+        // ...-...
+        // .0|@|0.
+        // ...-...
+        // ...0...
+        let you: coordinate::I2 = coordinate::I2::new(3, 3);
+        let stops: coordinate::I2Array =
+            coordinate::I2Array::from(vec![[3, 2], [2, 3], [3, 4], [4, 3]]);
+        let pushes: coordinate::I2Array =
+            coordinate::I2Array::from(vec![[3, 1], [1, 3], [3, 5], [5, 3]]);
+        let targets: coordinate::I2Array = coordinate::I2Array::from(vec![[6, 1], [7, 1]]);
+
+        let board: Sokoban =
+            Sokoban::new(you.clone(), stops.clone(), pushes.clone(), targets.clone());
+        assert_eq!(
+            board.you_move(coordinate::Direction::Up),
+            Sokoban::new(you.clone(), stops.clone(), pushes.clone(), targets.clone())
+        );
+        assert_eq!(
+            board.you_move(coordinate::Direction::Left),
+            Sokoban::new(you.clone(), stops.clone(), pushes.clone(), targets.clone())
+        );
+        assert_eq!(
+            board.you_move(coordinate::Direction::Down),
+            Sokoban::new(you.clone(), stops.clone(), pushes.clone(), targets.clone())
+        );
+        assert_eq!(
+            board.you_move(coordinate::Direction::Right),
+            Sokoban::new(you.clone(), stops.clone(), pushes.clone(), targets.clone())
+        );
+    }
+
+    #[test]
+    fn pushes_move_when_you_walk_into_them() {
+        // --...^^
+        // ...0...
+        // ..0@0..
+        // ...0...
+        // .......
+        let you: coordinate::I2 = coordinate::I2::new(3, 3);
+        let you_up: coordinate::I2 = coordinate::I2::new(3, 2);
+        let you_left: coordinate::I2 = coordinate::I2::new(2, 3);
+        let you_down: coordinate::I2 = coordinate::I2::new(3, 4);
+        let you_right: coordinate::I2 = coordinate::I2::new(4, 3);
+
+        let pushes: coordinate::I2Array =
+            coordinate::I2Array::from(vec![[3, 2], [2, 3], [3, 4], [4, 3]]);
+        let pushes_up: coordinate::I2Array =
+            coordinate::I2Array::from(vec![[3, 1], [2, 3], [3, 4], [4, 3]]);
+        let pushes_left: coordinate::I2Array =
+            coordinate::I2Array::from(vec![[3, 2], [1, 3], [3, 4], [4, 3]]);
+        let pushes_down: coordinate::I2Array =
+            coordinate::I2Array::from(vec![[3, 2], [2, 3], [3, 5], [4, 3]]);
+        let pushes_right: coordinate::I2Array =
+            coordinate::I2Array::from(vec![[3, 2], [2, 3], [3, 4], [5, 3]]);
+
+        let stops: coordinate::I2Array = coordinate::I2Array::from(vec![[1, 1], [2, 1]]);
+        let targets: coordinate::I2Array = coordinate::I2Array::from(vec![[6, 1], [7, 1]]);
+
+        let board = Sokoban::new(you, stops.clone(), pushes.clone(), targets.clone());
+
+        assert_eq!(
+            board.you_move(coordinate::Direction::Up),
+            Sokoban::new(you_up, stops.clone(), pushes_up.clone(), targets.clone())
+        );
+        assert_eq!(
+            board.you_move(coordinate::Direction::Left),
+            Sokoban::new(
+                you_left,
+                stops.clone(),
+                pushes_left.clone(),
+                targets.clone()
+            )
+        );
+        assert_eq!(
+            board.you_move(coordinate::Direction::Down),
+            Sokoban::new(
+                you_down,
+                stops.clone(),
+                pushes_down.clone(),
+                targets.clone()
+            )
+        );
+        assert_eq!(
+            board.you_move(coordinate::Direction::Right),
+            Sokoban::new(
+                you_right,
+                stops.clone(),
+                pushes_right.clone(),
+                targets.clone()
+            )
+        );
+    }
+
+    #[test]
+    fn push_moves_when_push_is_pushed_into_it() {
+        // .....
+        // ..0..
+        // ..0..
+        // ..0..
+        // ..0..
+        // ..@..
+        let you: coordinate::I2 = coordinate::I2::new(0, 5);
+        let you_final: coordinate::I2 = coordinate::I2::new(0, 4);
+        let pushes: coordinate::I2Array =
+            coordinate::I2Array::from(vec![[0, 1], [0, 2], [0, 3], [0, 4]]);
+        let pushes_final: coordinate::I2Array =
+            coordinate::I2Array::from(vec![[0, 0], [0, 1], [0, 2], [0, 3]]);
+        let stops: coordinate::I2Array = coordinate::I2Array::from(vec![]);
+        let targets: coordinate::I2Array = coordinate::I2Array::from(vec![]);
+
+        assert_eq!(
+            Sokoban::new(you, stops.clone(), pushes, targets.clone())
+                .you_move(coordinate::Direction::Up),
+            Sokoban::new(you_final, stops.clone(), pushes_final, targets.clone())
+        );
+    }
+
+    #[test]
+    fn push_doesnt_move_when_push_is_pushed_into_it_but_theres_a_stop() {
+        // ..-..
+        // ..0..
+        // ..0..
+        // ..0..
+        // ..0..
+        // ..@..
+        let you: coordinate::I2 = coordinate::I2::new(0, 5);
+        let pushes: coordinate::I2Array =
+            coordinate::I2Array::from(vec![[0, 1], [0, 2], [0, 3], [0, 4]]);
+        let stops: coordinate::I2Array = coordinate::I2Array::from(vec![[0, 0]]);
+        let targets: coordinate::I2Array = coordinate::I2Array::from(vec![]);
+
+        assert_eq!(
+            Sokoban::new(you, stops.clone(), pushes.clone(), targets.clone())
+                .you_move(coordinate::Direction::Up),
+            Sokoban::new(you, stops.clone(), pushes.clone(), targets.clone())
+        );
+
+        // ..0..
+        // ..0..
+        // ..0..
+        // ..0..
+        // ..@..
+        let you: coordinate::I2 = coordinate::I2::new(0, 4);
+        let pushes: coordinate::I2Array =
+            coordinate::I2Array::from(vec![[0, 0], [0, 1], [0, 2], [0, 3]]);
+        let targets: coordinate::I2Array = coordinate::I2Array::from(vec![]);
+
+        assert_eq!(
+            Sokoban::new(you, stops.clone(), pushes.clone(), targets.clone())
+                .you_move(coordinate::Direction::Up),
+            Sokoban::new(you, stops.clone(), pushes.clone(), targets.clone())
+        );
+    }
+
+    #[test]
+    fn pushes_dont_move_into_stop() {
+        // ..-..
+        // ..0..
+        // |0@0|
+        // ..0..
+        // ..-.^
+        let you: coordinate::I2 = coordinate::I2::new(2, 2);
+        let stops: coordinate::I2Array =
+            coordinate::I2Array::from(vec![[2, 0], [0, 2], [2, 4], [4, 2]]);
+        let pushes: coordinate::I2Array =
+            coordinate::I2Array::from(vec![[2, 1], [1, 2], [2, 3], [3, 2]]);
+        let targets: coordinate::I2Array = coordinate::I2Array::from(vec![[4, 4]]);
+
+        let board: Sokoban = Sokoban::new(you, stops.clone(), pushes.clone(), targets.clone());
+        assert_eq!(
+            board.you_move(coordinate::Direction::Up),
+            Sokoban::new(you, stops.clone(), pushes.clone(), targets.clone())
+        );
+        assert_eq!(
+            board.you_move(coordinate::Direction::Left),
+            Sokoban::new(you, stops.clone(), pushes.clone(), targets.clone())
+        );
+        assert_eq!(
+            board.you_move(coordinate::Direction::Down),
+            Sokoban::new(you, stops.clone(), pushes.clone(), targets.clone())
+        );
+        assert_eq!(
+            board.you_move(coordinate::Direction::Right),
+            Sokoban::new(you, stops.clone(), pushes.clone(), targets.clone())
+        );
+    }
+
+    #[test]
+    fn integer_xflow_is_stop() {
+        let stops: coordinate::I2Array = coordinate::I2Array::from(vec![]);
+        let pushes: coordinate::I2Array = coordinate::I2Array::from(vec![]);
+        let targets: coordinate::I2Array = coordinate::I2Array::from(vec![]);
+
+        assert_eq!(
+            Sokoban::new(
+                coordinate::I2::new(0, i32::MIN),
+                stops.clone(),
+                pushes.clone(),
+                targets.clone()
+            )
+            .you_move(coordinate::Direction::Up),
+            Sokoban::new(
+                coordinate::I2::new(0, i32::MIN),
+                stops.clone(),
+                pushes.clone(),
+                targets.clone()
+            )
+        );
+        assert_eq!(
+            Sokoban::new(
+                coordinate::I2::new(i32::MIN, 0),
+                stops.clone(),
+                pushes.clone(),
+                targets.clone()
+            )
+            .you_move(coordinate::Direction::Left),
+            Sokoban::new(
+                coordinate::I2::new(i32::MIN, 0),
+                stops.clone(),
+                pushes.clone(),
+                targets.clone()
+            )
+        );
+        assert_eq!(
+            Sokoban::new(
+                coordinate::I2::new(0, i32::MAX),
+                stops.clone(),
+                pushes.clone(),
+                targets.clone()
+            )
+            .you_move(coordinate::Direction::Down),
+            Sokoban::new(
+                coordinate::I2::new(0, i32::MAX),
+                stops.clone(),
+                pushes.clone(),
+                targets.clone()
+            )
+        );
+        assert_eq!(
+            Sokoban::new(
+                coordinate::I2::new(i32::MAX, 0),
+                stops.clone(),
+                pushes.clone(),
+                targets.clone()
+            )
+            .you_move(coordinate::Direction::Right),
+            Sokoban::new(
+                coordinate::I2::new(i32::MAX, 0),
+                stops.clone(),
+                pushes.clone(),
+                targets.clone()
+            )
+        );
+
+        assert_eq!(
+            Sokoban::new(
+                coordinate::I2::new(i32::MAX - 1, 0),
+                stops.clone(),
+                coordinate::I2Array::from(vec![[i32::MAX, 0]]),
+                targets.clone()
+            ),
+            Sokoban::new(
+                coordinate::I2::new(i32::MAX - 1, 0),
+                stops.clone(),
+                coordinate::I2Array::from(vec![[i32::MAX, 0]]),
+                targets.clone()
+            )
+        );
+    }
+
+    #[test]
+    fn lonely_target_is_not_triggered() {
+        // ..^..
+        // ..@..
+        let you: coordinate::I2 = coordinate::I2::new(0, 1);
+        let pushes: coordinate::I2Array = coordinate::I2Array::from(vec![]);
+        let targets: coordinate::I2Array = coordinate::I2Array::from(vec![[0, 0]]);
+        let stops: coordinate::I2Array = coordinate::I2Array::from(vec![]);
+
+        let board: Sokoban = Sokoban::new(you, stops, pushes, targets);
+        assert_eq!(board.triggered_targets(), Vec::<&coordinate::I2>::new());
+        assert!(!board.all_targets_triggered());
+    }
+
+    #[test]
+    fn target_on_push_is_triggered() {
+        // ..^..
+        // ..0..
+        // ..@..
+        let you: coordinate::I2 = coordinate::I2::new(0, 2);
+        let pushes: coordinate::I2Array = coordinate::I2Array::from(vec![[0, 1]]);
+        let targets: coordinate::I2Array = coordinate::I2Array::from(vec![[0, 0]]);
+        let stops: coordinate::I2Array = coordinate::I2Array::from(vec![]);
+
+        let board: Sokoban =
+            Sokoban::new(you, stops, pushes, targets.clone()).you_move(coordinate::Direction::Up);
+        assert_eq!(
+            board.triggered_targets(),
+            targets.iter().collect::<Vec<&coordinate::I2>>()
+        );
+        assert!(board.all_targets_triggered());
+    }
+
+    #[test]
+    fn target_on_you_is_not_triggered() {
+        // ..^..
+        // ..@..
+        let you: coordinate::I2 = coordinate::I2::new(0, 1);
+        let pushes: coordinate::I2Array = coordinate::I2Array::from(vec![]);
+        let targets: coordinate::I2Array = coordinate::I2Array::from(vec![[0, 0]]);
+        let stops: coordinate::I2Array = coordinate::I2Array::from(vec![]);
+
+        let board: Sokoban =
+            Sokoban::new(you, stops, pushes, targets).you_move(coordinate::Direction::Up);
+        assert_eq!(board.triggered_targets(), Vec::<&coordinate::I2>::new());
+        assert!(!board.all_targets_triggered());
+    }
+
+    #[test]
+    fn many_target_on_many_push_is_triggered() {
+        // ..^..
+        // ..0..
+        // ^0@0^
+        // ..0..
+        // ..^..
+        let you: coordinate::I2 = coordinate::I2::new(2, 2);
+        let pushes: coordinate::I2Array =
+            coordinate::I2Array::from(vec![[2, 1], [1, 2], [3, 2], [2, 3]]);
+        let targets: coordinate::I2Array =
+            coordinate::I2Array::from(vec![[2, 0], [0, 2], [2, 4], [4, 2]]);
+        let stops: coordinate::I2Array = coordinate::I2Array::from(vec![]);
+
+        let board: Sokoban = Sokoban::new(you, stops, pushes, targets.clone());
+        assert_eq!(
+            board.triggered_targets(),
+            targets.iter().take(0).collect::<Vec<&coordinate::I2>>()
+        );
+        assert!(!board.all_targets_triggered());
+
+        let board: Sokoban = board
+            .you_move(coordinate::Direction::Up) // top target
+            .you_move(coordinate::Direction::Down);
+        assert_eq!(
+            board.triggered_targets(),
+            targets.iter().take(1).collect::<Vec<&coordinate::I2>>()
+        );
+        assert!(!board.all_targets_triggered());
+
+        let board: Sokoban = board
+            .you_move(coordinate::Direction::Left) // left target
+            .you_move(coordinate::Direction::Right);
+        assert_eq!(
+            board.triggered_targets(),
+            targets.iter().take(2).collect::<Vec<&coordinate::I2>>()
+        );
+        assert!(!board.all_targets_triggered());
+
+        let board: Sokoban = board
+            .you_move(coordinate::Direction::Down) // bottom target
+            .you_move(coordinate::Direction::Up);
+        assert_eq!(
+            board.triggered_targets(),
+            targets.iter().take(3).collect::<Vec<&coordinate::I2>>()
+        );
+        assert!(!board.all_targets_triggered());
+
+        let board: Sokoban = board
+            .you_move(coordinate::Direction::Right) // right target
+            .you_move(coordinate::Direction::Left);
+        assert_eq!(
+            board.triggered_targets(),
+            targets.iter().collect::<Vec<&coordinate::I2>>()
+        );
+        assert!(board.all_targets_triggered());
+    }
+
+    #[test]
+    fn you_are_where_you_are() {
+        let you: coordinate::I2 = coordinate::I2::new(1, 1);
+        let stops: coordinate::I2Array = coordinate::I2Array::from(vec![[2, 2], [3, 3]]);
+        let pushes: coordinate::I2Array = coordinate::I2Array::from(vec![[3, 1], [1, 3]]);
+        let targets: coordinate::I2Array = coordinate::I2Array::from(vec![[4, 1], [1, 4]]);
+
+        let board: Sokoban = Sokoban::new(you, stops, pushes, targets);
+
+        assert_eq!(board.you(), you);
+        assert_eq!(
+            board.you_move(coordinate::Direction::Right).you(),
+            coordinate::I2::new(2, 1)
+        );
+    }
+
+    #[test]
+    fn stops_are_where_they_are() {
+        let you: coordinate::I2 = coordinate::I2::new(1, 1);
+        let stops: coordinate::I2Array = coordinate::I2Array::from(vec![[2, 2], [3, 3]]);
+        let pushes: coordinate::I2Array = coordinate::I2Array::from(vec![[3, 1], [1, 3]]);
+        let targets: coordinate::I2Array = coordinate::I2Array::from(vec![[4, 1], [1, 4]]);
+
+        let board: Sokoban = Sokoban::new(you, stops.clone(), pushes, targets);
+
+        assert_eq!(board.stops(), stops);
+    }
+
+    #[test]
+    fn pushes_are_where_they_are() {
+        let you: coordinate::I2 = coordinate::I2::new(1, 1);
+        let stops: coordinate::I2Array = coordinate::I2Array::from(vec![[2, 2], [3, 3]]);
+        let pushes: coordinate::I2Array = coordinate::I2Array::from(vec![[3, 1], [1, 3]]);
+        let targets: coordinate::I2Array = coordinate::I2Array::from(vec![[4, 1], [1, 4]]);
+
+        let board: Sokoban = Sokoban::new(you, stops, pushes.clone(), targets);
+
+        assert_eq!(board.pushes(), pushes);
+    }
+
+    #[test]
+    fn targets_are_where_they_are() {
+        let you: coordinate::I2 = coordinate::I2::new(1, 1);
+        let stops: coordinate::I2Array = coordinate::I2Array::from(vec![[2, 2], [3, 3]]);
+        let pushes: coordinate::I2Array = coordinate::I2Array::from(vec![[3, 1], [1, 3]]);
+        let targets: coordinate::I2Array = coordinate::I2Array::from(vec![[4, 1], [1, 4]]);
+
+        let board: Sokoban = Sokoban::new(you, stops, pushes, targets.clone());
+
+        assert_eq!(board.targets(), targets);
+    }
+
+    #[test]
+    fn partner_moves_independently_of_you() {
+        // .....
+        // .@.&.
+        // ...0^
+        let you: coordinate::I2 = coordinate::I2::new(1, 1);
+        let partner: coordinate::I2 = coordinate::I2::new(3, 1);
+        let stops: coordinate::I2Array = coordinate::I2Array::from(vec![]);
+        let pushes: coordinate::I2Array = coordinate::I2Array::from(vec![[3, 2]]);
+        let targets: coordinate::I2Array = coordinate::I2Array::from(vec![[4, 2]]);
+
+        let board: Sokoban =
+            Sokoban::new(you, stops.clone(), pushes, targets.clone()).with_partner(partner);
+        assert_eq!(board.partner(), Some(partner));
+
+        let board: Sokoban = board
+            .partner_move(coordinate::Direction::Up)
+            .partner_move(coordinate::Direction::Right)
+            .partner_move(coordinate::Direction::Down)
+            .partner_move(coordinate::Direction::Down)
+            .partner_move(coordinate::Direction::Left);
+        assert_eq!(board.you(), you);
+        assert_eq!(board.partner(), Some(coordinate::I2::new(3, 2)));
+        assert_eq!(board.pushes(), coordinate::I2Array::from(vec![[2, 2]]));
+    }
+
+    #[test]
+    fn you_and_partner_block_each_other() {
+        // .@&0.
+        let you: coordinate::I2 = coordinate::I2::new(1, 0);
+        let partner: coordinate::I2 = coordinate::I2::new(2, 0);
+        let stops: coordinate::I2Array = coordinate::I2Array::from(vec![]);
+        let pushes: coordinate::I2Array = coordinate::I2Array::from(vec![[3, 0]]);
+        let targets: coordinate::I2Array = coordinate::I2Array::from(vec![]);
+
+        let board: Sokoban = Sokoban::new(you, stops, pushes, targets).with_partner(partner);
+        assert_eq!(board.you_move(coordinate::Direction::Right), board);
+        assert_eq!(board.partner_move(coordinate::Direction::Left), board);
+
+        // you can't push a push into your partner either
+        let board: Sokoban = board
+            .partner_move(coordinate::Direction::Up)
+            .partner_move(coordinate::Direction::Right)
+            .partner_move(coordinate::Direction::Right)
+            .partner_move(coordinate::Direction::Down);
+        assert_eq!(board.partner(), Some(coordinate::I2::new(4, 0)));
+        assert_eq!(
+            board
+                .you_move(coordinate::Direction::Right)
+                .you_move(coordinate::Direction::Right),
+            board.you_move(coordinate::Direction::Right)
+        );
+    }
+
+    #[test]
+    fn partner_move_without_partner_does_nothing() {
+        let board: Sokoban = Sokoban::new(
+            coordinate::I2::new(0, 0),
+            coordinate::I2Array::from(vec![]),
+            coordinate::I2Array::from(vec![]),
+            coordinate::I2Array::from(vec![]),
+        );
+
+        assert_eq!(board.partner(), None);
+        assert_eq!(board.partner_move(coordinate::Direction::Up), board);
+    }
+
+    #[test]
+    fn moves_cost_what_the_tile_they_land_on_costs() {
+        // |@~0.
+        // where ~ is mud costing 2
+        let board: Sokoban = Sokoban::new(
+            coordinate::I2::new(1, 0),
+            coordinate::I2Array::from(vec![[0, 0]]),
+            coordinate::I2Array::from(vec![[3, 0]]),
+            coordinate::I2Array::from(vec![]),
+        )
+        .with_costs(vec![(coordinate::I2::new(2, 0), 2)]);
+
+        assert_eq!(board.cost(coordinate::I2::new(2, 0)), 2);
+        assert_eq!(board.cost(coordinate::I2::new(3, 0)), 1);
+        assert_eq!(board.you_move_cost(coordinate::Direction::Right), 2);
+        assert_eq!(board.you_move_cost(coordinate::Direction::Down), 1);
+        assert_eq!(board.you_move_cost(coordinate::Direction::Left), 0);
+        assert_eq!(
+            board
+                .you_move(coordinate::Direction::Right)
+                .you_move_cost(coordinate::Direction::Right),
+            1
+        );
+    }
+
+    #[test]
+    fn pushes_wear_out_into_stops() {
+        // @00.
+        // where the first push can be pushed twice
+        let board: Sokoban = Sokoban::new(
+            coordinate::I2::new(0, 0),
+            coordinate::I2Array::from(vec![]),
+            coordinate::I2Array::from(vec![[1, 0], [2, 0]]),
+            coordinate::I2Array::from(vec![]),
+        )
+        .with_durability(vec![(coordinate::I2::new(1, 0), 2)], Breakage::Stop);
+
+        let board: Sokoban = board.you_move(coordinate::Direction::Right);
+        assert_eq!(
+            board.events(),
+            &[Event::Worn {
+                at: coordinate::I2::new(2, 0),
+                remaining: 1
+            }]
+        );
+        assert_eq!(board.durability(coordinate::I2::new(2, 0)), Some(1));
+        assert_eq!(board.durability(coordinate::I2::new(3, 0)), None);
+
+        let board: Sokoban = board.you_move(coordinate::Direction::Right);
+        assert_eq!(
+            board.events(),
+            &[Event::Worn {
+                at: coordinate::I2::new(3, 0),
+                remaining: 0
+            }]
+        );
+        assert_eq!(board.durability(coordinate::I2::new(3, 0)), None);
+        assert_eq!(board.pushes(), coordinate::I2Array::from(vec![[4, 0]]));
+        assert_eq!(board.stops(), coordinate::I2Array::from(vec![[3, 0]]));
+
+        // you can't get through what's left
+        let board: Sokoban = board.you_move(coordinate::Direction::Right);
+        assert_eq!(board.you(), coordinate::I2::new(2, 0));
+        assert_eq!(board.events(), &[]);
+    }
+
+    #[test]
+    fn worn_out_pushes_can_be_removed() {
+        // @0.
+        let board: Sokoban = Sokoban::new(
+            coordinate::I2::new(0, 0),
+            coordinate::I2Array::from(vec![]),
+            coordinate::I2Array::from(vec![[1, 0]]),
+            coordinate::I2Array::from(vec![]),
+        )
+        .with_durability(vec![(coordinate::I2::new(1, 0), 1)], Breakage::Remove);
+
+        let board: Sokoban = board.you_move(coordinate::Direction::Right);
+        assert_eq!(board.pushes(), coordinate::I2Array::from(vec![]));
+        assert_eq!(board.stops(), coordinate::I2Array::from(vec![]));
+        assert_eq!(
+            board.events(),
+            &[Event::Worn {
+                at: coordinate::I2::new(2, 0),
+                remaining: 0
+            }]
+        );
+    }
+
+    #[test]
+    fn cracked_floor_breaks_once_walked_off_of() {
+        // @x0.
+        // where x is cracked floor
+        let board: Sokoban = Sokoban::new(
+            coordinate::I2::new(0, 0),
+            coordinate::I2Array::from(vec![]),
+            coordinate::I2Array::from(vec![[2, 0]]),
+            coordinate::I2Array::from(vec![]),
+        )
+        .with_cracks(coordinate::I2Array::from(vec![[1, 0]]));
+
+        let board: Sokoban = board.you_move(coordinate::Direction::Right);
+        assert_eq!(board.you(), coordinate::I2::new(1, 0));
+        assert_eq!(board.events(), &[]);
+        assert_eq!(board.stops(), coordinate::I2Array::from(vec![]));
+
+        let board: Sokoban = board.you_move(coordinate::Direction::Right);
+        assert_eq!(
+            board.events(),
+            &[Event::Cracked {
+                at: coordinate::I2::new(1, 0)
+            }]
+        );
+        assert_eq!(board.stops(), coordinate::I2Array::from(vec![[1, 0]]));
+        assert_eq!(board.cracks(), coordinate::I2Array::from(vec![]));
+
+        let board: Sokoban = board.you_move(coordinate::Direction::Left);
+        assert_eq!(board.you(), coordinate::I2::new(2, 0));
+    }
+
+    #[test]
+    fn blocked_moves_do_not_break_cracked_floor() {
+        // |@
+        // where you're on cracked floor
+        let board: Sokoban = Sokoban::new(
+            coordinate::I2::new(1, 0),
+            coordinate::I2Array::from(vec![[0, 0]]),
+            coordinate::I2Array::from(vec![]),
+            coordinate::I2Array::from(vec![]),
+        )
+        .with_cracks(coordinate::I2Array::from(vec![[1, 0]]));
+
+        let board: Sokoban = board.you_move(coordinate::Direction::Left);
+        assert_eq!(board.cracks(), coordinate::I2Array::from(vec![[1, 0]]));
+        assert_eq!(board.events(), &[]);
+    }
+
+    #[test]
+    fn switches_open_walls_while_held() {
+        // @0._.|.
+        // where _ is a switch for the wall |
+        let board: Sokoban = Sokoban::new(
+            coordinate::I2::new(0, 0),
+            coordinate::I2Array::from(vec![]),
+            coordinate::I2Array::from(vec![[1, 0]]),
+            coordinate::I2Array::from(vec![]),
+        )
+        .with_switches(vec![Switch {
+            at: coordinate::I2::new(3, 0),
+            walls: coordinate::I2Array::from(vec![[5, 0]]),
+        }]);
+        assert_eq!(
+            board.closed_walls(),
+            coordinate::I2Array::from(vec![[5, 0]])
+        );
+
+        // push the block onto the switch
+        let board: Sokoban = board
+            .you_move(coordinate::Direction::Right)
+            .you_move(coordinate::Direction::Right);
+        assert_eq!(board.pushes(), coordinate::I2Array::from(vec![[3, 0]]));
+        assert_eq!(board.closed_walls(), coordinate::I2Array::from(vec![]));
+
+        // then stand on it yourself and push the block into the wall,
+        // which won't close on it
+        let board: Sokoban = board
+            .you_move(coordinate::Direction::Right)
+            .you_move(coordinate::Direction::Right);
+        assert_eq!(board.pushes(), coordinate::I2Array::from(vec![[5, 0]]));
+        assert_eq!(board.closed_walls(), coordinate::I2Array::from(vec![]));
+
+        // and through it
+        let board: Sokoban = board.you_move(coordinate::Direction::Right);
+        assert_eq!(board.you(), coordinate::I2::new(5, 0));
+        assert_eq!(board.pushes(), coordinate::I2Array::from(vec![[6, 0]]));
+
+        // the wall closes once it's clear and the switch is let go
+        let board: Sokoban = board.you_move(coordinate::Direction::Left);
+        assert_eq!(
+            board.closed_walls(),
+            coordinate::I2Array::from(vec![[5, 0]])
+        );
+        let board: Sokoban = board.you_move(coordinate::Direction::Right);
+        assert_eq!(board.you(), coordinate::I2::new(4, 0));
+    }
+
+    #[test]
+    fn magnets_pull_the_nearest_push_in_front_of_them() {
+        // >..0.0
+        // @
+        // where > is a magnet facing right
+        let board: Sokoban = Sokoban::new(
+            coordinate::I2::new(0, 1),
+            coordinate::I2Array::from(vec![]),
+            coordinate::I2Array::from(vec![[3, 0], [5, 0]]),
+            coordinate::I2Array::from(vec![]),
+        )
+        .with_magnets(vec![Magnet {
+            at: coordinate::I2::new(0, 0),
+            facing: coordinate::Direction::Right,
+        }]);
+
+        let board: Sokoban = board.you_move(coordinate::Direction::Down);
+        assert_eq!(
+            board.pushes(),
+            coordinate::I2Array::from(vec![[2, 0], [5, 0]])
+        );
+        assert_eq!(
+            board.events(),
+            &[Event::Pulled {
+                from: coordinate::I2::new(3, 0),
+                to: coordinate::I2::new(2, 0)
+            }]
+        );
+
+        let board: Sokoban = board
+            .you_move(coordinate::Direction::Down)
+            .you_move(coordinate::Direction::Down);
+        assert_eq!(
+            board.pushes(),
+            coordinate::I2Array::from(vec![[0, 0], [5, 0]])
+        );
+
+        // it's stuck to the magnet now, and shields the one behind it
+        let board: Sokoban = board.you_move(coordinate::Direction::Down);
+        assert_eq!(
+            board.pushes(),
+            coordinate::I2Array::from(vec![[0, 0], [5, 0]])
+        );
+        assert_eq!(board.events(), &[]);
+    }
+
+    #[test]
+    fn magnets_do_not_pull_through_things() {
+        // >.|0
+        //  @
+        let board: Sokoban = Sokoban::new(
+            coordinate::I2::new(1, 1),
+            coordinate::I2Array::from(vec![[2, 0]]),
+            coordinate::I2Array::from(vec![[3, 0]]),
+            coordinate::I2Array::from(vec![]),
+        )
+        .with_magnets(vec![Magnet {
+            at: coordinate::I2::new(0, 0),
+            facing: coordinate::Direction::Right,
+        }]);
+
+        // moving up puts you in the way, too
+        for direction in [coordinate::Direction::Down, coordinate::Direction::Up] {
+            let moved: Sokoban = board.you_move(direction);
+            assert_eq!(moved.pushes(), coordinate::I2Array::from(vec![[3, 0]]));
+        }
+
+        // and blocked moves don't set magnets off
+        let board: Sokoban = board.with_you(coordinate::I2::new(4, 0));
+        assert_eq!(board.you_move(coordinate::Direction::Left), board);
+    }
+
+    #[test]
+    fn undo_takes_back_moves_one_at_a_time() {
+        // @0.
+        // ...
+        let board: Sokoban = Sokoban::new(
+            coordinate::I2::new(0, 0),
+            coordinate::I2Array::from(vec![]),
+            coordinate::I2Array::from(vec![[1, 0]]),
+            coordinate::I2Array::from(vec![]),
+        )
+        .with_partner(coordinate::I2::new(0, 1));
+        assert!(!board.can_undo());
+        assert_eq!(board.undo(), board);
+
+        let pushed: Sokoban = board.you_move(coordinate::Direction::Right);
+        let walked: Sokoban = pushed.partner_move(coordinate::Direction::Right);
+        // blocked by the partner, so there's nothing to undo here
+        let bumped: Sokoban = walked.you_move(coordinate::Direction::Down);
+        assert!(bumped.can_undo());
+
+        assert_eq!(bumped.undo(), pushed);
+        assert_eq!(bumped.undo().undo(), board);
+        assert!(!bumped.undo().undo().can_undo());
+    }
+
+    #[test]
+    fn boards_draw_as_xsb() {
+        //   ---
+        //   |^|
+        //   | ----
+        // ---0 0^|
+        // |^ 0@---
+        // ----0|
+        //    |^|
+        //    ---
+        let board: Sokoban = Sokoban::new(
+            coordinate::I2::new(4, 4),
+            coordinate::I2Array::from(vec![
+                [2, 0],
+                [3, 0],
+                [4, 0],
+                [2, 1],
+                [4, 1],
+                [2, 2],
+                [4, 2],
+                [5, 2],
+                [6, 2],
+                [7, 2],
+                [0, 3],
+                [1, 3],
+                [2, 3],
+                [7, 3],
+                [0, 4],
+                [5, 4],
+                [6, 4],
+                [7, 4],
+                [0, 5],
+                [1, 5],
+                [2, 5],
+                [3, 5],
+                [5, 5],
+                [3, 6],
+                [5, 6],
+                [3, 7],
+                [4, 7],
+                [5, 7],
+            ]),
+            coordinate::I2Array::from(vec![[3, 3], [5, 3], [3, 4], [4, 5]]),
+            coordinate::I2Array::from(vec![[3, 1], [6, 3], [1, 4], [4, 6]]),
+        );
+
+        assert_eq!(
+            board.to_xsb(),
+            ["  ###", "  #.#", "  # ####", "###$ $.#", "#. $@###", "####$#", "   #.#", "   ###",]
+                .join("\n")
+        );
+        assert_eq!(
+            board
+                .you_move(coordinate::Direction::Down)
+                .to_string()
+                .lines()
+                .nth(6),
+            Some("   #*#")
+        );
+    }
+
+    #[test]
+    fn boards_read_back_from_xsb() {
+        let xsb: &str = "\
+  ###
+  #.#
+  # ####
+###$ $.#
+#. $@###
+####*#
+   #.#
+   ###";
+        let board: Sokoban = Sokoban::from_xsb(xsb).unwrap();
+
+        assert_eq!(board.to_xsb(), xsb);
+        assert_eq!(board.you(), coordinate::I2::new(4, 4));
+        assert_eq!(Sokoban::from_xsb("#+*-_#").unwrap().to_xsb(), "#+*  #");
+        assert_eq!(Sokoban::from_xsb("#$.#"), Err("There's none of you"));
+        assert_eq!(Sokoban::from_xsb("@+"), Err("There's more than one of you"));
+        assert_eq!(Sokoban::from_xsb("@0^"), Err("Not a board character"));
+    }
+
+    #[test]
+    fn xsb_and_diagrams_go_wrong_the_same_way() {
+        let pairs: [(&str, &str); 3] = [("#$.#", "|0^|"), ("@+", "@@"), ("@$x", "@0x")];
+        for (xsb, diagram) in pairs {
+            assert_eq!(
+                Sokoban::from_xsb(xsb).err(),
+                diagram.parse::<Sokoban>().err()
+            );
+        }
+    }
+
+    #[test]
+    fn boards_parse_from_diagrams() {
+        let board: Sokoban = "\
+  ---
+  |^|
+  | ----
+---0 0^|
+|^ 0@---
+----0|
+   |^|
+   ---"
+        .parse()
+        .unwrap();
+
+        assert_eq!(board.you(), coordinate::I2::new(4, 4));
+        assert_eq!(
+            board.pushes(),
+            coordinate::I2Array::from(vec![[3, 3], [5, 3], [3, 4], [4, 5]])
+        );
+        assert_eq!(
+            board.targets(),
+            coordinate::I2Array::from(vec![[3, 1], [6, 3], [1, 4], [4, 6]])
+        );
+        assert_eq!(board.stops().iter().count(), 28);
+        assert!(board.stops().contains(&coordinate::I2::new(2, 0)));
+        assert!(board.stops().contains(&coordinate::I2::new(5, 7)));
+    }
+
+    #[test]
+    fn diagrams_need_exactly_one_of_you_and_nothing_unknown() {
+        assert!("0.^".parse::<Sokoban>().is_err());
+        assert!("@.@".parse::<Sokoban>().is_err());
+        assert!("@.$".parse::<Sokoban>().is_err());
+        assert_eq!(
+            "..\n.@".parse::<Sokoban>(),
+            Ok(Sokoban::new(
+                coordinate::I2::new(1, 1),
+                coordinate::I2Array::from(vec![]),
+                coordinate::I2Array::from(vec![]),
+                coordinate::I2Array::from(vec![]),
+            ))
+        );
+    }
+
+    #[test]
+    fn pushes_that_cannot_reach_a_target_are_deadlocked() {
+        let board: Sokoban = "\
+------
+|0 0 |
+|^  0|
+| @ 0|
+|^ 00-
+-----"
+            .parse()
+            .unwrap();
+
+        // the top and right walls have no targets along them, and the
+        // pair in the bottom right are frozen against the corner
+        assert_eq!(
+            board.deadlocked_pushes(),
+            coordinate::I2Array::from(vec![[1, 1], [3, 1], [4, 2], [4, 3], [3, 4], [4, 4]])
+        );
+        assert_eq!(
+            "@0^".parse::<Sokoban>().unwrap().deadlocked_pushes(),
+            coordinate::I2Array::from(vec![])
+        );
+    }
+
+    #[test]
+    fn pushes_freeze_each_other_in_clusters() {
+        let board: Sokoban = "\
+--------
+|^^    |
+|  00 @|
+|  00  |
+|^^    |
+--------"
+            .parse()
+            .unwrap();
+
+        assert_eq!(
+            board.deadlocked_pushes(),
+            coordinate::I2Array::from(vec![[3, 2], [4, 2], [3, 3], [4, 3]])
+        );
+        // but they're free again if you can get a row of them moving
+        assert_eq!(
+            board
+                .with_you(coordinate::I2::new(5, 3))
+                .you_move(coordinate::Direction::Left)
+                .deadlocked_pushes(),
+            coordinate::I2Array::from(vec![])
+        );
+    }
+
+    #[test]
+    fn solvability_is_worked_out_within_limits() {
+        let board: Sokoban = "\
+-------
+|@   ^|
+| 0 ---
+|   |
+-----"
+            .parse()
+            .unwrap();
+        let deadlocked: Sokoban = "\
+-----
+|0 ^|
+|@  |
+-----"
+            .parse()
+            .unwrap();
+
+        assert_eq!(
+            board.is_solvable(solver::Limits::default()),
+            Solvability::Solvable
+        );
+        assert_eq!(
+            deadlocked.is_solvable(solver::Limits {
+                nodes: Some(0),
+                time: None
+            }),
+            Solvability::Unsolvable
+        );
+        assert_eq!(
+            board.is_solvable(solver::Limits {
+                nodes: Some(3),
+                time: None
+            }),
+            Solvability::Unknown
+        );
+    }
+
+    #[test]
+    fn teleporters_can_save_pushes_that_look_deadlocked() {
+        // the push against the wall can't be pushed onto the target, but
+        // it can be pushed through a teleporter to the other side
+        let board: Sokoban = "\
+----------
+|@0 |   ^|
+|   |    |
+----------"
+            .parse::<Sokoban>()
+            .unwrap()
+            .with_teleporters(vec![
+                (coordinate::I2::new(3, 1), coordinate::I2::new(6, 1)),
+                (coordinate::I2::new(1, 2), coordinate::I2::new(5, 2)),
+            ]);
+
+        assert!(solver::breadth_first(&board).is_some());
+        assert_eq!(
+            board.hint(solver::Limits::default()),
+            Some(coordinate::Direction::Right)
+        );
+        assert_eq!(
+            board.is_solvable(solver::Limits::default()),
+            Solvability::Solvable
+        );
+    }
+
+    #[test]
+    fn hints_lead_to_a_win() {
+        let mut board: Sokoban = "\
+------
+|    |
+| 0 ^|
+|@   |
+------"
+            .parse()
+            .unwrap();
+
+        for _ in 0..10 {
+            match board.hint(solver::Limits::default()) {
+                Some(direction) => board = board.you_move(direction),
+                None => break,
+            }
+        }
+        assert!(board.all_targets_triggered());
+        assert_eq!(board.hint(solver::Limits::default()), None);
+        assert_eq!(
+            board.undo().hint(solver::Limits {
+                nodes: Some(0),
+                time: None
+            }),
+            None
+        );
+    }
+
+    #[test]
+    fn dashes_stop_before_anything_in_the_way() {
+        let board: Sokoban = "\
+--------
+|@   0^|
+|      |
+--------"
+            .parse()
+            .unwrap();
+
+        let dashed: Sokoban = board.you_dash(coordinate::Direction::Right);
+        assert_eq!(dashed.you(), coordinate::I2::new(4, 1));
+        assert_eq!(dashed.pushes(), board.pushes());
+        assert_eq!(dashed.undo(), board);
+        assert_eq!(
+            dashed.you_dash(coordinate::Direction::Right).you(),
+            coordinate::I2::new(4, 1)
+        );
+        assert_eq!(dashed.you_dash(coordinate::Direction::Right).undo(), board);
+        assert_eq!(
+            board.you_dash(coordinate::Direction::Down).you(),
+            coordinate::I2::new(1, 2)
+        );
+    }
+
+    #[test]
+    fn dashes_stop_going_around_teleporter_loops() {
+        let board: Sokoban = "\
+------
+|@  ^|
+------"
+            .parse::<Sokoban>()
+            .unwrap()
+            .with_teleporters(vec![(coordinate::I2::new(3, 1), coordinate::I2::new(1, 1))]);
+
+        // stepping onto (3, 1) takes you back to (1, 1), where you started
+        assert_eq!(
+            board.you_dash(coordinate::Direction::Right).you(),
+            coordinate::I2::new(1, 1)
+        );
+    }
+
+    #[test]
+    fn can_move_and_can_push_agree_with_you_move() {
+        let board: Sokoban = "\
+----
+|@0 |
+| | |
+----"
+            .parse()
+            .unwrap();
+
+        assert!(board.can_move(coordinate::Direction::Right));
+        assert!(board.can_push(coordinate::Direction::Right));
+        assert!(board.can_move(coordinate::Direction::Down));
+        assert!(!board.can_push(coordinate::Direction::Down));
+        assert!(!board.can_move(coordinate::Direction::Left));
+        assert!(!board.can_push(coordinate::Direction::Left));
+
+        let board: Sokoban = board.you_move(coordinate::Direction::Right);
+        assert!(!board.can_move(coordinate::Direction::Right));
+    }
+
+    #[test]
+    fn moves_say_how_they_went() {
+        let board: Sokoban = "\
+------
+|@00 ^|
+|   ^ |
+------"
+            .parse()
+            .unwrap();
+
+        let (board, outcome) = board.you_move_outcome(coordinate::Direction::Left);
+        assert_eq!(outcome, MoveOutcome::Blocked);
+        let (board, outcome) = board.you_move_outcome(coordinate::Direction::Right);
+        assert_eq!(outcome, MoveOutcome::Pushed(2));
+        let (board, outcome) = board.you_move_outcome(coordinate::Direction::Down);
+        assert_eq!(outcome, MoveOutcome::Moved);
+        let (_, outcome) = board.partner_move_outcome(coordinate::Direction::Down);
+        assert_eq!(outcome, MoveOutcome::Blocked);
+    }
+
+    #[test]
+    fn the_winning_move_says_so() {
+        let board: Sokoban = "@0^".parse().unwrap();
+
+        let (board, outcome) = board.you_move_outcome(coordinate::Direction::Right);
+        assert_eq!(outcome, MoveOutcome::Won);
+        assert!(board.all_targets_triggered());
+    }
+
+    #[test]
+    fn pushes_slide_across_ice_until_they_are_off_it() {
+        let board: Sokoban = "@0    ^"
+            .parse::<Sokoban>()
+            .unwrap()
+            .with_ices(coordinate::I2Array::from(vec![[2, 0], [3, 0], [4, 0]]));
+
+        let board: Sokoban = board.you_move(coordinate::Direction::Right);
+        assert_eq!(board.you(), coordinate::I2::new(1, 0));
+        assert_eq!(board.pushes(), coordinate::I2Array::from(vec![[5, 0]]));
+        assert_eq!(
+            board.events(),
+            &[Event::Slid {
+                from: coordinate::I2::new(2, 0),
+                to: coordinate::I2::new(5, 0),
+            }]
+        );
+    }
+
+    #[test]
+    fn pushes_slide_across_ice_until_they_hit_something() {
+        let board: Sokoban = "\
+--------
+|@00   |
+--------"
+            .parse::<Sokoban>()
+            .unwrap()
+            .with_ices(coordinate::I2Array::from(vec![
+                [3, 1],
+                [4, 1],
+                [5, 1],
+                [6, 1],
+            ]));
+
+        let board: Sokoban = board.you_move(coordinate::Direction::Right);
+        assert_eq!(board.you(), coordinate::I2::new(2, 1));
+        assert_eq!(
+            board.pushes(),
+            coordinate::I2Array::from(vec![[5, 1], [6, 1]])
+        );
+    }
+
+    #[test]
+    fn teleporters_carry_you_to_their_twin() {
+        let board: Sokoban = "@    "
+            .parse::<Sokoban>()
+            .unwrap()
+            .with_teleporters(vec![(coordinate::I2::new(1, 0), coordinate::I2::new(4, 0))]);
+
+        let board: Sokoban = board.you_move(coordinate::Direction::Right);
+        assert_eq!(board.you(), coordinate::I2::new(4, 0));
+        assert_eq!(
+            board.events(),
+            &[Event::Teleported {
+                from: coordinate::I2::new(1, 0),
+                to: coordinate::I2::new(4, 0),
+            }]
+        );
+
+        // standing on the far end keeps you there
+        let board: Sokoban = board.you_move(coordinate::Direction::Up);
+        assert_eq!(board.you(), coordinate::I2::new(4, -1));
+        let board: Sokoban = board.you_move(coordinate::Direction::Down);
+        assert_eq!(board.you(), coordinate::I2::new(1, 0));
+    }
+
+    #[test]
+    fn teleporters_carry_pushes_unless_their_twin_is_taken() {
+        let board: Sokoban = "@0 0 ".parse::<Sokoban>().unwrap().with_teleporters(vec![
+            (coordinate::I2::new(2, 0), coordinate::I2::new(2, 3)),
+            (coordinate::I2::new(4, 0), coordinate::I2::new(3, 0)),
+        ]);
+
+        let board: Sokoban = board.you_move(coordinate::Direction::Right);
+        assert_eq!(board.you(), coordinate::I2::new(1, 0));
+        assert_eq!(
+            board.pushes(),
+            coordinate::I2Array::from(vec![[2, 3], [3, 0]])
+        );
+
+        let board: Sokoban = board.you_move(coordinate::Direction::Right);
+        let board: Sokoban = board.you_move(coordinate::Direction::Right);
+        assert_eq!(board.you(), coordinate::I2::new(3, 0));
+        assert_eq!(
+            board.pushes(),
+            coordinate::I2Array::from(vec![[2, 3], [4, 0]])
+        );
+    }
+
+    #[test]
+    fn colored_targets_need_a_push_of_their_color() {
+        let board: Sokoban = "@0^".parse().unwrap();
+        let push: coordinate::I2 = coordinate::I2::new(1, 0);
+        let target: coordinate::I2 = coordinate::I2::new(2, 0);
+
+        let mismatched: Sokoban = board
+            .clone()
+            .with_colors(vec![(push, 2)], vec![(target, 1)])
+            .you_move(coordinate::Direction::Right);
+        assert_eq!(mismatched.push_color(target), Some(2));
+        assert_eq!(
+            mismatched.triggered_targets(),
+            Vec::<&coordinate::I2>::new()
+        );
+        assert!(!mismatched.all_targets_triggered());
+
+        let matched: Sokoban = board
+            .clone()
+            .with_colors(vec![(push, 1)], vec![(target, 1)])
+            .you_move(coordinate::Direction::Right);
+        assert!(matched.all_targets_triggered());
+
+        let uncolored_target: Sokoban = board
+            .with_colors(vec![(push, 2)], vec![])
+            .you_move(coordinate::Direction::Right);
+        assert!(uncolored_target.all_targets_triggered());
+    }
+
+    #[test]
+    fn clones_move_with_you() {
+        // where C is a clone of you
+        //
+        // @0 CC |
+        // C0|   |
+        let board: Sokoban = Sokoban::new(
+            coordinate::I2::new(1, 1),
+            coordinate::I2Array::from(vec![[3, 2], [7, 1], [7, 2]]),
+            coordinate::I2Array::from(vec![[2, 1], [2, 2]]),
+            coordinate::I2Array::from(vec![]),
+        )
+        .with_clones(coordinate::I2Array::from(vec![[1, 2], [4, 1], [5, 1]]));
+
+        let (board, outcome) = board.you_move_outcome(coordinate::Direction::Right);
+        assert_eq!(outcome, MoveOutcome::Pushed(1));
+        assert_eq!(board.you(), coordinate::I2::new(2, 1));
+        assert_eq!(
+            board.clones(),
+            coordinate::I2Array::from(vec![[1, 2], [5, 1], [6, 1]])
+        );
+        assert_eq!(
+            board.pushes(),
+            coordinate::I2Array::from(vec![[3, 1], [2, 2]])
+        );
+
+        // the clones at the wall hold up the ones behind them
+        let board: Sokoban = board.you_move(coordinate::Direction::Right);
+        assert_eq!(board.you(), coordinate::I2::new(3, 1));
+        assert_eq!(
+            board.clones(),
+            coordinate::I2Array::from(vec![[1, 2], [5, 1], [6, 1]])
+        );
+        assert!(!board.can_move(coordinate::Direction::Right));
+        assert!(board.can_move(coordinate::Direction::Left));
+    }
+
+    #[test]
+    fn chasing_enemies_catch_you() {
+        // where E is an enemy
+        //
+        // @ 0
+        //
+        //     E
+        let board: Sokoban = "@ 0".parse::<Sokoban>().unwrap().with_enemies(vec![Enemy {
+            at: coordinate::I2::new(4, 2),
+            movement: Movement::Chase,
+        }]);
+
+        let (board, outcome) = board.you_move_outcome(coordinate::Direction::Right);
+        assert_eq!(outcome, MoveOutcome::Moved);
+        assert_eq!(board.enemies()[0].at, coordinate::I2::new(3, 2));
+        assert!(!board.is_lost());
 
+        let (board, outcome) = board.you_move_outcome(coordinate::Direction::Down);
+        assert_eq!(outcome, MoveOutcome::Moved);
+        assert_eq!(board.enemies()[0].at, coordinate::I2::new(2, 2));
+
+        let (board, outcome) = board.you_move_outcome(coordinate::Direction::Down);
+        assert_eq!(outcome, MoveOutcome::Lost);
+        assert!(board.is_lost());
         assert_eq!(
-            board.you_move(coordinate::Direction::Up),
-            Sokoban::new(you_up, stops.clone(), pushes.clone(), targets.clone())
+            board.you_move_outcome(coordinate::Direction::Up).1,
+            MoveOutcome::GameOver
         );
+        assert!(!board.undo().is_lost());
+    }
+
+    #[test]
+    fn patrolling_enemies_walk_their_route() {
+        let board: Sokoban = "@ 0".parse::<Sokoban>().unwrap().with_enemies(vec![Enemy {
+            at: coordinate::I2::new(3, 1),
+            movement: Movement::Patrol {
+                route: vec![coordinate::Direction::Up, coordinate::Direction::Down],
+                next: 0,
+            },
+        }]);
+
+        let board: Sokoban = board.you_move(coordinate::Direction::Right);
+        assert_eq!(board.enemies()[0].at, coordinate::I2::new(3, 0));
+        // enemies are solid, so you can't push into one
+        assert!(!board.can_move(coordinate::Direction::Right));
+
+        let board: Sokoban = board.you_move(coordinate::Direction::Down);
+        assert_eq!(board.enemies()[0].at, coordinate::I2::new(3, 1));
+        let board: Sokoban = board.you_move(coordinate::Direction::Up);
+        assert_eq!(board.enemies()[0].at, coordinate::I2::new(3, 0));
+    }
+
+    #[test]
+    fn push_limits_stop_long_lines() {
+        let board: Sokoban = "@00 ".parse().unwrap();
+
+        let limited: Sokoban = board.clone().with_push_limit(Some(1));
+        assert_eq!(limited.you_move(coordinate::Direction::Right), limited);
         assert_eq!(
-            board.you_move(coordinate::Direction::Left),
-            Sokoban::new(you_left, stops.clone(), pushes.clone(), targets.clone())
+            board
+                .with_push_limit(Some(2))
+                .you_move(coordinate::Direction::Right)
+                .pushes(),
+            coordinate::I2Array::from(vec![[2, 0], [3, 0]])
         );
+
+        let limited: Sokoban = "@0 0".parse::<Sokoban>().unwrap().with_push_limit(Some(1));
         assert_eq!(
-            board.you_move(coordinate::Direction::Down),
-            Sokoban::new(you_down, stops.clone(), pushes.clone(), targets.clone())
+            limited.you_move(coordinate::Direction::Right).pushes(),
+            coordinate::I2Array::from(vec![[2, 0], [3, 0]])
         );
+    }
+
+    #[test]
+    fn heavy_pushes_only_move_alone() {
+        let heavy: coordinate::I2Array = coordinate::I2Array::from(vec![[2, 0]]);
+
+        let board: Sokoban = "@00 "
+            .parse::<Sokoban>()
+            .unwrap()
+            .with_heavies(heavy.clone());
+        assert_eq!(board.you_move(coordinate::Direction::Right), board);
+
+        let board: Sokoban = "@ 0 ".parse::<Sokoban>().unwrap().with_heavies(heavy);
+        let board: Sokoban = board
+            .you_move(coordinate::Direction::Right)
+            .you_move(coordinate::Direction::Right);
+        assert_eq!(board.pushes(), coordinate::I2Array::from(vec![[3, 0]]));
+        assert_eq!(board.heavies(), coordinate::I2Array::from(vec![[3, 0]]));
+    }
+
+    #[test]
+    fn running_out_of_moves_fails_the_board() {
+        let board: Sokoban = "@ 0 ^"
+            .parse::<Sokoban>()
+            .unwrap()
+            .with_move_limit(Some(3))
+            .with_costs(vec![(coordinate::I2::new(1, -1), 2)]);
+
+        let (board, outcome) = board.you_move_outcome(coordinate::Direction::Right);
+        assert_eq!(outcome, MoveOutcome::Moved);
+        assert_eq!(board.moves(), 1);
+        let (board, outcome) = board.you_move_outcome(coordinate::Direction::Up);
+        assert_eq!(outcome, MoveOutcome::Failed);
+        assert_eq!(board.moves(), 3);
+        assert!(board.is_failed());
         assert_eq!(
-            board.you_move(coordinate::Direction::Right),
-            Sokoban::new(you_right, stops.clone(), pushes.clone(), targets.clone())
+            board.you_move_outcome(coordinate::Direction::Down).1,
+            MoveOutcome::GameOver
         );
+        assert!(!board.undo().is_failed());
     }
 
     #[test]
-    fn you_dont_move_into_stop() {
-        // ...0.^^
-        // ...-...
-        // .0|@|0.
-        // ...-...
-        // ...0...
-        let you: coordinate::I2 = coordinate::I2::new(3, 3);
-        let stops: coordinate::I2Array =
-            coordinate::I2Array::from(vec![[3, 2], [2, 3], [3, 4], [4, 3]]);
-        let pushes: coordinate::I2Array =
-            coordinate::I2Array::from(vec![[3, 1], [1, 3], [3, 5], [5, 3]]);
-        let targets: coordinate::I2Array = coordinate::I2Array::from(vec![[6, 1], [7, 1]]);
+    fn won_boards_take_no_more_moves() {
+        let board: Sokoban = "@0^ "
+            .parse::<Sokoban>()
+            .unwrap()
+            .with_partner(coordinate::I2::new(0, 1));
+        assert!(!board.is_won());
 
-        let board: Sokoban =
-            Sokoban::new(you.clone(), stops.clone(), pushes.clone(), targets.clone());
+        let (board, outcome) = board.you_move_outcome(coordinate::Direction::Right);
+        assert_eq!(outcome, MoveOutcome::Won);
+        assert!(board.is_won());
+        assert!(!board.can_move(coordinate::Direction::Right));
+        assert!(!board.can_push(coordinate::Direction::Right));
+        for direction in DIRECTIONS {
+            let (after, outcome) = board.you_move_outcome(direction);
+            assert_eq!(outcome, MoveOutcome::GameOver);
+            assert_eq!(after, board);
+            let (after, outcome) = board.partner_move_outcome(direction);
+            assert_eq!(outcome, MoveOutcome::GameOver);
+            assert_eq!(after, board);
+        }
+
+        let board: Sokoban = board.undo();
+        assert!(!board.is_won());
         assert_eq!(
-            board.you_move(coordinate::Direction::Up),
-            Sokoban::new(you.clone(), stops.clone(), pushes.clone(), targets.clone())
+            board.you_move_outcome(coordinate::Direction::Left).1,
+            MoveOutcome::Moved
         );
+    }
+
+    #[test]
+    fn boards_without_targets_are_never_won() {
+        let board: Sokoban = "@ 0".parse().unwrap();
+
+        assert!(board.all_targets_triggered());
+        assert!(!board.is_won());
         assert_eq!(
-            board.you_move(coordinate::Direction::Left),
-            Sokoban::new(you.clone(), stops.clone(), pushes.clone(), targets.clone())
+            board.you_move_outcome(coordinate::Direction::Right).1,
+            MoveOutcome::Moved
+        );
+    }
+
+    #[test]
+    fn winning_on_the_last_move_is_not_failing() {
+        let board: Sokoban = "@0^".parse::<Sokoban>().unwrap().with_move_limit(Some(1));
+
+        let (board, outcome) = board.you_move_outcome(coordinate::Direction::Right);
+        assert_eq!(outcome, MoveOutcome::Won);
+        assert!(!board.is_failed());
+    }
+
+    #[test]
+    fn moving_in_place_matches_making_a_new_board() {
+        let board: Sokoban = "@0 ^".parse().unwrap();
+
+        let mut moved: Sokoban = board.clone();
+        assert_eq!(
+            moved.you_move_mut(coordinate::Direction::Right),
+            MoveOutcome::Pushed(1)
         );
+        assert_eq!(moved, board.you_move(coordinate::Direction::Right));
+        assert_eq!(moved.undo(), board);
+
         assert_eq!(
-            board.you_move(coordinate::Direction::Down),
-            Sokoban::new(you.clone(), stops.clone(), pushes.clone(), targets.clone())
+            moved.you_move_mut(coordinate::Direction::Up),
+            MoveOutcome::Moved
         );
         assert_eq!(
-            board.you_move(coordinate::Direction::Right),
-            Sokoban::new(you.clone(), stops.clone(), pushes.clone(), targets.clone())
+            moved.you_move_mut(coordinate::Direction::Up),
+            MoveOutcome::Moved
         );
+        assert_eq!(moved.you(), coordinate::I2::new(1, -2));
     }
 
     #[test]
-    fn pushes_move_when_you_walk_into_them() {
-        // --...^^
-        // ...0...
-        // ..0@0..
-        // ...0...
-        // .......
-        let you: coordinate::I2 = coordinate::I2::new(3, 3);
-        let you_up: coordinate::I2 = coordinate::I2::new(3, 2);
-        let you_left: coordinate::I2 = coordinate::I2::new(2, 3);
-        let you_down: coordinate::I2 = coordinate::I2::new(3, 4);
-        let you_right: coordinate::I2 = coordinate::I2::new(4, 3);
+    fn moves_share_stops_and_targets_until_they_change() {
+        let board: Sokoban = "|@ ^"
+            .parse::<Sokoban>()
+            .unwrap()
+            .with_cracks(coordinate::I2Array::from(vec![[2, 0]]));
 
-        let pushes: coordinate::I2Array =
-            coordinate::I2Array::from(vec![[3, 2], [2, 3], [3, 4], [4, 3]]);
-        let pushes_up: coordinate::I2Array =
-            coordinate::I2Array::from(vec![[3, 1], [2, 3], [3, 4], [4, 3]]);
-        let pushes_left: coordinate::I2Array =
-            coordinate::I2Array::from(vec![[3, 2], [1, 3], [3, 4], [4, 3]]);
-        let pushes_down: coordinate::I2Array =
-            coordinate::I2Array::from(vec![[3, 2], [2, 3], [3, 5], [4, 3]]);
-        let pushes_right: coordinate::I2Array =
-            coordinate::I2Array::from(vec![[3, 2], [2, 3], [3, 4], [5, 3]]);
+        let moved: Sokoban = board.you_move(coordinate::Direction::Right);
+        assert!(board.layers.shares(&moved.layers, EntityKind::Stop));
+        assert!(board.layers.shares(&moved.layers, EntityKind::Target));
 
-        let stops: coordinate::I2Array = coordinate::I2Array::from(vec![[1, 1], [2, 1]]);
-        let targets: coordinate::I2Array = coordinate::I2Array::from(vec![[6, 1], [7, 1]]);
+        // walking off the crack makes a new stop
+        let moved: Sokoban = moved.you_move(coordinate::Direction::Right);
+        assert!(!board.layers.shares(&moved.layers, EntityKind::Stop));
+        assert_eq!(board.stops(), coordinate::I2Array::from(vec![[0, 0]]));
+        assert_eq!(
+            moved.stops(),
+            coordinate::I2Array::from(vec![[0, 0], [2, 0]])
+        );
+    }
 
-        let board = Sokoban::new(you, stops.clone(), pushes.clone(), targets.clone());
+    #[test]
+    fn normalized_boards_start_at_the_origin() {
+        let board: Sokoban = Sokoban::new(
+            coordinate::I2::new(3, -2),
+            coordinate::I2Array::from(vec![[2, -1]]),
+            coordinate::I2Array::from(vec![[4, -2]]),
+            coordinate::I2Array::from(vec![[5, -2]]),
+        )
+        .with_ices(coordinate::I2Array::from(vec![[4, -1]]))
+        .you_move(coordinate::Direction::Right);
+        let normalized: Sokoban = board.normalized();
+
+        assert_eq!(normalized.you(), coordinate::I2::new(2, 0));
+        assert_eq!(normalized.stops(), coordinate::I2Array::from(vec![[0, 1]]));
+        assert_eq!(normalized.pushes(), coordinate::I2Array::from(vec![[3, 0]]));
+        assert_eq!(normalized.ices(), coordinate::I2Array::from(vec![[2, 1]]));
+        assert!(!normalized.can_undo());
+        assert_eq!(normalized.normalized(), normalized);
+    }
+
+    #[test]
+    fn rotating_and_mirroring_undo_themselves() {
+        let board: Sokoban = "\
+-----
+|@0^|
+| - |
+-----"
+            .parse::<Sokoban>()
+            .unwrap()
+            .with_magnets(vec![Magnet {
+                at: coordinate::I2::new(3, 2),
+                facing: coordinate::Direction::Up,
+            }]);
 
+        assert_eq!(board.rotate_cw().rotate_ccw(), board);
+        assert_eq!(board.rotate_cw().rotate_cw().rotate_cw().rotate_cw(), board);
+        assert_eq!(board.mirror_horizontal().mirror_horizontal(), board);
+        assert_eq!(board.mirror_vertical().mirror_vertical(), board);
         assert_eq!(
-            board.you_move(coordinate::Direction::Up),
-            Sokoban::new(you_up, stops.clone(), pushes_up.clone(), targets.clone())
+            board.rotate_cw().rotate_cw(),
+            board.mirror_horizontal().mirror_vertical()
         );
+    }
+
+    #[test]
+    fn transformed_boards_play_the_same() {
+        let board: Sokoban = "\
+-----
+|@0^|
+| - |
+-----"
+            .parse()
+            .unwrap();
+
         assert_eq!(
-            board.you_move(coordinate::Direction::Left),
-            Sokoban::new(
-                you_left,
-                stops.clone(),
-                pushes_left.clone(),
-                targets.clone()
-            )
+            board.rotate_cw().you_move(coordinate::Direction::Down),
+            board.you_move(coordinate::Direction::Right).rotate_cw()
         );
         assert_eq!(
-            board.you_move(coordinate::Direction::Down),
-            Sokoban::new(
-                you_down,
-                stops.clone(),
-                pushes_down.clone(),
-                targets.clone()
-            )
+            board.rotate_ccw().you_move(coordinate::Direction::Up),
+            board.you_move(coordinate::Direction::Right).rotate_ccw()
         );
         assert_eq!(
-            board.you_move(coordinate::Direction::Right),
-            Sokoban::new(
-                you_right,
-                stops.clone(),
-                pushes_right.clone(),
-                targets.clone()
-            )
+            board
+                .mirror_horizontal()
+                .you_move(coordinate::Direction::Left),
+            board
+                .you_move(coordinate::Direction::Right)
+                .mirror_horizontal()
+        );
+        assert_eq!(
+            board.mirror_vertical().you_move(coordinate::Direction::Up),
+            board
+                .you_move(coordinate::Direction::Down)
+                .mirror_vertical()
+        );
+        assert_eq!(
+            board.rotate_cw().normalized().to_xsb(),
+            "\
+####
+# @#
+##$#
+# .#
+####"
         );
     }
 
     #[test]
-    fn push_moves_when_push_is_pushed_into_it() {
-        // .....
-        // ..0..
-        // ..0..
-        // ..0..
-        // ..0..
-        // ..@..
-        let you: coordinate::I2 = coordinate::I2::new(0, 5);
-        let you_final: coordinate::I2 = coordinate::I2::new(0, 4);
-        let pushes: coordinate::I2Array =
-            coordinate::I2Array::from(vec![[0, 1], [0, 2], [0, 3], [0, 4]]);
-        let pushes_final: coordinate::I2Array =
-            coordinate::I2Array::from(vec![[0, 0], [0, 1], [0, 2], [0, 3]]);
-        let stops: coordinate::I2Array = coordinate::I2Array::from(vec![]);
-        let targets: coordinate::I2Array = coordinate::I2Array::from(vec![]);
+    fn cells_cover_everything_from_the_floor_up() {
+        let board: Sokoban = "\
+-------
+|@00^^|
+|     |
+-------"
+            .parse::<Sokoban>()
+            .unwrap()
+            .with_partner(coordinate::I2::new(3, 2))
+            .you_move(coordinate::Direction::Right);
+        let cells: Vec<(coordinate::I2, Entity)> = board.cells().collect();
 
+        assert_eq!(cells.len(), board.stops().iter().count() + 5);
+        assert!(cells.contains(&(coordinate::I2::new(0, 0), Entity::Stop)));
+        assert!(cells.contains(&(coordinate::I2::new(3, 1), Entity::Push)));
+        assert!(cells.contains(&(coordinate::I2::new(4, 1), Entity::TriggeredTarget)));
+        assert!(cells.contains(&(coordinate::I2::new(5, 1), Entity::Target)));
         assert_eq!(
-            Sokoban::new(you, stops.clone(), pushes, targets.clone())
-                .you_move(coordinate::Direction::Up),
-            Sokoban::new(you_final, stops.clone(), pushes_final, targets.clone())
+            &cells[cells.len() - 2..],
+            &[
+                (coordinate::I2::new(3, 2), Entity::Partner),
+                (coordinate::I2::new(2, 1), Entity::You),
+            ]
         );
     }
 
     #[test]
-    fn push_doesnt_move_when_push_is_pushed_into_it_but_theres_a_stop() {
-        // ..-..
-        // ..0..
-        // ..0..
-        // ..0..
-        // ..0..
-        // ..@..
-        let you: coordinate::I2 = coordinate::I2::new(0, 5);
-        let pushes: coordinate::I2Array =
-            coordinate::I2Array::from(vec![[0, 1], [0, 2], [0, 3], [0, 4]]);
-        let stops: coordinate::I2Array = coordinate::I2Array::from(vec![[0, 0]]);
-        let targets: coordinate::I2Array = coordinate::I2Array::from(vec![]);
+    fn recording_keeps_the_moves_that_went_somewhere() {
+        let board: Sokoban = "\
+------
+|@0 ^|
+------"
+            .parse()
+            .unwrap();
+
+        assert_eq!(board.you_move(coordinate::Direction::Right).history(), None);
 
+        let board: Sokoban = board
+            .with_recording()
+            .you_move(coordinate::Direction::Right)
+            .you_move(coordinate::Direction::Up)
+            .you_move(coordinate::Direction::Right);
         assert_eq!(
-            Sokoban::new(you, stops.clone(), pushes.clone(), targets.clone())
-                .you_move(coordinate::Direction::Up),
-            Sokoban::new(you, stops.clone(), pushes.clone(), targets.clone())
+            board.history(),
+            Some(&[coordinate::Direction::Right, coordinate::Direction::Right][..])
+        );
+        assert_eq!(
+            board.undo().history(),
+            Some(&[coordinate::Direction::Right][..])
+        );
+        assert_eq!(
+            board.mirror_horizontal().history(),
+            Some(&[coordinate::Direction::Left, coordinate::Direction::Left][..])
+        );
+    }
+
+    #[test]
+    fn applying_moves_plays_them_back_in_order() {
+        let board: Sokoban = "\
+------
+|@0 ^|
+|    |
+------"
+            .parse::<Sokoban>()
+            .unwrap()
+            .with_recording();
+        let recorded: Sokoban = board
+            .you_move(coordinate::Direction::Down)
+            .you_move(coordinate::Direction::Up)
+            .you_move(coordinate::Direction::Right)
+            .you_move(coordinate::Direction::Right);
+
+        let replayed: Sokoban = board.apply_moves(recorded.history().unwrap()).unwrap();
+        assert_eq!(replayed, recorded);
+        assert!(replayed.all_targets_triggered());
+        assert_eq!(
+            board.apply_moves(&[
+                coordinate::Direction::Right,
+                coordinate::Direction::Up,
+                coordinate::Direction::Right,
+            ]),
+            Err(ReplayError::Blocked(1))
+        );
+        assert_eq!(board.apply_moves(&[]), Ok(board));
+    }
+
+    #[test]
+    fn triggered_targets_are_counted_through_every_kind_of_move() {
+        // the push on ice slides onto the far target, the worn out push
+        // leaves its target, and the magnet pulls a push off of one
+        let board: Sokoban = "\
+---------
+|@0  ^  |
+|0^     |
+|^0    ^|
+|       |
+---------"
+            .parse::<Sokoban>()
+            .unwrap()
+            .with_ices(coordinate::I2Array::from(vec![[3, 1], [4, 1]]))
+            .with_durability(vec![(coordinate::I2::new(1, 2), 1)], Breakage::Remove)
+            .with_magnets(vec![Magnet {
+                at: coordinate::I2::new(7, 3),
+                facing: coordinate::Direction::Left,
+            }]);
+        let mut moved: Sokoban = board.clone();
+        for direction in [
+            coordinate::Direction::Right,
+            coordinate::Direction::Left,
+            coordinate::Direction::Down,
+            coordinate::Direction::Right,
+            coordinate::Direction::Down,
+            coordinate::Direction::Down,
+            coordinate::Direction::Right,
+        ] {
+            moved.you_move_mut(direction);
+            assert_eq!(moved.triggered, moved.count_triggered());
+            assert_eq!(moved.undo().triggered, moved.undo().count_triggered());
+        }
+        assert_eq!(
+            board
+                .with_colors(vec![], vec![(coordinate::I2::new(2, 2), 1)])
+                .triggered,
+            0
+        );
+    }
+
+    #[test]
+    fn stats_count_everything_including_what_was_taken_back() {
+        let board: Sokoban = "\
+-------
+|@00 ^|
+|     |
+-------"
+            .parse::<Sokoban>()
+            .unwrap()
+            .with_partner(coordinate::I2::new(5, 2));
+        assert_eq!(board.stats(), GameStats::default());
+
+        let board: Sokoban = board
+            .you_move(coordinate::Direction::Right)
+            .you_move(coordinate::Direction::Up)
+            .partner_move(coordinate::Direction::Left)
+            .undo()
+            .you_move(coordinate::Direction::Left)
+            .reset();
+        assert_eq!(
+            board.stats(),
+            GameStats {
+                moves: 3,
+                pushes: 2,
+                undos: 1,
+                rewinds: 0,
+                resets: 1,
+                turns: 4,
+                elapsed: std::time::Duration::ZERO,
+            }
+        );
+        assert!(!board.can_undo());
+        assert_eq!(board.you(), coordinate::I2::new(1, 1));
+        assert_eq!(board.reset().stats().resets, 2);
+    }
+
+    #[test]
+    fn time_counts_until_the_board_is_over() {
+        let second: std::time::Duration = std::time::Duration::from_secs(1);
+        let mut board: Sokoban = "@0^".parse().unwrap();
+        board.tick(second);
+
+        // undoing doesn't take back time spent
+        let mut board: Sokoban = board.you_move(coordinate::Direction::Left).undo();
+        board.tick(second);
+        assert_eq!(board.stats().elapsed, 2 * second);
+
+        let mut board: Sokoban = board.you_move(coordinate::Direction::Right);
+        assert!(board.is_won());
+        board.tick(second);
+        assert_eq!(board.stats().elapsed, 2 * second);
+    }
+
+    #[test]
+    fn items_lie_under_everything_until_picked_up() {
+        let coin: Item = Item {
+            at: coordinate::I2::new(3, 1),
+            kind: 0,
+            collectible: true,
+        };
+        let decal: Item = Item {
+            at: coordinate::I2::new(4, 1),
+            kind: 1,
+            collectible: false,
+        };
+        let board: Sokoban = "\
+--------
+|@0   ^|
+--------"
+            .parse::<Sokoban>()
+            .unwrap()
+            .with_items(vec![coin, decal]);
+
+        // the push covers the coin without taking it
+        let covered: Sokoban = board.you_move(coordinate::Direction::Right);
+        assert_eq!(covered.pushes(), coordinate::I2Array::from(vec![[3, 1]]));
+        assert_eq!(covered.items(), &[coin, decal]);
+        assert!(covered
+            .cells()
+            .any(|cell| cell == (coin.at, Entity::Item(0))));
+
+        // and you pick it up once it's uncovered
+        let picked_up: Sokoban = covered.you_move(coordinate::Direction::Right);
+        assert_eq!(picked_up.items(), &[decal]);
+        assert_eq!(picked_up.collected(), &[coin]);
+        assert_eq!(picked_up.events(), &[Event::PickedUp { item: coin }]);
+
+        // but not the decal
+        let walked_over: Sokoban = picked_up.you_move(coordinate::Direction::Right);
+        assert_eq!(walked_over.items(), &[decal]);
+        assert_eq!(walked_over.undo().undo(), covered);
+    }
+
+    #[test]
+    fn sticky_pushes_drag_each_other_along() {
+        // the sticky pushes are at (2, 1), (2, 2), and (3, 2), and the
+        // one at (3, 2) shoves the plain push at (4, 2)
+        let board: Sokoban = "\
+--------
+|@0    |
+| 000  |
+|     ^|
+--------"
+            .parse::<Sokoban>()
+            .unwrap()
+            .with_stickies(coordinate::I2Array::from(vec![[2, 1], [2, 2], [3, 2]]));
+
+        let moved: Sokoban = board.you_move(coordinate::Direction::Right);
+        assert_eq!(moved.you(), coordinate::I2::new(2, 1));
+        assert_eq!(
+            moved.pushes(),
+            coordinate::I2Array::from(vec![[3, 1], [3, 2], [4, 2], [5, 2]])
+        );
+        assert_eq!(
+            moved.stickies(),
+            coordinate::I2Array::from(vec![[3, 1], [3, 2], [4, 2]])
+        );
+        assert_eq!(
+            moved.you_move_outcome(coordinate::Direction::Right).1,
+            MoveOutcome::Pushed(4)
+        );
+        // the plain push at (1, 2) isn't stuck to anything
+        assert_eq!(
+            board.you_move(coordinate::Direction::Down).pushes(),
+            board.pushes()
+        );
+        // and if any of them are blocked, they all are
+        assert_eq!(
+            moved
+                .you_move(coordinate::Direction::Right)
+                .you_move_outcome(coordinate::Direction::Right)
+                .1,
+            MoveOutcome::Blocked
+        );
+    }
+
+    #[test]
+    fn mergeable_pushes_merge_and_split_back_apart() {
+        // there's a splitter at (5, 1)
+        let board: Sokoban = "\
+---------
+|@0 0   |
+|  0    |
+---------"
+            .parse::<Sokoban>()
+            .unwrap()
+            .with_mergeables(coordinate::I2Array::from(vec![[2, 1], [4, 1]]))
+            .with_splitters(coordinate::I2Array::from(vec![[5, 1]]));
+
+        let (merged, outcome): (Sokoban, MoveOutcome) = board
+            .you_move(coordinate::Direction::Right)
+            .you_move_outcome(coordinate::Direction::Right);
+        assert_eq!(outcome, MoveOutcome::Pushed(1));
+        assert_eq!(merged.you(), coordinate::I2::new(3, 1));
+        assert_eq!(
+            merged.pushes(),
+            coordinate::I2Array::from(vec![[4, 1], [3, 2]])
+        );
+        assert_eq!(merged.size(coordinate::I2::new(4, 1)), Some(2));
+        assert_eq!(
+            merged.events(),
+            &[Event::Merged {
+                at: coordinate::I2::new(4, 1),
+                size: 2,
+                color: None,
+            }]
+        );
+
+        let split: Sokoban = merged.you_move(coordinate::Direction::Right);
+        assert_eq!(
+            split.pushes(),
+            coordinate::I2Array::from(vec![[5, 1], [3, 2], [6, 1]])
+        );
+        assert_eq!(split.size(coordinate::I2::new(5, 1)), Some(1));
+        assert_eq!(split.size(coordinate::I2::new(6, 1)), Some(1));
+        assert!(split.mergeables().contains(&coordinate::I2::new(6, 1)));
+        assert_eq!(
+            split.events(),
+            &[Event::Split {
+                from: coordinate::I2::new(5, 1),
+                to: coordinate::I2::new(6, 1)
+            }]
         );
 
-        // ..0..
-        // ..0..
-        // ..0..
-        // ..0..
-        // ..@..
-        let you: coordinate::I2 = coordinate::I2::new(0, 4);
-        let pushes: coordinate::I2Array =
-            coordinate::I2Array::from(vec![[0, 0], [0, 1], [0, 2], [0, 3]]);
-        let targets: coordinate::I2Array = coordinate::I2Array::from(vec![]);
-
+        // plain pushes don't merge
+        let shoved: Sokoban = board
+            .with_mergeables(coordinate::I2Array::from(vec![[2, 1]]))
+            .you_move(coordinate::Direction::Right)
+            .you_move(coordinate::Direction::Right);
         assert_eq!(
-            Sokoban::new(you, stops.clone(), pushes.clone(), targets.clone())
-                .you_move(coordinate::Direction::Up),
-            Sokoban::new(you, stops.clone(), pushes.clone(), targets.clone())
+            shoved.pushes(),
+            coordinate::I2Array::from(vec![[4, 1], [5, 1], [3, 2]])
         );
     }
 
     #[test]
-    fn pushes_dont_move_into_stop() {
-        // ..-..
-        // ..0..
-        // |0@0|
-        // ..0..
-        // ..-.^
-        let you: coordinate::I2 = coordinate::I2::new(2, 2);
-        let stops: coordinate::I2Array =
-            coordinate::I2Array::from(vec![[2, 0], [0, 2], [2, 4], [4, 2]]);
-        let pushes: coordinate::I2Array =
-            coordinate::I2Array::from(vec![[2, 1], [1, 2], [2, 3], [3, 2]]);
-        let targets: coordinate::I2Array = coordinate::I2Array::from(vec![[4, 4]]);
+    fn merge_tables_only_merge_matching_colors_and_upgrade_them() {
+        let board: Sokoban = "@0 0 0"
+            .parse::<Sokoban>()
+            .unwrap()
+            .with_mergeables(coordinate::I2Array::from(vec![[1, 0], [3, 0], [5, 0]]))
+            .with_colors(
+                vec![
+                    (coordinate::I2::new(1, 0), 2),
+                    (coordinate::I2::new(3, 0), 2),
+                    (coordinate::I2::new(5, 0), 4),
+                ],
+                vec![],
+            )
+            .with_merge_rules(MergeRules {
+                table: vec![(2, 4), (4, 8)],
+            });
+        let right: coordinate::Direction = coordinate::Direction::Right;
 
-        let board: Sokoban = Sokoban::new(you, stops.clone(), pushes.clone(), targets.clone());
-        assert_eq!(
-            board.you_move(coordinate::Direction::Up),
-            Sokoban::new(you, stops.clone(), pushes.clone(), targets.clone())
-        );
+        let merged: Sokoban = board.you_move(right).you_move(right);
         assert_eq!(
-            board.you_move(coordinate::Direction::Left),
-            Sokoban::new(you, stops.clone(), pushes.clone(), targets.clone())
+            merged.pushes(),
+            coordinate::I2Array::from(vec![[3, 0], [5, 0]])
         );
+        assert_eq!(merged.push_color(coordinate::I2::new(3, 0)), Some(4));
         assert_eq!(
-            board.you_move(coordinate::Direction::Down),
-            Sokoban::new(you, stops.clone(), pushes.clone(), targets.clone())
+            merged.events(),
+            &[Event::Merged {
+                at: coordinate::I2::new(3, 0),
+                size: 2,
+                color: Some(4),
+            }]
         );
+
+        // and now it's the same color as the last one
+        let merged: Sokoban = merged.you_move(right).you_move(right);
+        assert_eq!(merged.pushes(), coordinate::I2Array::from(vec![[5, 0]]));
+        assert_eq!(merged.push_color(coordinate::I2::new(5, 0)), Some(8));
+        assert_eq!(merged.size(coordinate::I2::new(5, 0)), Some(3));
+
+        // different colors, or colors missing from the table, shove
+        let mismatched: Sokoban = board
+            .with_merge_rules(MergeRules {
+                table: vec![(4, 8)],
+            })
+            .you_move(right)
+            .you_move(right);
         assert_eq!(
-            board.you_move(coordinate::Direction::Right),
-            Sokoban::new(you, stops.clone(), pushes.clone(), targets.clone())
+            mismatched.pushes(),
+            coordinate::I2Array::from(vec![[3, 0], [4, 0], [5, 0]])
         );
     }
 
     #[test]
-    fn integer_xflow_is_stop() {
-        let stops: coordinate::I2Array = coordinate::I2Array::from(vec![]);
-        let pushes: coordinate::I2Array = coordinate::I2Array::from(vec![]);
-        let targets: coordinate::I2Array = coordinate::I2Array::from(vec![]);
+    fn level_ids_are_stable_and_tell_levels_apart() {
+        let board: Sokoban = "\
+------
+|@0 ^|
+------"
+            .parse()
+            .unwrap();
 
+        // this is the same on every platform, and shouldn't ever change
+        // lest saves and leaderboards be orphaned
+        assert_eq!(board.level_id(), 0xeb98_3434_c024_ebdd);
         assert_eq!(
-            Sokoban::new(
-                coordinate::I2::new(0, i32::MIN),
-                stops.clone(),
-                pushes.clone(),
-                targets.clone()
-            )
-            .you_move(coordinate::Direction::Up),
-            Sokoban::new(
-                coordinate::I2::new(0, i32::MIN),
-                stops.clone(),
-                pushes.clone(),
-                targets.clone()
-            )
+            board.you_move(coordinate::Direction::Right).level_id(),
+            board.level_id()
         );
         assert_eq!(
-            Sokoban::new(
-                coordinate::I2::new(i32::MIN, 0),
-                stops.clone(),
-                pushes.clone(),
-                targets.clone()
-            )
-            .you_move(coordinate::Direction::Left),
-            Sokoban::new(
-                coordinate::I2::new(i32::MIN, 0),
+            board
+                .transform(
+                    &|cell| coordinate::I2::new(cell.x() + 3, cell.y() - 7),
+                    &|direction| direction
+                )
+                .level_id(),
+            board.level_id()
+        );
+        assert_ne!(
+            board.clone().with_push_limit(Some(1)).level_id(),
+            board.level_id()
+        );
+        assert_ne!(board.rotate_cw().level_id(), board.level_id());
+    }
+
+    #[test]
+    fn checked_boards_reject_things_on_top_of_each_other() {
+        let stops: coordinate::I2Array = coordinate::I2Array::from(vec![[3, 0]]);
+        let targets: coordinate::I2Array = coordinate::I2Array::from(vec![[2, 0]]);
+        let board = |you: [i32; 2], pushes: Vec<[i32; 2]>| {
+            Sokoban::try_new(
+                coordinate::I2::new(you[0], you[1]),
                 stops.clone(),
-                pushes.clone(),
-                targets.clone()
+                coordinate::I2Array::from(pushes),
+                targets.clone(),
             )
+        };
+
+        assert_eq!(board([3, 0], vec![]), Err(SokobanError::YouOnStop));
+        assert_eq!(
+            board([1, 0], vec![[2, 0], [1, 0]]),
+            Err(SokobanError::YouOnPush(coordinate::I2::new(1, 0)))
         );
         assert_eq!(
-            Sokoban::new(
-                coordinate::I2::new(0, i32::MAX),
-                stops.clone(),
-                pushes.clone(),
-                targets.clone()
-            )
-            .you_move(coordinate::Direction::Down),
-            Sokoban::new(
-                coordinate::I2::new(0, i32::MAX),
-                stops.clone(),
-                pushes.clone(),
-                targets.clone()
-            )
+            board([0, 0], vec![[3, 0]]),
+            Err(SokobanError::PushOnStop(coordinate::I2::new(3, 0)))
         );
         assert_eq!(
-            Sokoban::new(
-                coordinate::I2::new(i32::MAX, 0),
-                stops.clone(),
-                pushes.clone(),
-                targets.clone()
-            )
-            .you_move(coordinate::Direction::Right),
-            Sokoban::new(
-                coordinate::I2::new(i32::MAX, 0),
-                stops.clone(),
-                pushes.clone(),
-                targets.clone()
-            )
+            board([0, 0], vec![[1, 0], [2, 0], [1, 0]]),
+            Err(SokobanError::DuplicatePush(coordinate::I2::new(1, 0)))
         );
-
         assert_eq!(
-            Sokoban::new(
-                coordinate::I2::new(i32::MAX - 1, 0),
-                stops.clone(),
-                coordinate::I2Array::from(vec![[i32::MAX, 0]]),
-                targets.clone()
-            ),
-            Sokoban::new(
-                coordinate::I2::new(i32::MAX - 1, 0),
+            board([0, 0], vec![[1, 0], [2, 0]]),
+            Ok(Sokoban::new(
+                coordinate::I2::new(0, 0),
                 stops.clone(),
-                coordinate::I2Array::from(vec![[i32::MAX, 0]]),
-                targets.clone()
-            )
+                coordinate::I2Array::from(vec![[1, 0], [2, 0]]),
+                targets.clone(),
+            ))
         );
     }
 
     #[test]
-    fn lonely_target_is_not_triggered() {
-        // ..^..
-        // ..@..
-        let you: coordinate::I2 = coordinate::I2::new(0, 1);
-        let pushes: coordinate::I2Array = coordinate::I2Array::from(vec![]);
-        let targets: coordinate::I2Array = coordinate::I2Array::from(vec![[0, 0]]);
-        let stops: coordinate::I2Array = coordinate::I2Array::from(vec![]);
+    fn you_are_stuck_when_nothing_moves_you() {
+        let board: Sokoban = "\
+.-
+|@0-
+ - ^"
+        .parse()
+        .unwrap();
+        assert!(board.is_stuck());
 
-        let board: Sokoban = Sokoban::new(you, stops, pushes, targets);
-        assert_eq!(board.triggered_targets(), Vec::<&coordinate::I2>::new());
-        assert!(!board.all_targets_triggered());
+        // a push with room to go isn't in the way
+        let board: Sokoban = "\
+.-
+|@0 -
+ - ^"
+        .parse()
+        .unwrap();
+        assert!(!board.is_stuck());
+
+        // nor is being done with the board
+        let board: Sokoban = "-\n@0^-\n-".parse().unwrap();
+        let board: Sokoban = board.you_move(coordinate::Direction::Right);
+        assert!(board.is_won());
+        assert!(!board.is_stuck());
     }
 
     #[test]
-    fn target_on_push_is_triggered() {
-        // ..^..
-        // ..0..
-        // ..@..
-        let you: coordinate::I2 = coordinate::I2::new(0, 2);
-        let pushes: coordinate::I2Array = coordinate::I2Array::from(vec![[0, 1]]);
-        let targets: coordinate::I2Array = coordinate::I2Array::from(vec![[0, 0]]);
-        let stops: coordinate::I2Array = coordinate::I2Array::from(vec![]);
+    fn rewinds_go_back_several_moves_and_run_out() {
+        // @.0.^
+        let board: Sokoban = "@.0.^".parse::<Sokoban>().unwrap().with_rewinds(2, 2);
+        let right: coordinate::Direction = coordinate::Direction::Right;
+        assert_eq!(board.rewind(1), board);
+        assert_eq!(board.rewind(1).rewinds_left(), 2);
 
-        let board: Sokoban =
-            Sokoban::new(you, stops, pushes, targets.clone()).you_move(coordinate::Direction::Up);
-        assert_eq!(
-            board.triggered_targets(),
-            targets.iter().collect::<Vec<&coordinate::I2>>()
-        );
-        assert!(board.all_targets_triggered());
+        // rewinds that can't go back any moves aren't used up
+        let shallow: Sokoban = board.clone().with_rewinds(2, 0).you_move(right);
+        assert_eq!(shallow.rewind(1), shallow);
+        assert_eq!(shallow.rewind(1).rewinds_left(), 2);
+
+        let walked: Sokoban = board.apply_moves(&[right, right, right]).unwrap();
+        let rewound: Sokoban = walked.rewind(3);
+        // only as deep as the rewinds were given
+        assert_eq!(rewound, board.you_move(right));
+        assert_eq!(rewound.rewinds_left(), 1);
+        assert_eq!(rewound.stats().rewinds, 1);
+        assert_eq!(rewound.stats().undos, 0);
+
+        // undoing doesn't give a rewind back
+        assert_eq!(rewound.undo(), board);
+        assert_eq!(rewound.undo().rewinds_left(), 1);
+
+        let rewound: Sokoban = rewound.you_move(right).rewind(1);
+        assert_eq!(rewound, board.you_move(right));
+        assert_eq!(rewound.rewinds_left(), 0);
+        assert_eq!(rewound.you_move(right).rewind(1), rewound.you_move(right));
     }
 
     #[test]
-    fn target_on_you_is_not_triggered() {
-        // ..^..
-        // ..@..
-        let you: coordinate::I2 = coordinate::I2::new(0, 1);
-        let pushes: coordinate::I2Array = coordinate::I2Array::from(vec![]);
-        let targets: coordinate::I2Array = coordinate::I2Array::from(vec![[0, 0]]);
-        let stops: coordinate::I2Array = coordinate::I2Array::from(vec![]);
+    fn restoring_a_snapshot_brings_back_the_board_but_not_its_undos() {
+        // @0^.
+        // .0^.
+        let board: Sokoban = "\
+@0^.
+.0^."
+            .parse::<Sokoban>()
+            .unwrap();
+        let right: coordinate::Direction = coordinate::Direction::Right;
+        let down: coordinate::Direction = coordinate::Direction::Down;
+        let left: coordinate::Direction = coordinate::Direction::Left;
+        let triggered: Sokoban = board.you_move(right);
+        let checkpoint: Snapshot = triggered.snapshot();
+        assert!(checkpoint
+            .layers
+            .shares(&triggered.layers, EntityKind::Stop));
 
-        let board: Sokoban =
-            Sokoban::new(you, stops, pushes, targets).you_move(coordinate::Direction::Up);
-        assert_eq!(board.triggered_targets(), Vec::<&coordinate::I2>::new());
-        assert!(!board.all_targets_triggered());
+        let moved_on: Sokoban = triggered.apply_moves(&[left, down, right]).unwrap();
+        assert!(moved_on.all_targets_triggered());
+        let restored: Sokoban = moved_on.restore(checkpoint.clone());
+        assert_eq!(restored, triggered);
+        assert_eq!(restored.triggered_count(), 1);
+        assert_eq!(restored.stats(), moved_on.stats());
+        // undoing takes back the restore, not the move before the
+        // snapshot
+        assert_eq!(restored.undo(), moved_on);
+
+        // the same snapshot can be gone back to again
+        assert_eq!(restored.you_move(right).restore(checkpoint), triggered);
     }
 
     #[test]
-    fn many_target_on_many_push_is_triggered() {
-        // ..^..
-        // ..0..
-        // ^0@0^
-        // ..0..
-        // ..^..
-        let you: coordinate::I2 = coordinate::I2::new(2, 2);
-        let pushes: coordinate::I2Array =
-            coordinate::I2Array::from(vec![[2, 1], [1, 2], [3, 2], [2, 3]]);
-        let targets: coordinate::I2Array =
-            coordinate::I2Array::from(vec![[2, 0], [0, 2], [2, 4], [4, 2]]);
-        let stops: coordinate::I2Array = coordinate::I2Array::from(vec![]);
-
-        let board: Sokoban = Sokoban::new(you, stops, pushes, targets.clone());
-        assert_eq!(
-            board.triggered_targets(),
-            targets.iter().take(0).collect::<Vec<&coordinate::I2>>()
-        );
-        assert!(!board.all_targets_triggered());
+    fn turn_events_follow_pushes_on_and_off_targets() {
+        // @0^.|
+        // 0^..|
+        let board: Sokoban = "\
+@0^.|
+0^..|"
+            .parse::<Sokoban>()
+            .unwrap();
+        let right: coordinate::Direction = coordinate::Direction::Right;
+        assert_eq!(board.turn_events(), &[]);
 
-        let board: Sokoban = board
-            .you_move(coordinate::Direction::Up) // top target
-            .you_move(coordinate::Direction::Down);
+        let board: Sokoban = board.you_move(right);
         assert_eq!(
-            board.triggered_targets(),
-            targets.iter().take(1).collect::<Vec<&coordinate::I2>>()
+            board.turn_events(),
+            &[
+                TurnEvent::Pushed {
+                    from: coordinate::I2::new(1, 0),
+                    to: coordinate::I2::new(2, 0),
+                },
+                TurnEvent::Triggered {
+                    at: coordinate::I2::new(2, 0),
+                },
+            ]
         );
-        assert!(!board.all_targets_triggered());
+        assert_eq!(board.undo().turn_events(), &[]);
 
-        let board: Sokoban = board
-            .you_move(coordinate::Direction::Left) // left target
-            .you_move(coordinate::Direction::Right);
+        let board: Sokoban = board.you_move(right);
         assert_eq!(
-            board.triggered_targets(),
-            targets.iter().take(2).collect::<Vec<&coordinate::I2>>()
+            board.turn_events(),
+            &[
+                TurnEvent::Pushed {
+                    from: coordinate::I2::new(2, 0),
+                    to: coordinate::I2::new(3, 0),
+                },
+                TurnEvent::Untriggered {
+                    at: coordinate::I2::new(2, 0),
+                },
+            ]
         );
-        assert!(!board.all_targets_triggered());
 
-        let board: Sokoban = board
-            .you_move(coordinate::Direction::Down) // bottom target
-            .you_move(coordinate::Direction::Up);
         assert_eq!(
-            board.triggered_targets(),
-            targets.iter().take(3).collect::<Vec<&coordinate::I2>>()
+            board.you_move(right).turn_events(),
+            &[TurnEvent::Blocked { direction: right }]
         );
-        assert!(!board.all_targets_triggered());
-
-        let board: Sokoban = board
-            .you_move(coordinate::Direction::Right) // right target
-            .you_move(coordinate::Direction::Left);
+        // there's no partner to move, so that's blocked too
         assert_eq!(
-            board.triggered_targets(),
-            targets.iter().collect::<Vec<&coordinate::I2>>()
+            board
+                .partner_move(coordinate::Direction::Left)
+                .turn_events(),
+            &[TurnEvent::Blocked {
+                direction: coordinate::Direction::Left,
+            }]
         );
-        assert!(board.all_targets_triggered());
     }
 
     #[test]
-    fn you_are_where_you_are() {
-        let you: coordinate::I2 = coordinate::I2::new(1, 1);
-        let stops: coordinate::I2Array = coordinate::I2Array::from(vec![[2, 2], [3, 3]]);
-        let pushes: coordinate::I2Array = coordinate::I2Array::from(vec![[3, 1], [1, 3]]);
-        let targets: coordinate::I2Array = coordinate::I2Array::from(vec![[4, 1], [1, 4]]);
-
-        let board: Sokoban = Sokoban::new(you, stops, pushes, targets);
-
-        assert_eq!(board.you(), you);
-        assert_eq!(
-            board.you_move(coordinate::Direction::Right).you(),
-            coordinate::I2::new(2, 1)
+    fn repeated_targets_are_only_counted_once() {
+        let board: Sokoban = Sokoban::new(
+            coordinate::I2::new(0, 0),
+            coordinate::I2Array::from(vec![]),
+            coordinate::I2Array::from(vec![[1, 0]]),
+            coordinate::I2Array::from(vec![[2, 0], [2, 0]]),
         );
-    }
-
-    #[test]
-    fn stops_are_where_they_are() {
-        let you: coordinate::I2 = coordinate::I2::new(1, 1);
-        let stops: coordinate::I2Array = coordinate::I2Array::from(vec![[2, 2], [3, 3]]);
-        let pushes: coordinate::I2Array = coordinate::I2Array::from(vec![[3, 1], [1, 3]]);
-        let targets: coordinate::I2Array = coordinate::I2Array::from(vec![[4, 1], [1, 4]]);
-
-        let board: Sokoban = Sokoban::new(you, stops.clone(), pushes, targets);
 
-        assert_eq!(board.stops(), stops);
+        let board: Sokoban = board.you_move(coordinate::Direction::Right);
+        assert_eq!(board.triggered_count(), 1);
+        assert_eq!(board.targets(), coordinate::I2Array::from(vec![[2, 0]]));
+        assert!(board.all_targets_triggered());
     }
 
     #[test]
-    fn pushes_are_where_they_are() {
-        let you: coordinate::I2 = coordinate::I2::new(1, 1);
-        let stops: coordinate::I2Array = coordinate::I2Array::from(vec![[2, 2], [3, 3]]);
-        let pushes: coordinate::I2Array = coordinate::I2Array::from(vec![[3, 1], [1, 3]]);
-        let targets: coordinate::I2Array = coordinate::I2Array::from(vec![[4, 1], [1, 4]]);
-
-        let board: Sokoban = Sokoban::new(you, stops, pushes.clone(), targets);
+    fn reachable_tiles_stop_at_anything_in_the_way() {
+        // the partner is at (3, 2)
+        let board: Sokoban = "\
+-------
+|@ |  |
+|0    |
+-------"
+            .parse::<Sokoban>()
+            .unwrap()
+            .with_partner(coordinate::I2::new(3, 2));
+        let reachable: coordinate::I2Array = board.reachable_tiles();
 
-        assert_eq!(board.pushes(), pushes);
+        assert_eq!(reachable.iter().count(), 3);
+        assert!(reachable.contains(&coordinate::I2::new(1, 1)));
+        assert!(reachable.contains(&coordinate::I2::new(2, 1)));
+        assert!(reachable.contains(&coordinate::I2::new(2, 2)));
     }
 
     #[test]
-    fn targets_are_where_they_are() {
-        let you: coordinate::I2 = coordinate::I2::new(1, 1);
-        let stops: coordinate::I2Array = coordinate::I2Array::from(vec![[2, 2], [3, 3]]);
-        let pushes: coordinate::I2Array = coordinate::I2Array::from(vec![[3, 1], [1, 3]]);
-        let targets: coordinate::I2Array = coordinate::I2Array::from(vec![[4, 1], [1, 4]]);
-
-        let board: Sokoban = Sokoban::new(you, stops, pushes, targets.clone());
+    fn reachable_tiles_on_open_boards_stop_past_the_edge() {
+        let reachable: coordinate::I2Array = "@".parse::<Sokoban>().unwrap().reachable_tiles();
 
-        assert_eq!(board.targets(), targets);
+        assert_eq!(reachable.iter().count(), 9);
     }
 
     #[test]
@@ -921,4 +5755,65 @@ mod tests {
             .you_move(coordinate::Direction::Right)
             .all_targets_triggered());
     }
+
+    proptest::proptest! {
+        #[test]
+        fn moves_uphold_board_invariants(board: Sokoban, direction: coordinate::Direction) {
+            crate::arbitrary::check_move(&board, direction, &board.you_move(direction))?;
+        }
+
+        #[test]
+        fn moves_keep_count_of_triggered_targets(
+            board: Sokoban,
+            directions: Vec<coordinate::Direction>,
+        ) {
+            let mut board: Sokoban = board;
+            for direction in directions {
+                board.you_move_mut(direction);
+                proptest::prop_assert_eq!(board.triggered, board.count_triggered());
+            }
+        }
+
+        #[test]
+        fn all_targets_triggered_agrees_with_triggered_targets(board: Sokoban) {
+            proptest::prop_assert_eq!(
+                board.all_targets_triggered(),
+                board.triggered_targets().len() == board.targets().iter().count()
+            );
+        }
+
+        #[test]
+        fn triggered_targets_agree_however_they_are_got(
+            board: Sokoban,
+            repeats: Vec<proptest::sample::Index>,
+            direction: coordinate::Direction,
+        ) {
+            // list some of the targets twice over
+            let targets: Vec<coordinate::I2> = board.targets().iter().copied().collect();
+            let repeated: coordinate::I2Array = targets
+                .iter()
+                .copied()
+                .chain(
+                    repeats
+                        .iter()
+                        .filter(|_| !targets.is_empty())
+                        .map(|repeat| *repeat.get(&targets)),
+                )
+                .collect();
+            let board: Sokoban =
+                Sokoban::new(board.you(), board.stops(), board.pushes(), repeated);
+
+            for board in [board.clone(), board.you_move(direction)] {
+                proptest::prop_assert_eq!(
+                    board.triggered_targets_iter().collect::<Vec<&coordinate::I2>>(),
+                    board.triggered_targets()
+                );
+                proptest::prop_assert_eq!(board.triggered_count(), board.triggered_targets().len());
+                proptest::prop_assert_eq!(
+                    board.all_targets_triggered(),
+                    board.triggered_targets().len() == board.targets().iter().count()
+                );
+            }
+        }
+    }
 }
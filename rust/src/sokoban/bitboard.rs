@@ -0,0 +1,179 @@
+//! A dense picture of a board, one bit per tile
+//!
+//! A [`Sokoban`] keeps lists of where things are, which is compact for
+//! boards that are mostly floor, but slow to compare and hash when
+//! there are millions of them, like when solving.  A [`Bitboard`]
+//! instead covers a rectangle of the board with one bit per tile for
+//! each of stops, pushes, and targets, so two of them are compared a
+//! word at a time.
+//!
+//! A bitboard takes a bit for every tile in the rectangle, however few
+//! things there are in it, so boards spread over more than [`MAX_AREA`]
+//! tiles don't get one.
+
+use super::{Bounds, EntityKind, Sokoban};
+use crate::coordinate;
+
+/// The most tiles a bitboard may cover
+///
+/// That's a board of 64 by 64 tiles, which takes 1.5 KiB.  Any bigger
+/// and it'd usually be smaller to list where things are.
+pub const MAX_AREA: usize = 64 * 64;
+
+/// One bit per tile for each of stops, pushes, and targets over a
+/// rectangle of a board, along with where you are
+///
+/// # Examples
+///
+/// ```
+/// let board: Sokoban = "@0^".parse().unwrap();
+/// let bits: Bitboard = Bitboard::new(&board).unwrap();
+///
+/// assert!(bits.is_push(coordinate::I2::new(1, 0)));
+/// assert!(!bits.all_targets_triggered());
+/// ```
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct Bitboard {
+    left: i32,
+    top: i32,
+    width: usize,
+    height: usize,
+    you: coordinate::I2,
+    stops: Vec<u64>,
+    pushes: Vec<u64>,
+    targets: Vec<u64>,
+}
+
+impl Bitboard {
+    /// Draw `board` over the area around everything on it
+    ///
+    /// That's the smallest rectangle containing everything, grown by
+    /// one tile on each side.  It's `None` if that's more than
+    /// [`MAX_AREA`] tiles.
+    pub fn new(board: &Sokoban) -> Option<Bitboard> {
+        Bitboard::within(board, &Bounds::new(board))
+    }
+
+    /// Draw `board` over `bounds`, leaving out anything outside of it
+    ///
+    /// This is `None` if `bounds` covers more than [`MAX_AREA`] tiles.
+    pub(super) fn within(board: &Sokoban, bounds: &Bounds) -> Option<Bitboard> {
+        let width: usize = bounds.right.abs_diff(bounds.left) as usize + 1;
+        let height: usize = bounds.bottom.abs_diff(bounds.top) as usize + 1;
+        let area: usize = width.checked_mul(height)?;
+        if area > MAX_AREA {
+            return None;
+        }
+        let words: usize = area.div_ceil(64);
+        let mut bitboard: Bitboard = Bitboard {
+            left: bounds.left,
+            top: bounds.top,
+            width,
+            height,
+            you: board.you,
+            stops: vec![0; words],
+            pushes: vec![0; words],
+            targets: vec![0; words],
+        };
+        for stop in board.layers[EntityKind::Stop].iter() {
+            if let Some(bit) = bitboard.bit(*stop) {
+                set(&mut bitboard.stops, bit);
+            }
+        }
+        for push in board.layers[EntityKind::Push].iter() {
+            if let Some(bit) = bitboard.bit(*push) {
+                set(&mut bitboard.pushes, bit);
+            }
+        }
+        for target in board.layers[EntityKind::Target].iter() {
+            if let Some(bit) = bitboard.bit(*target) {
+                set(&mut bitboard.targets, bit);
+            }
+        }
+        Some(bitboard)
+    }
+
+    /// Gets the position of the player
+    pub fn you(&self) -> coordinate::I2 {
+        self.you
+    }
+
+    /// Checks if there's a stop at `cell`
+    ///
+    /// Everything outside the bitboard counts as a stop.
+    pub fn is_stop(&self, cell: coordinate::I2) -> bool {
+        match self.bit(cell) {
+            Some(bit) => get(&self.stops, bit),
+            None => true,
+        }
+    }
+
+    /// Checks if there's a push at `cell`
+    pub fn is_push(&self, cell: coordinate::I2) -> bool {
+        self.bit(cell).is_some_and(|bit| get(&self.pushes, bit))
+    }
+
+    /// Checks if there's a target at `cell`
+    pub fn is_target(&self, cell: coordinate::I2) -> bool {
+        self.bit(cell).is_some_and(|bit| get(&self.targets, bit))
+    }
+
+    /// Checks if all the targets have a push on them
+    pub fn all_targets_triggered(&self) -> bool {
+        self.targets
+            .iter()
+            .zip(self.pushes.iter())
+            .all(|(targets, pushes)| targets & !pushes == 0)
+    }
+
+    /// The index of the bit for `cell`, if it's on the bitboard
+    fn bit(&self, cell: coordinate::I2) -> Option<usize> {
+        let x: usize = cell.x().checked_sub(self.left)?.try_into().ok()?;
+        let y: usize = cell.y().checked_sub(self.top)?.try_into().ok()?;
+        (x < self.width && y < self.height).then_some(y * self.width + x)
+    }
+}
+
+fn get(words: &[u64], bit: usize) -> bool {
+    words[bit / 64] & (1 << (bit % 64)) != 0
+}
+
+fn set(words: &mut [u64], bit: usize) {
+    words[bit / 64] |= 1 << (bit % 64);
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn bitboards_match_the_board_they_are_drawn_from() {
+        let board: Sokoban = "\
+-----
+|@0^|
+-----"
+            .parse()
+            .unwrap();
+        let bits: Bitboard = Bitboard::new(&board).unwrap();
+
+        assert_eq!(bits.you(), coordinate::I2::new(1, 1));
+        assert!(bits.is_stop(coordinate::I2::new(0, 1)));
+        assert!(bits.is_push(coordinate::I2::new(2, 1)));
+        assert!(bits.is_target(coordinate::I2::new(3, 1)));
+        assert!(!bits.is_push(coordinate::I2::new(3, 1)));
+        // off the edge
+        assert!(bits.is_stop(coordinate::I2::new(10, 10)));
+    }
+
+    #[test]
+    fn boards_spread_too_far_get_no_bitboard() {
+        let board: Sokoban = Sokoban::new(
+            coordinate::I2::new(0, 0),
+            coordinate::I2Array::from(vec![[i32::MAX - 1, i32::MAX - 1]]),
+            coordinate::I2Array::from(vec![]),
+            coordinate::I2Array::from(vec![]),
+        );
+
+        assert_eq!(Bitboard::new(&board), None);
+    }
+}
@@ -0,0 +1,251 @@
+//! Everything done on a board, so a game can be put down and picked
+//! back up
+//!
+//! [`Sokoban::history`] only keeps your moves that haven't been undone,
+//! which is enough to play back a win, but not to carry on a game where
+//! it was left: undos, rewinds, resets, and your partner's moves all
+//! change what's on the board and what can still be undone.  A
+//! [`History`] keeps every one of them, in order, so playing it back on
+//! the board it started from gives the same board, undos and
+//! [`GameStats`] included.
+//!
+//! [`GameStats`]: super::GameStats
+//!
+//! Histories are exported as a line of text, one character per action,
+//! which is what they're stored as with the `serde` feature.
+
+use super::Sokoban;
+use crate::coordinate;
+
+/// One thing done to a board
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum Action {
+    /// You moved toward the given direction, whether or not you got
+    /// anywhere
+    You(coordinate::Direction),
+    /// Your partner moved toward the given direction
+    Partner(coordinate::Direction),
+    /// The last move was taken back with [`Sokoban::undo`]
+    Undo,
+    /// Up to this many moves were taken back with [`Sokoban::rewind`]
+    Rewind(usize),
+    /// The board was put back how it started with [`Sokoban::reset`]
+    Reset,
+}
+
+impl Action {
+    /// Do the action on `board`
+    pub fn apply(&self, board: &mut Sokoban) {
+        match *self {
+            Action::You(direction) => {
+                board.you_move_mut(direction);
+            }
+            Action::Partner(direction) => {
+                board.partner_move_mut(direction);
+            }
+            Action::Undo => *board = board.undo(),
+            Action::Rewind(moves) => *board = board.rewind(moves),
+            Action::Reset => *board = board.reset(),
+        }
+    }
+}
+
+/// Every action done on a board since it started, in order
+///
+/// # Examples
+///
+/// ```
+/// let start: Sokoban = "@0 ^".parse().unwrap();
+/// let history: History = History::import("rrzr").unwrap();
+/// let board: Sokoban = history.replay(&start);
+///
+/// assert!(board.all_targets_triggered());
+/// assert_eq!(board.stats().undos, 1);
+/// assert_eq!(history.export(), "rrzr");
+/// ```
+#[derive(Debug, Clone, Default, PartialEq)]
+#[cfg_attr(
+    feature = "serde",
+    derive(serde::Serialize, serde::Deserialize),
+    serde(try_from = "String", into = "String")
+)]
+pub struct History {
+    actions: Vec<Action>,
+}
+
+impl History {
+    /// A history with nothing in it yet
+    pub fn new() -> History {
+        History::default()
+    }
+
+    /// Remember that `action` was done, after everything else
+    pub fn push(&mut self, action: Action) {
+        self.actions.push(action);
+    }
+
+    /// Gets every action, first to last
+    pub fn actions(&self) -> &[Action] {
+        &self.actions
+    }
+
+    /// Do every action in turn on `start`, which should be the board
+    /// the history was made on
+    ///
+    /// Only [`GameStats::elapsed`] isn't brought back, since the time
+    /// between actions isn't kept.
+    ///
+    /// [`GameStats::elapsed`]: super::GameStats::elapsed
+    pub fn replay(&self, start: &Sokoban) -> Sokoban {
+        let mut board: Sokoban = start.clone();
+        for action in &self.actions {
+            action.apply(&mut board);
+        }
+        board
+    }
+
+    /// Writes the history as a line of text for [`History::import`]
+    ///
+    /// Your moves are `u`, `l`, `d`, and `r`, and your partner's are
+    /// the same in capitals.  An undo is `z`, a reset is `x`, and a
+    /// rewind is `w` followed by how many moves it was for, like `w3`.
+    pub fn export(&self) -> String {
+        let mut text: String = String::new();
+        for action in &self.actions {
+            match *action {
+                Action::You(direction) => text.push(direction_char(direction)),
+                Action::Partner(direction) => {
+                    text.push(direction_char(direction).to_ascii_uppercase())
+                }
+                Action::Undo => text.push('z'),
+                Action::Rewind(moves) => text.push_str(&format!("w{}", moves)),
+                Action::Reset => text.push('x'),
+            }
+        }
+        text
+    }
+
+    /// Reads a history written by [`History::export`]
+    ///
+    /// Anything else in `text`, including spaces, will `Err`.
+    pub fn import(text: &str) -> Result<History, &'static str> {
+        let mut actions: Vec<Action> = vec![];
+        let mut chars = text.chars().peekable();
+        while let Some(c) = chars.next() {
+            let action: Action = match c {
+                'u' | 'l' | 'd' | 'r' => Action::You(char_direction(c)),
+                'U' | 'L' | 'D' | 'R' => Action::Partner(char_direction(c.to_ascii_lowercase())),
+                'z' => Action::Undo,
+                'x' => Action::Reset,
+                'w' => {
+                    let mut digits: String = String::new();
+                    while let Some(digit) = chars.next_if(char::is_ascii_digit) {
+                        digits.push(digit);
+                    }
+                    Action::Rewind(digits.parse().map_err(|_| "Not a History")?)
+                }
+                _ => return Err("Not a History"),
+            };
+            actions.push(action);
+        }
+        Ok(History { actions })
+    }
+}
+
+impl TryFrom<String> for History {
+    type Error = &'static str;
+
+    /// Reads a history like [`History::import`]
+    fn try_from(text: String) -> Result<Self, Self::Error> {
+        History::import(&text)
+    }
+}
+
+impl From<History> for String {
+    fn from(history: History) -> Self {
+        history.export()
+    }
+}
+
+/// How `direction` is written in an exported history
+fn direction_char(direction: coordinate::Direction) -> char {
+    match direction {
+        coordinate::Direction::Up => 'u',
+        coordinate::Direction::Left => 'l',
+        coordinate::Direction::Down => 'd',
+        coordinate::Direction::Right => 'r',
+    }
+}
+
+/// The direction written as `c`, which must be one of `u`, `l`, `d`, or
+/// `r`
+fn char_direction(c: char) -> coordinate::Direction {
+    match c {
+        'u' => coordinate::Direction::Up,
+        'l' => coordinate::Direction::Left,
+        'd' => coordinate::Direction::Down,
+        _ => coordinate::Direction::Right,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn replaying_brings_back_the_undos_too() {
+        let start: Sokoban = "\
+-------
+|@00 ^|
+|     |
+-------"
+            .parse::<Sokoban>()
+            .unwrap()
+            .with_partner(coordinate::I2::new(5, 2))
+            .with_rewinds(1, 2);
+        let history: History = History::import("rulUzw2xrdR").unwrap();
+
+        let right: coordinate::Direction = coordinate::Direction::Right;
+        let up: coordinate::Direction = coordinate::Direction::Up;
+        let played: Sokoban = start
+            .you_move(right)
+            .you_move(up)
+            .you_move(coordinate::Direction::Left)
+            .partner_move(up)
+            .undo()
+            .rewind(2)
+            .reset()
+            .you_move(right)
+            .you_move(coordinate::Direction::Down)
+            .partner_move(right);
+        let replayed: Sokoban = history.replay(&start);
+        assert_eq!(replayed, played);
+        assert_eq!(replayed.stats(), played.stats());
+        assert_eq!(replayed.undo(), played.undo());
+        assert_eq!(replayed.undo().undo(), played.undo().undo());
+    }
+
+    #[test]
+    fn histories_round_trip_through_text() {
+        let text: &str = "uldrULDRzxw0w12";
+        let history: History = History::import(text).unwrap();
+        assert_eq!(history.actions().len(), 12);
+        assert_eq!(history.actions()[11], Action::Rewind(12));
+        assert_eq!(history.export(), text);
+
+        assert_eq!(History::import(""), Ok(History::new()));
+        assert!(History::import("w").is_err());
+        assert!(History::import("r r").is_err());
+        assert!(History::import("q").is_err());
+    }
+
+    #[cfg(feature = "serde")]
+    #[test]
+    fn histories_round_trip_through_serde() {
+        let history: History = History::import("rrzw3").unwrap();
+        let written: String = serde_json::to_string(&history).unwrap();
+        assert_eq!(written, "\"rrzw3\"");
+        assert_eq!(serde_json::from_str::<History>(&written).unwrap(), history);
+        assert!(serde_json::from_str::<History>("\"rr?\"").is_err());
+    }
+}
@@ -0,0 +1,132 @@
+//! Where each kind of thing that's nothing more than a position is
+//!
+//! Most of what's on a board only ever needs to say where it is: a
+//! stop, a push, a patch of ice.  Rather than a field apiece, they're
+//! kept in [`Layers`], one list of positions for each [`EntityKind`], so
+//! a new kind of tile is a new variant rather than a new field that
+//! every method moving, comparing, or transforming boards has to learn
+//! about.
+
+use std::ops::Index;
+use std::rc::Rc;
+
+use crate::coordinate;
+
+/// A kind of thing on a board that's nothing more than where it is
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum EntityKind {
+    /// Stopping collision
+    Stop,
+    /// A pushable object
+    Push,
+    /// Where the pushes need to go
+    Target,
+    /// A push that can't be pushed along with others in a line
+    Heavy,
+    /// A push that drags its neighbours along with it
+    Sticky,
+    /// A push that merges with the pushes it's pushed into
+    Mergeable,
+    /// Floor that breaks merged pushes apart
+    Splitter,
+    /// Cracked floor that breaks once walked off of
+    Crack,
+    /// Icy floor that pushes slide over
+    Ice,
+}
+
+impl EntityKind {
+    /// Every kind of entity
+    pub const ALL: [EntityKind; 9] = [
+        EntityKind::Stop,
+        EntityKind::Push,
+        EntityKind::Target,
+        EntityKind::Heavy,
+        EntityKind::Sticky,
+        EntityKind::Mergeable,
+        EntityKind::Splitter,
+        EntityKind::Crack,
+        EntityKind::Ice,
+    ];
+
+    /// The kinds that say something about the push at the same
+    /// position, and so go wherever it goes
+    pub const ATTACHED: [EntityKind; 3] =
+        [EntityKind::Heavy, EntityKind::Sticky, EntityKind::Mergeable];
+}
+
+/// One list of positions for each [`EntityKind`]
+///
+/// Each layer is shared between copies of a board until one of them
+/// changes it, so layers that rarely change, like stops and targets,
+/// are hardly ever copied.
+#[derive(Debug, Clone, PartialEq)]
+pub(crate) struct Layers {
+    layers: [Rc<coordinate::I2Array>; EntityKind::ALL.len()],
+}
+
+impl Layers {
+    /// Layers with nothing on any of them
+    pub(crate) fn new() -> Layers {
+        Layers {
+            layers: std::array::from_fn(|_| Rc::new(coordinate::I2Array::from(vec![]))),
+        }
+    }
+
+    /// The positions on the layer of `kind`, ready to be changed
+    pub(crate) fn get_mut(&mut self, kind: EntityKind) -> &mut coordinate::I2Array {
+        Rc::make_mut(&mut self.layers[kind as usize])
+    }
+
+    /// Replace the positions on the layer of `kind` with `cells`
+    pub(crate) fn set(&mut self, kind: EntityKind, cells: coordinate::I2Array) {
+        self.layers[kind as usize] = Rc::new(cells);
+    }
+
+    /// Pass each position on the layer of `kind` through `cell`
+    pub(crate) fn map(
+        &mut self,
+        kind: EntityKind,
+        cell: impl Fn(&coordinate::I2) -> coordinate::I2,
+    ) {
+        let cells: coordinate::I2Array = self[kind].iter().map(cell).collect();
+        self.set(kind, cells);
+    }
+
+    /// Keep only the positions on the layer of `kind` that pass `keep`
+    pub(crate) fn retain(&mut self, kind: EntityKind, keep: impl Fn(&coordinate::I2) -> bool) {
+        if self[kind].iter().all(&keep) {
+            return;
+        }
+        let cells: coordinate::I2Array = self[kind]
+            .iter()
+            .filter(|cell| keep(cell))
+            .copied()
+            .collect();
+        self.set(kind, cells);
+    }
+
+    /// These layers with every position passed through `cell`
+    pub(crate) fn transformed(&self, cell: &dyn Fn(coordinate::I2) -> coordinate::I2) -> Layers {
+        Layers {
+            layers: std::array::from_fn(|index| {
+                Rc::new(self.layers[index].iter().map(|at| cell(*at)).collect())
+            }),
+        }
+    }
+
+    /// Checks if the layer of `kind` is the very same one as in `other`,
+    /// rather than a copy of it
+    #[cfg(test)]
+    pub(crate) fn shares(&self, other: &Layers, kind: EntityKind) -> bool {
+        Rc::ptr_eq(&self.layers[kind as usize], &other.layers[kind as usize])
+    }
+}
+
+impl Index<EntityKind> for Layers {
+    type Output = coordinate::I2Array;
+
+    fn index(&self, kind: EntityKind) -> &coordinate::I2Array {
+        &self.layers[kind as usize]
+    }
+}
@@ -0,0 +1,1095 @@
+//! Searching for the moves that solve a board
+//!
+//! The solvers here only ever move you, never a partner, and play by
+//! the rules of [`Sokoban::you_move`], so anything a board can do on a
+//! move, the solvers take into account.  What they make as few of
+//! differs, though.  [`breadth_first`] and [`iterative_deepening`] find
+//! the fewest steps, while [`a_star`] and [`optimal`] count moves the
+//! way [`Sokoban::moves`] does, with tiles that cost more than one move
+//! to step onto costing that much.
+//!
+//! Boards don't have edges, so to keep the search finite nothing may
+//! leave the area the board starts in, that is, the smallest rectangle
+//! containing everything on it, grown by one tile on each side so you
+//! can walk around the outside.
+//!
+//! Besides [`breadth_first`], the solvers take a push shoved into a
+//! tunnel, a corridor one tile wide, all the way through it as a single
+//! step, since there's nothing else worth doing with it on the way.
+//! Corridor-heavy boards have far fewer boards to look at that way.
+//!
+//! The solvers also skip over boards with a dead corral, a walled-off
+//! area with an empty target in it that no push can ever get into.
+
+use std::cmp::Reverse;
+use std::collections::{BinaryHeap, HashMap, HashSet, VecDeque};
+use std::time::{Duration, Instant};
+
+use super::bitboard::Bitboard;
+use super::{Bounds, EntityKind, Item, MoveOutcome, Movement, Sokoban, DIRECTIONS};
+use crate::coordinate;
+
+/// How much a solver may do before giving up
+///
+/// The default is to never give up.
+#[derive(Debug, Clone, Copy, PartialEq, Default)]
+pub struct Limits {
+    /// The most boards to look at, or `None` for no limit
+    pub nodes: Option<usize>,
+    /// The longest to spend looking, or `None` for no limit
+    pub time: Option<Duration>,
+}
+
+/// What came of a solver that works within [`Limits`]
+#[derive(Debug, Clone, PartialEq)]
+pub enum SolverResult {
+    /// The moves that solve the board
+    Solved(Vec<coordinate::Direction>),
+    /// The board can't be solved
+    Unsolvable,
+    /// The solver hit its limits before it could say either way
+    Timeout,
+}
+
+/// What a solver tries to make as few of as it can
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum Objective {
+    /// Every move counts, whether it pushes anything or not, by what
+    /// it costs as [`Sokoban::moves`] counts it
+    #[default]
+    Moves,
+    /// Only moves that push something count, and the rest only break
+    /// ties
+    Pushes,
+}
+
+impl Objective {
+    /// The cost of having made `moves` moves, `pushes` of which pushed
+    /// something, with what matters most first
+    fn rank(&self, moves: usize, pushes: usize) -> (usize, usize) {
+        match self {
+            Objective::Moves => (moves, pushes),
+            Objective::Pushes => (pushes, moves),
+        }
+    }
+}
+
+/// The moves that solve a board, along with how they measure up
+#[derive(Debug, Clone, PartialEq)]
+pub struct Solution {
+    /// The moves that solve the board
+    pub moves: Vec<coordinate::Direction>,
+    /// How many of the moves pushed something
+    pub pushes: usize,
+}
+
+/// A board a search has seen, along with the index of the board and
+/// the moves that led to it
+type Seen = (Sokoban, Option<(usize, Vec<coordinate::Direction>)>);
+
+/// Everything that can change over the course of solving a board
+///
+/// You, stops, and pushes are kept as a [`Layout`], and the rest are
+/// sorted, so that boards that only differ in which push is which are
+/// seen as the same.
+#[derive(Clone, PartialEq, Eq, Hash)]
+struct Key {
+    layout: Layout,
+    clones: Vec<coordinate::I2>,
+    cracks: Vec<coordinate::I2>,
+    durability: Vec<(coordinate::I2, u32)>,
+    colors: Vec<(coordinate::I2, u32)>,
+    heavies: Vec<coordinate::I2>,
+    stickies: Vec<coordinate::I2>,
+    mergeables: Vec<coordinate::I2>,
+    sizes: Vec<(coordinate::I2, u32)>,
+    enemies: Vec<(coordinate::I2, usize)>,
+    items: Vec<Item>,
+    collected: Vec<Item>,
+}
+
+/// Where you, stops, pushes, and targets are on a board
+#[derive(Clone, PartialEq, Eq, Hash)]
+enum Layout {
+    /// A [`Bitboard`] over the bounds of the search
+    Dense(Bitboard),
+    /// Sorted lists of where everything is, for searches over more than
+    /// [`MAX_AREA`](super::bitboard::MAX_AREA) tiles
+    Sparse {
+        you: coordinate::I2,
+        stops: Vec<coordinate::I2>,
+        pushes: Vec<coordinate::I2>,
+        targets: Vec<coordinate::I2>,
+    },
+}
+
+impl Key {
+    fn new(board: &Sokoban, bounds: &Bounds) -> Key {
+        let sorted = |cells: &coordinate::I2Array| {
+            let mut cells: Vec<coordinate::I2> = cells.iter().copied().collect();
+            cells.sort_by_key(|cell| (cell.x(), cell.y()));
+            cells
+        };
+        let mut durability: Vec<(coordinate::I2, u32)> = board.durability.clone();
+        durability.sort_by_key(|(push, _)| (push.x(), push.y()));
+        let mut colors: Vec<(coordinate::I2, u32)> = board.colors.clone();
+        colors.sort_by_key(|(push, _)| (push.x(), push.y()));
+        let mut sizes: Vec<(coordinate::I2, u32)> = board.sizes.clone();
+        sizes.sort_by_key(|(push, _)| (push.x(), push.y()));
+        let sorted_items = |items: &[Item]| {
+            let mut items: Vec<Item> = items.to_vec();
+            items.sort_by_key(|item| (item.at.x(), item.at.y(), item.kind, item.collectible));
+            items
+        };
+
+        Key {
+            layout: match Bitboard::within(board, bounds) {
+                Some(bits) => Layout::Dense(bits),
+                None => Layout::Sparse {
+                    you: board.you,
+                    stops: sorted(&board.layers[EntityKind::Stop]),
+                    pushes: sorted(&board.layers[EntityKind::Push]),
+                    targets: sorted(&board.layers[EntityKind::Target]),
+                },
+            },
+            clones: sorted(&board.clones),
+            cracks: sorted(&board.layers[EntityKind::Crack]),
+            durability,
+            colors,
+            heavies: sorted(&board.layers[EntityKind::Heavy]),
+            stickies: sorted(&board.layers[EntityKind::Sticky]),
+            mergeables: sorted(&board.layers[EntityKind::Mergeable]),
+            sizes,
+            enemies: board
+                .enemies
+                .iter()
+                .map(|enemy| match enemy.movement {
+                    Movement::Patrol { next, .. } => (enemy.at, next),
+                    Movement::Chase => (enemy.at, 0),
+                })
+                .collect(),
+            items: sorted_items(&board.items),
+            collected: sorted_items(&board.collected),
+        }
+    }
+}
+
+/// The boards one move away from `board`, along with the move
+///
+/// Moves that don't change anything, that go out of `bounds`, that get
+/// you caught, that run out the move budget, or that leave a dead
+/// corral are left out.  The boards are [`without_past`] them.
+fn successors<'a>(
+    board: &'a Sokoban,
+    bounds: &'a Bounds,
+) -> impl Iterator<Item = (coordinate::Direction, Sokoban)> + 'a {
+    DIRECTIONS.into_iter().filter_map(move |direction| {
+        let next: Sokoban = without_past(board.you_move(direction));
+        (next != *board
+            && !next.is_lost()
+            && !next.is_failed()
+            && bounds.contains_board(&next)
+            && !has_dead_corral(&next, bounds))
+        .then_some((direction, next))
+    })
+}
+
+/// `board` without the moves that led to it
+///
+/// Every board a move makes holds on to the one before it, for
+/// [`Sokoban::undo`], and to the moves made so far, if they're being
+/// recorded.  The searches keep track of how they got to a board
+/// themselves, so holding on to all that would only take up memory for
+/// every board they've seen.
+fn without_past(board: Sokoban) -> Sokoban {
+    Sokoban {
+        previous: None,
+        history: None,
+        ..board
+    }
+}
+
+/// Checks if `board` has a corral with an empty target that no push
+/// can ever be pushed into
+///
+/// A corral is an area you can't walk into, shut off by stops and
+/// pushes.  The only way into it for a push is from one of the pushes
+/// along its edge, and that takes you standing on the far side of that
+/// push.  If there's a stop there, or a push that can never move, then
+/// nothing can ever get in, and so a target inside is never triggered.
+///
+/// Boards with anything that moves pushes other than by you shoving
+/// them, or that makes pushes disappear, could get a push into a corral
+/// some other way, so they never have dead corrals.  Nor do boards
+/// spread out over too many tiles to flood.
+fn has_dead_corral(board: &Sokoban, bounds: &Bounds) -> bool {
+    if board.has_more_than_pushing() || !bounds.is_small() {
+        return false;
+    }
+
+    let closed_walls: coordinate::I2Array = board.closed_walls();
+    let flood = |from: coordinate::I2, is_open: &dyn Fn(&coordinate::I2) -> bool| {
+        let mut area: HashSet<coordinate::I2> = HashSet::from([from]);
+        let mut frontier: Vec<coordinate::I2> = vec![from];
+        while let Some(cell) = frontier.pop() {
+            for neighbor in DIRECTIONS
+                .iter()
+                .filter_map(|direction| cell.nudge(*direction))
+            {
+                if is_open(&neighbor) && area.insert(neighbor) {
+                    frontier.push(neighbor);
+                }
+            }
+        }
+        area
+    };
+    let reachable: HashSet<coordinate::I2> = flood(board.you, &|cell| {
+        bounds.contains(cell)
+            && !board.layers[EntityKind::Stop].contains(cell)
+            && !board.layers[EntityKind::Push].contains(cell)
+            && !closed_walls.contains(cell)
+            && board.partner != Some(*cell)
+    });
+    // switch walls and your partner are only in the way of you, not
+    // of the corral, so it's counted as bigger than it might be
+    let is_corral = |cell: &coordinate::I2| {
+        bounds.contains(cell)
+            && !board.layers[EntityKind::Stop].contains(cell)
+            && !board.layers[EntityKind::Push].contains(cell)
+            && !reachable.contains(cell)
+    };
+
+    board.layers[EntityKind::Target]
+        .iter()
+        .filter(|target| is_corral(target))
+        .any(|target| {
+            let corral: HashSet<coordinate::I2> = flood(*target, &is_corral);
+            corral.iter().all(|cell| {
+                DIRECTIONS.iter().all(|direction| {
+                    let Some(edge) = cell.nudge(*direction) else {
+                        return true;
+                    };
+                    if !board.layers[EntityKind::Push].contains(&edge) {
+                        return true;
+                    }
+                    match edge.nudge(*direction) {
+                        Some(behind) => {
+                            board.layers[EntityKind::Stop].contains(&behind)
+                                || (board.layers[EntityKind::Push].contains(&behind)
+                                    && is_stuck(board, behind, &[]))
+                        }
+                        None => true,
+                    }
+                })
+            })
+        })
+}
+
+/// Checks if `push` can never be moved, because along each axis
+/// there's a stop or a stuck push on at least one side of it
+///
+/// `walls` are pushes already taken to be stuck, so pushes holding
+/// each other in place don't go around in circles.
+fn is_stuck(board: &Sokoban, push: coordinate::I2, walls: &[coordinate::I2]) -> bool {
+    let mut walls: Vec<coordinate::I2> = walls.to_vec();
+    walls.push(push);
+    [
+        [coordinate::Direction::Up, coordinate::Direction::Down],
+        [coordinate::Direction::Left, coordinate::Direction::Right],
+    ]
+    .into_iter()
+    .all(|axis| {
+        axis.iter().any(|direction| match push.nudge(*direction) {
+            Some(side) => {
+                board.layers[EntityKind::Stop].contains(&side)
+                    || walls.contains(&side)
+                    || (board.layers[EntityKind::Push].contains(&side)
+                        && is_stuck(board, side, &walls))
+            }
+            None => true,
+        })
+    })
+}
+
+/// The boards reachable from `board` like [`successors`], except that
+/// a push shoved into a tunnel is shoved on through it, along with the
+/// moves it took
+///
+/// The push stops short of the end of the tunnel if it comes onto a
+/// target, or if shoving it any further would do more than slide it
+/// along one tile.
+fn macro_successors<'a>(
+    board: &'a Sokoban,
+    bounds: &'a Bounds,
+) -> impl Iterator<Item = (Vec<coordinate::Direction>, Sokoban)> + 'a {
+    successors(board, bounds).map(move |(direction, next)| {
+        let mut moves: Vec<coordinate::Direction> = vec![direction];
+        let mut next: Sokoban = next;
+        let pushed: bool =
+            board.layers[EntityKind::Push].contains(&next.you) && next.events().is_empty();
+        while pushed && is_in_tunnel(&next, direction) {
+            let (further, outcome): (Sokoban, MoveOutcome) = next.you_move_outcome(direction);
+            if outcome != MoveOutcome::Pushed(1)
+                || !further.events().is_empty()
+                || !bounds.contains_board(&further)
+            {
+                break;
+            }
+            moves.push(direction);
+            next = without_past(further);
+        }
+        (moves, next)
+    })
+}
+
+/// Checks if you're in a tunnel behind a push, facing `direction`
+///
+/// That is, the push is just ahead of you and not on a target, and
+/// both of you have stops on either side.
+fn is_in_tunnel(board: &Sokoban, direction: coordinate::Direction) -> bool {
+    let sides: [coordinate::Direction; 2] = match direction {
+        coordinate::Direction::Up | coordinate::Direction::Down => {
+            [coordinate::Direction::Left, coordinate::Direction::Right]
+        }
+        coordinate::Direction::Left | coordinate::Direction::Right => {
+            [coordinate::Direction::Up, coordinate::Direction::Down]
+        }
+    };
+    let Some(push) = board.you.nudge(direction) else {
+        return false;
+    };
+    board.layers[EntityKind::Push].contains(&push)
+        && !board.layers[EntityKind::Target].contains(&push)
+        && [board.you, push].iter().all(|cell| {
+            sides.iter().all(|side| {
+                cell.nudge(*side)
+                    .is_some_and(|side| board.layers[EntityKind::Stop].contains(&side))
+            })
+        })
+}
+
+/// Find one of the shortest sequences of moves that triggers all the
+/// targets on `board`
+///
+/// This tries every sequence of moves in order of length, so it always
+/// finds a shortest solution, but it's only practical for small
+/// boards.  If the board can't be solved, this is `None`, which
+/// includes boards with no targets, since [`Sokoban::is_won`] never
+/// counts them as won.
+///
+/// # Examples
+///
+/// ```
+/// let board: Sokoban = "@0.^".parse().unwrap();
+///
+/// assert_eq!(
+///     solver::breadth_first(&board),
+///     Some(vec![coordinate::Direction::Right, coordinate::Direction::Right])
+/// );
+/// ```
+pub fn breadth_first(board: &Sokoban) -> Option<Vec<coordinate::Direction>> {
+    if !has_targets(board) {
+        return None;
+    }
+    let bounds: Bounds = Bounds::new(board);
+    // every board seen, along with the board and moves that led to it
+    let mut seen: Vec<Seen> = vec![(without_past(board.clone()), None)];
+    let mut keys: HashSet<Key> = HashSet::from([Key::new(board, &bounds)]);
+    let mut frontier: VecDeque<usize> = VecDeque::from([0]);
+
+    while let Some(index) = frontier.pop_front() {
+        if seen[index].0.is_won() {
+            return Some(moves_to(&seen, index));
+        }
+
+        let next_boards: Vec<(coordinate::Direction, Sokoban)> =
+            successors(&seen[index].0, &bounds).collect();
+        for (direction, next) in next_boards {
+            if keys.insert(Key::new(&next, &bounds)) {
+                seen.push((next, Some((index, vec![direction]))));
+                frontier.push_back(seen.len() - 1);
+            }
+        }
+    }
+    None
+}
+
+/// Find one of the cheapest sequences of moves that triggers all the
+/// targets on `board`, looking at the most promising moves first
+///
+/// Where every tile costs one move to step onto, this finds the same
+/// length of solution as [`breadth_first`], and otherwise it finds the
+/// solution that adds the least to [`Sokoban::moves`].  It steers
+/// toward moves that bring pushes closer to targets, which
+/// makes it practical for much bigger boards.  The exception is on
+/// boards with magnets, which move pushes for free and so can lead it
+/// to a solution that's a little longer than it needs to be.  It also
+/// shoves pushes through tunnels in one go.
+///
+/// If the board can't be solved, this is `None`.
+pub fn a_star(board: &Sokoban) -> Option<Vec<coordinate::Direction>> {
+    optimal(board, Objective::Moves).map(|solution| solution.moves)
+}
+
+/// Find a solution to `board` with as few of whatever `objective`
+/// counts as possible, the way [`a_star`] does
+///
+/// Ties are broken by whatever the objective doesn't count, so a
+/// solution with the fewest pushes also has the fewest moves of any
+/// with that many pushes, and the other way around.  If the board can't
+/// be solved, this is `None`.
+///
+/// # Examples
+///
+/// ```
+/// let board: Sokoban = "@0.^".parse().unwrap();
+/// let solution: solver::Solution = solver::optimal(&board, solver::Objective::Pushes).unwrap();
+///
+/// assert_eq!(solution.moves.len(), 2);
+/// assert_eq!(solution.pushes, 2);
+/// ```
+pub fn optimal(board: &Sokoban, objective: Objective) -> Option<Solution> {
+    if !has_targets(board) {
+        return None;
+    }
+    let bounds: Bounds = Bounds::new(board);
+    // every board seen, along with the board and moves that led to it
+    let mut seen: Vec<Seen> = vec![(without_past(board.clone()), None)];
+    // how many moves and pushes it took to get to each board seen
+    let mut costs: Vec<(usize, usize)> = vec![(0, 0)];
+    // the best each board has been reached in, as ranked by the objective
+    let mut best: HashMap<Key, (usize, usize)> =
+        HashMap::from([(Key::new(board, &bounds), (0, 0))]);
+    // boards to look at next ordered by their estimated total of what
+    // matters most, then by what matters least so far, and then by the
+    // order they were seen in to break ties
+    let mut frontier: BinaryHeap<Reverse<(usize, usize, usize)>> =
+        BinaryHeap::from([Reverse((distance_to_solved(board), 0, 0))]);
+
+    while let Some(Reverse((_, _, index))) = frontier.pop() {
+        let board: &Sokoban = &seen[index].0;
+        let (moves, pushes): (usize, usize) = costs[index];
+        let rank: (usize, usize) = objective.rank(moves, pushes);
+        if best.get(&Key::new(board, &bounds)) != Some(&rank) {
+            // it's since been reached in fewer
+            continue;
+        }
+        if board.is_won() {
+            return Some(Solution {
+                moves: moves_to(&seen, index),
+                pushes,
+            });
+        }
+
+        let shoved: u32 = board.stats.pushes;
+        let spent: u32 = board.moves();
+        let next_boards: Vec<(Vec<coordinate::Direction>, Sokoban)> =
+            macro_successors(board, &bounds).collect();
+        for (steps, next) in next_boards {
+            // only the first of several steps can be without a push
+            let pushes: usize = if next.stats.pushes > shoved {
+                pushes + steps.len()
+            } else {
+                pushes
+            };
+            // steps onto costly tiles count for more than one move
+            let moves: usize = moves + next.moves().saturating_sub(spent) as usize;
+            let rank: (usize, usize) = objective.rank(moves, pushes);
+            let key: Key = Key::new(&next, &bounds);
+            if best.get(&key).is_some_and(|best| *best <= rank) {
+                continue;
+            }
+            best.insert(key, rank);
+            let estimate: usize = rank.0 + distance_to_solved(&next);
+            seen.push((next, Some((index, steps))));
+            costs.push((moves, pushes));
+            frontier.push(Reverse((estimate, rank.1, seen.len() - 1)));
+        }
+    }
+    None
+}
+
+/// Find one of the shortest sequences of moves that triggers all the
+/// targets on `board`, using hardly any memory
+///
+/// This searches depth-first, giving up on any sequence of moves that
+/// [`a_star`]'s estimate says can't finish within some number of
+/// moves, and raising that number each time it comes up empty.  Only
+/// the moves being tried are remembered, so big boards that would run
+/// [`breadth_first`] or [`a_star`] out of memory can still be solved,
+/// given time.  That's also why it takes `limits`.  Shoving a push
+/// through a tunnel counts as just one board toward them.
+///
+/// # Examples
+///
+/// ```
+/// let board: Sokoban = "@0.^".parse().unwrap();
+/// let limits: solver::Limits = solver::Limits {
+///     nodes: Some(1_000_000),
+///     time: Some(Duration::from_secs(1)),
+/// };
+///
+/// assert_eq!(
+///     solver::iterative_deepening(&board, limits),
+///     solver::SolverResult::Solved(vec![
+///         coordinate::Direction::Right,
+///         coordinate::Direction::Right,
+///     ])
+/// );
+/// ```
+pub fn iterative_deepening(board: &Sokoban, limits: Limits) -> SolverResult {
+    if !has_targets(board) {
+        return SolverResult::Unsolvable;
+    }
+    let bounds: Bounds = Bounds::new(board);
+    let mut search: Deepening = Deepening {
+        path: HashSet::from([Key::new(board, &bounds)]),
+        bounds,
+        limits,
+        started: Instant::now(),
+        nodes: 0,
+        moves: vec![],
+    };
+    let mut threshold: usize = distance_to_solved(board);
+    loop {
+        match search.deepen(board, threshold) {
+            Deepened::Solved => return SolverResult::Solved(search.moves),
+            Deepened::Exceeded(estimate) => threshold = estimate,
+            Deepened::Exhausted => return SolverResult::Unsolvable,
+            Deepened::OutOfLimits => return SolverResult::Timeout,
+        }
+    }
+}
+
+/// The state of an [`iterative_deepening`] search
+struct Deepening {
+    bounds: Bounds,
+    limits: Limits,
+    started: Instant,
+    /// How many boards have been looked at
+    nodes: usize,
+    /// The boards along the moves being tried
+    path: HashSet<Key>,
+    /// The moves being tried
+    moves: Vec<coordinate::Direction>,
+}
+
+/// What came of one depth-first pass of an [`iterative_deepening`] search
+enum Deepened {
+    Solved,
+    /// Nothing within the threshold solved the board, and this is the
+    /// smallest estimate that went over it
+    Exceeded(usize),
+    /// Nothing at all solves the board
+    Exhausted,
+    OutOfLimits,
+}
+
+impl Deepening {
+    /// Try every sequence of moves on from `board` whose estimated
+    /// total is no more than `threshold`
+    fn deepen(&mut self, board: &Sokoban, threshold: usize) -> Deepened {
+        let estimate: usize = self.moves.len() + distance_to_solved(board);
+        if estimate > threshold {
+            return Deepened::Exceeded(estimate);
+        }
+        if board.is_won() {
+            return Deepened::Solved;
+        }
+
+        self.nodes += 1;
+        if self.limits.nodes.is_some_and(|nodes| self.nodes > nodes)
+            || self
+                .limits
+                .time
+                .is_some_and(|time| self.started.elapsed() > time)
+        {
+            return Deepened::OutOfLimits;
+        }
+
+        let mut smallest_excess: Option<usize> = None;
+        let next_boards: Vec<(Vec<coordinate::Direction>, Sokoban)> =
+            macro_successors(board, &self.bounds).collect();
+        for (steps, next) in next_boards {
+            let key: Key = Key::new(&next, &self.bounds);
+            if !self.path.insert(key.clone()) {
+                // going around in circles
+                continue;
+            }
+            let length: usize = self.moves.len();
+            self.moves.extend(steps);
+            match self.deepen(&next, threshold) {
+                Deepened::Solved => return Deepened::Solved,
+                Deepened::OutOfLimits => return Deepened::OutOfLimits,
+                Deepened::Exceeded(estimate) => {
+                    smallest_excess =
+                        Some(smallest_excess.map_or(estimate, |smallest| smallest.min(estimate)))
+                }
+                Deepened::Exhausted => {}
+            }
+            self.moves.truncate(length);
+            self.path.remove(&key);
+        }
+        smallest_excess.map_or(Deepened::Exhausted, Deepened::Exceeded)
+    }
+}
+
+/// A lower bound on the number of moves it'll take to solve `board`
+///
+/// Every target needs its own push, and each push needs at least as
+/// many moves as it is tiles from the nearest target.  So, if there
+/// are `n` targets, it'll take at least as many moves as it takes to
+/// get the furthest of the `n` pushes that are closest to a target onto
+/// one.
+///
+/// One move can shove a whole line of pushes along, up to the board's
+/// push limit, bringing each of them a tile closer at once.  So it'll
+/// also take at least the total distance of those `n` pushes split
+/// between as many of them as can be shoved together, and with a push
+/// limit of one, that's the total itself.
+fn distance_to_solved(board: &Sokoban) -> usize {
+    let mut distances: Vec<usize> = board.layers[EntityKind::Push]
+        .iter()
+        .map(|push| {
+            board.layers[EntityKind::Target]
+                .iter()
+                .map(|target| {
+                    (push.x().abs_diff(target.x()) + push.y().abs_diff(target.y())) as usize
+                })
+                .min()
+                .unwrap_or(0)
+        })
+        .collect();
+    distances.sort();
+    distances.truncate(board.layers[EntityKind::Target].iter().count());
+
+    let line: usize = board
+        .push_limit
+        .map_or(distances.len(), |limit| limit.min(distances.len()))
+        .max(1);
+    let furthest: usize = distances.last().copied().unwrap_or(0);
+    distances.iter().sum::<usize>().div_ceil(line).max(furthest)
+}
+
+/// Checks if `board` has any targets, without which it can never be won
+fn has_targets(board: &Sokoban) -> bool {
+    board.layers[EntityKind::Target].iter().next().is_some()
+}
+
+/// The moves that led from the first of `seen` to the one at `index`
+fn moves_to(seen: &[Seen], index: usize) -> Vec<coordinate::Direction> {
+    let mut moves: Vec<coordinate::Direction> = vec![];
+    let mut index: usize = index;
+    while let Some((parent, steps)) = &seen[index].1 {
+        moves.extend(steps.iter().rev());
+        index = *parent;
+    }
+    moves.reverse();
+    moves
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Checks that playing `moves` on `board` solves it
+    fn solves(board: &Sokoban, moves: &[coordinate::Direction]) -> bool {
+        moves
+            .iter()
+            .fold(board.clone(), |board, direction| board.you_move(*direction))
+            .all_targets_triggered()
+    }
+
+    #[test]
+    fn solved_boards_need_no_moves() {
+        let board: Sokoban = Sokoban::from_xsb("@*").unwrap();
+        assert_eq!(breadth_first(&board), Some(vec![]));
+    }
+
+    #[test]
+    fn boards_without_targets_have_no_solution() {
+        // there's nothing to trigger, but that's not a win either
+        let board: Sokoban = "@0".parse().unwrap();
+        assert!(!board.is_won());
+
+        assert_eq!(breadth_first(&board), None);
+        assert_eq!(a_star(&board), None);
+        assert_eq!(
+            iterative_deepening(&board, Limits::default()),
+            SolverResult::Unsolvable
+        );
+    }
+
+    #[test]
+    fn breadth_first_finds_a_shortest_solution() {
+        let board: Sokoban = "\
+-------
+|@   ^|
+| 0 ---
+|   |
+-----"
+            .parse()
+            .unwrap();
+
+        let moves: Vec<coordinate::Direction> = breadth_first(&board).unwrap();
+        assert!(solves(&board, &moves));
+        // around to below the push to shove it up, then back around to
+        // its left to shove it over
+        assert_eq!(moves.len(), 9);
+    }
+
+    #[test]
+    fn a_star_agrees_with_breadth_first() {
+        let boards: [&str; 3] = [
+            "\
+-------
+|@   ^|
+| 0 ---
+|   |
+-----",
+            "\
+--------
+|^  0 @|
+| 0  - |
+|   ^  |
+--------",
+            "\
+-----
+|0 ^|
+|@  |
+-----",
+        ];
+
+        for board in boards {
+            let board: Sokoban = board.parse().unwrap();
+            let moves: Option<Vec<coordinate::Direction>> = a_star(&board);
+
+            assert_eq!(
+                moves.as_ref().map(Vec::len),
+                breadth_first(&board).as_ref().map(Vec::len)
+            );
+            if let Some(moves) = moves {
+                assert!(solves(&board, &moves));
+            }
+        }
+    }
+
+    #[test]
+    fn iterative_deepening_agrees_with_breadth_first() {
+        let board: Sokoban = "\
+-------
+|@   ^|
+| 0 ---
+|   |
+-----"
+            .parse()
+            .unwrap();
+
+        match iterative_deepening(&board, Limits::default()) {
+            SolverResult::Solved(moves) => {
+                assert_eq!(moves.len(), breadth_first(&board).unwrap().len());
+                assert!(solves(&board, &moves));
+            }
+            result => panic!("expected a solution but got {:?}", result),
+        }
+    }
+
+    #[test]
+    fn iterative_deepening_gives_up_at_its_limits() {
+        let board: Sokoban = "\
+-------
+|@   ^|
+| 0 ---
+|   |
+-----"
+            .parse()
+            .unwrap();
+
+        assert_eq!(
+            iterative_deepening(
+                &board,
+                Limits {
+                    nodes: Some(3),
+                    time: None
+                }
+            ),
+            SolverResult::Timeout
+        );
+        assert_eq!(
+            iterative_deepening(
+                &board,
+                Limits {
+                    nodes: None,
+                    time: Some(Duration::ZERO)
+                }
+            ),
+            SolverResult::Timeout
+        );
+    }
+
+    #[test]
+    fn searches_do_not_hold_on_to_the_past() {
+        let board: Sokoban = "\
+-----------
+|@0      ^|
+| 0      ^|
+-----------"
+            .parse::<Sokoban>()
+            .unwrap()
+            .with_recording()
+            .you_move(coordinate::Direction::Down);
+        assert!(board.can_undo());
+        let bounds: Bounds = Bounds::new(&board);
+
+        for (_, next) in successors(&board, &bounds) {
+            assert!(!next.can_undo());
+            assert_eq!(next.history(), None);
+        }
+        for (_, next) in macro_successors(&board, &bounds) {
+            assert!(!next.can_undo());
+            assert_eq!(next.history(), None);
+        }
+    }
+
+    #[test]
+    fn pushes_go_through_tunnels_in_one_step() {
+        let board: Sokoban = "\
+-----------
+|@0      ^|
+-----------"
+            .parse()
+            .unwrap();
+
+        let through: Vec<(Vec<coordinate::Direction>, Sokoban)> =
+            macro_successors(&board, &Bounds::new(&board)).collect();
+        assert_eq!(through.len(), 1);
+        // it stops short of the target, so the last push is its own step
+        assert_eq!(through[0].0, vec![coordinate::Direction::Right; 6]);
+        assert_eq!(through[0].1.you(), coordinate::I2::new(7, 1));
+
+        assert_eq!(a_star(&board).map(|moves| moves.len()), Some(7));
+        match iterative_deepening(
+            &board,
+            Limits {
+                nodes: Some(2),
+                time: None,
+            },
+        ) {
+            SolverResult::Solved(moves) => {
+                assert_eq!(moves, vec![coordinate::Direction::Right; 7]);
+            }
+            result => panic!("expected a solution but got {:?}", result),
+        }
+    }
+
+    #[test]
+    fn walking_through_a_tunnel_is_not_pushing() {
+        let board: Sokoban = "\
+------
+|@  0|
+------"
+            .parse()
+            .unwrap();
+
+        let through: Vec<(Vec<coordinate::Direction>, Sokoban)> =
+            macro_successors(&board, &Bounds::new(&board)).collect();
+        assert_eq!(through.len(), 1);
+        assert_eq!(through[0].0, vec![coordinate::Direction::Right]);
+    }
+
+    #[test]
+    fn targets_no_push_can_get_to_are_dead_corrals() {
+        // the push in the corner is stuck, so there's never anywhere to
+        // stand to shove the other push down onto the target
+        let board: Sokoban = Sokoban::from_xsb(
+            "\
+ #######
+##*    #
+# $   @#
+##.#####
+ ###",
+        )
+        .unwrap();
+        assert!(has_dead_corral(&board, &Bounds::new(&board)));
+        assert!(board.deadlocked_pushes().iter().next().is_none());
+        // so the search gives up straight away rather than trying every
+        // way to shuffle the push about
+        assert_eq!(
+            iterative_deepening(
+                &board,
+                Limits {
+                    nodes: Some(1),
+                    time: None
+                }
+            ),
+            SolverResult::Unsolvable
+        );
+
+        // with room to stand there, it's fine
+        let board: Sokoban = Sokoban::from_xsb(
+            "\
+ #######
+## .   #
+# $   @#
+##.#####
+ ###",
+        )
+        .unwrap();
+        assert!(!has_dead_corral(&board, &Bounds::new(&board)));
+    }
+
+    #[test]
+    fn objectives_trade_moves_for_pushes() {
+        let board: Sokoban = "\
+-------
+|  ^  |
+| 0   |
+|^0 --|
+| @---|
+-------"
+            .parse()
+            .unwrap();
+
+        let fewest_moves: Solution = optimal(&board, Objective::Moves).unwrap();
+        assert!(solves(&board, &fewest_moves.moves));
+        assert_eq!(
+            fewest_moves.moves.len(),
+            breadth_first(&board).unwrap().len()
+        );
+        assert_eq!((fewest_moves.moves.len(), fewest_moves.pushes), (9, 4));
+
+        // walking the long way around saves a push
+        let fewest_pushes: Solution = optimal(&board, Objective::Pushes).unwrap();
+        assert!(solves(&board, &fewest_pushes.moves));
+        assert_eq!((fewest_pushes.moves.len(), fewest_pushes.pushes), (13, 3));
+    }
+
+    #[test]
+    fn fewest_moves_go_around_costly_tiles() {
+        // stepping onto the tile right of you costs 10 moves, so it's
+        // cheaper to walk around it
+        let board: Sokoban = "\
+-------
+|@  0^|
+|     |
+-------"
+            .parse::<Sokoban>()
+            .unwrap()
+            .with_costs(vec![(coordinate::I2::new(2, 1), 10)]);
+
+        let fewest_moves: Solution = optimal(&board, Objective::Moves).unwrap();
+        assert!(solves(&board, &fewest_moves.moves));
+        assert_eq!(fewest_moves.moves.len(), 5);
+        assert_eq!(breadth_first(&board).unwrap().len(), 3);
+        let played: Sokoban = fewest_moves
+            .moves
+            .iter()
+            .fold(board.clone(), |board, direction| board.you_move(*direction));
+        assert_eq!(played.moves(), 5);
+        assert_eq!(a_star(&board), Some(fewest_moves.moves));
+    }
+
+    #[test]
+    fn distance_to_solved_only_counts_the_pushes_it_needs() {
+        // two pushes, one target, and you're irrelevant
+        let board: Sokoban = "@0 ^  0".parse().unwrap();
+
+        assert_eq!(distance_to_solved(&board), 2);
+        assert_eq!(distance_to_solved(&"@^".parse().unwrap()), 0);
+    }
+
+    #[test]
+    fn chain_pushes_are_not_overestimated() {
+        // both pushes go one tile closer on each move
+        let board: Sokoban = "@00^^".parse().unwrap();
+        assert_eq!(board.push_limit(), None);
+
+        assert_eq!(distance_to_solved(&board), 2);
+        assert_eq!(a_star(&board).map(|moves| moves.len()), Some(2));
+        match iterative_deepening(&board, Limits::default()) {
+            SolverResult::Solved(moves) => assert_eq!(moves.len(), 2),
+            result => panic!("expected a solution but got {:?}", result),
+        }
+
+        // one at a time, it's the total distance
+        assert_eq!(distance_to_solved(&board.with_push_limit(Some(1))), 3);
+    }
+
+    #[test]
+    fn unsolvable_boards_have_no_solution() {
+        // the push is stuck in the corner
+        let board: Sokoban = "\
+-----
+|0 ^|
+|@  |
+-----"
+            .parse()
+            .unwrap();
+
+        assert_eq!(breadth_first(&board), None);
+        assert_eq!(a_star(&board), None);
+        assert_eq!(
+            iterative_deepening(&board, Limits::default()),
+            SolverResult::Unsolvable
+        );
+    }
+
+    #[test]
+    fn boards_spread_too_far_for_a_bitboard_are_still_solved() {
+        let mut board: Sokoban = "@0^".parse().unwrap();
+        board
+            .layers
+            .get_mut(EntityKind::Stop)
+            .push(coordinate::I2::new(i32::MAX - 1, i32::MAX - 1));
+
+        assert_eq!(
+            breadth_first(&board),
+            Some(vec![coordinate::Direction::Right])
+        );
+        assert_eq!(a_star(&board), Some(vec![coordinate::Direction::Right]));
+        assert_eq!(
+            iterative_deepening(&board, Limits::default()),
+            SolverResult::Solved(vec![coordinate::Direction::Right])
+        );
+        assert_eq!(
+            board.is_solvable(Limits::default()),
+            crate::sokoban::Solvability::Solvable
+        );
+    }
+
+    #[test]
+    fn picking_items_up_changes_the_board() {
+        let board: Sokoban = "\
+------
+|@ 0^|
+------"
+            .parse::<Sokoban>()
+            .unwrap()
+            .with_items(vec![Item {
+                at: coordinate::I2::new(2, 1),
+                kind: 0,
+                collectible: true,
+            }]);
+        let bounds: Bounds = Bounds::new(&board);
+
+        // you're back where you started, but the item's gone
+        let there_and_back: Sokoban = board
+            .you_move(coordinate::Direction::Right)
+            .you_move(coordinate::Direction::Left);
+        assert_eq!(there_and_back.you(), board.you());
+        assert!(Key::new(&there_and_back, &bounds) != Key::new(&board, &bounds));
+    }
+
+    #[test]
+    fn open_boards_are_searched_in_a_finite_area() {
+        // the target is walled in, but there's nothing around the rest
+        let board: Sokoban = "\
+@0 -
+  -^-
+   -"
+        .parse()
+        .unwrap();
+
+        assert_eq!(breadth_first(&board), None);
+    }
+}
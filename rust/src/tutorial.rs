@@ -0,0 +1,195 @@
+//! Scripted tutorials that teach the rules one step at a time
+//!
+//! A tutorial is a list of [`Step`]s, each of which can limit which
+//! ways you're allowed to move, set up a board for you to play on, and
+//! wait for you to do something in particular before moving on to the
+//! next step.
+
+use crate::coordinate;
+use crate::sokoban::Sokoban;
+
+/// What has to happen on the board for a tutorial step to be done
+#[derive(Debug, Clone, PartialEq)]
+pub enum Goal {
+    /// You stand on the coordinate
+    YouAt(coordinate::I2),
+    /// There's a push on the coordinate
+    PushAt(coordinate::I2),
+    /// Every target has a push on it
+    AllTargetsTriggered,
+}
+
+impl Goal {
+    /// Checks if `board` satisfies the goal
+    pub fn is_met(&self, board: &Sokoban) -> bool {
+        match self {
+            Goal::YouAt(coordinate) => board.you() == *coordinate,
+            Goal::PushAt(coordinate) => board.pushes().contains(coordinate),
+            Goal::AllTargetsTriggered => board.all_targets_triggered(),
+        }
+    }
+}
+
+/// A single instruction in a tutorial
+#[derive(Debug, Clone, PartialEq)]
+pub struct Step {
+    /// What to tell the player to do
+    pub prompt: String,
+    /// The only directions you may move in, or `None` to allow any
+    pub allowed: Option<Vec<coordinate::Direction>>,
+    /// The board to put in front of the player when the step begins,
+    /// or `None` to carry on with the board from the step before
+    pub board: Option<Sokoban>,
+    /// What finishes the step
+    pub goal: Goal,
+}
+
+/// A sequence of steps and how far through them the player is
+///
+/// # Examples
+///
+/// ```
+/// let mut tutorial: Tutorial = Tutorial::new(vec![Step {
+///     prompt: String::from("Walk right"),
+///     allowed: Some(vec![coordinate::Direction::Right]),
+///     board: None,
+///     goal: Goal::YouAt(coordinate::I2::new(1, 0)),
+/// }]);
+///
+/// assert!(!tutorial.allows(coordinate::Direction::Left));
+/// tutorial.advance(&board.you_move(coordinate::Direction::Right));
+/// assert!(tutorial.is_finished());
+/// ```
+#[derive(Debug, Clone, PartialEq)]
+pub struct Tutorial {
+    steps: Vec<Step>,
+    current: usize,
+}
+
+impl Tutorial {
+    /// Create a tutorial that starts on the first of `steps`
+    pub fn new(steps: Vec<Step>) -> Tutorial {
+        Tutorial { steps, current: 0 }
+    }
+
+    /// The step the player is on, or `None` once they've done them all
+    pub fn current_step(&self) -> Option<&Step> {
+        self.steps.get(self.current)
+    }
+
+    /// The index of the step the player is on
+    pub fn current_index(&self) -> usize {
+        self.current
+    }
+
+    /// Checks if every step is done
+    pub fn is_finished(&self) -> bool {
+        self.current >= self.steps.len()
+    }
+
+    /// Checks if the current step lets you move toward `direction`
+    ///
+    /// Once the tutorial is finished, everything is allowed.
+    pub fn allows(&self, direction: coordinate::Direction) -> bool {
+        match self.current_step().and_then(|step| step.allowed.as_ref()) {
+            Some(allowed) => allowed.contains(&direction),
+            None => true,
+        }
+    }
+
+    /// Move on to the next step if `board` meets the current step's goal
+    ///
+    /// This returns `true` if the tutorial moved on.  Only one step is
+    /// finished per call, even if the board also meets the goal of the
+    /// step after.
+    pub fn advance(&mut self, board: &Sokoban) -> bool {
+        match self.current_step() {
+            Some(step) if step.goal.is_met(board) => {
+                self.current += 1;
+                true
+            }
+            _ => false,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn board() -> Sokoban {
+        // @.0.^
+        Sokoban::new(
+            coordinate::I2::new(0, 0),
+            coordinate::I2Array::from(vec![]),
+            coordinate::I2Array::from(vec![[2, 0]]),
+            coordinate::I2Array::from(vec![[4, 0]]),
+        )
+    }
+
+    fn steps() -> Vec<Step> {
+        vec![
+            Step {
+                prompt: String::from("Walk up to the block"),
+                allowed: Some(vec![coordinate::Direction::Right]),
+                board: None,
+                goal: Goal::YouAt(coordinate::I2::new(1, 0)),
+            },
+            Step {
+                prompt: String::from("Push it"),
+                allowed: None,
+                board: None,
+                goal: Goal::PushAt(coordinate::I2::new(3, 0)),
+            },
+            Step {
+                prompt: String::from("Finish the job"),
+                allowed: None,
+                board: Some(board()),
+                goal: Goal::AllTargetsTriggered,
+            },
+        ]
+    }
+
+    #[test]
+    fn goals_are_met_by_the_right_boards() {
+        assert!(Goal::YouAt(coordinate::I2::new(0, 0)).is_met(&board()));
+        assert!(!Goal::YouAt(coordinate::I2::new(1, 0)).is_met(&board()));
+        assert!(Goal::PushAt(coordinate::I2::new(2, 0)).is_met(&board()));
+        assert!(!Goal::PushAt(coordinate::I2::new(3, 0)).is_met(&board()));
+        assert!(!Goal::AllTargetsTriggered.is_met(&board()));
+    }
+
+    #[test]
+    fn steps_restrict_directions() {
+        let tutorial: Tutorial = Tutorial::new(steps());
+
+        assert!(tutorial.allows(coordinate::Direction::Right));
+        assert!(!tutorial.allows(coordinate::Direction::Left));
+        assert!(!tutorial.allows(coordinate::Direction::Up));
+    }
+
+    #[test]
+    fn tutorial_advances_a_step_at_a_time() {
+        let mut tutorial: Tutorial = Tutorial::new(steps());
+
+        assert!(!tutorial.advance(&board()));
+        assert_eq!(tutorial.current_index(), 0);
+
+        let played: Sokoban = board().you_move(coordinate::Direction::Right);
+        assert!(tutorial.advance(&played));
+        assert_eq!(tutorial.current_step().unwrap().prompt, "Push it");
+        assert!(tutorial.allows(coordinate::Direction::Left));
+
+        let played: Sokoban = played.you_move(coordinate::Direction::Right);
+        assert!(tutorial.advance(&played));
+        assert_eq!(tutorial.current_step().unwrap().board, Some(board()));
+        assert!(!tutorial.is_finished());
+
+        let played: Sokoban = played.you_move(coordinate::Direction::Right);
+        assert!(tutorial.advance(&played));
+        assert!(tutorial.is_finished());
+        assert_eq!(tutorial.current_step(), None);
+        assert!(tutorial.allows(coordinate::Direction::Up));
+        assert!(!tutorial.advance(&played));
+    }
+}
@@ -0,0 +1,349 @@
+//! Levels made of several boards linked together by exits
+//!
+//! A [`World`] holds a number of [`Sokoban`] boards and the [`Exit`]s
+//! between them.  You play on one board at a time, and walking onto an
+//! exit carries you over to the board it links to.  Shoving a push
+//! onto an exit carries the push over the same way.  Boards you leave
+//! are kept as you left them, so coming back finds the pushes where
+//! they were.
+
+use crate::coordinate;
+use crate::sokoban::Sokoban;
+
+/// A tile on one board that leads to a tile on another
+#[derive(Debug, Clone, PartialEq)]
+pub struct Exit {
+    /// The index of the board the exit is on
+    pub board: usize,
+    /// Where on that board the exit is
+    pub at: coordinate::I2,
+    /// The index of the board the exit leads to
+    pub to: usize,
+    /// Where you arrive on the board the exit leads to
+    pub arrival: coordinate::I2,
+}
+
+/// What happened when a move took you from one board to another
+///
+/// This is meant for the renderer, so it can swap out the board it's
+/// drawing and play whatever transition it likes.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Transition {
+    /// The index of the board you left
+    pub from: usize,
+    /// The index of the board you arrived on
+    pub to: usize,
+    /// Where you arrived
+    pub arrival: coordinate::I2,
+}
+
+/// A graph of boards connected by exits, along with the one you're on
+///
+/// # Examples
+///
+/// ```
+/// // Two rooms, where E is an exit to the other room
+/// //
+/// // @E   E.
+/// let world: World = World::new(
+///     vec![
+///         Sokoban::new([0, 0], stops.clone(), pushes.clone(), targets.clone()),
+///         Sokoban::new([1, 0], stops, pushes, targets),
+///     ],
+///     vec![
+///         Exit { board: 0, at: [1, 0], to: 1, arrival: [1, 0] },
+///         Exit { board: 1, at: [0, 0], to: 0, arrival: [0, 0] },
+///     ],
+/// );
+///
+/// let world: World = world.you_move(coordinate::Direction::Right);
+/// assert_eq!(world.current(), 1);
+/// assert_eq!(
+///     world.transition(),
+///     Some(Transition { from: 0, to: 1, arrival: [1, 0] })
+/// );
+/// ```
+#[derive(Debug, Clone, PartialEq)]
+pub struct World {
+    boards: Vec<Sokoban>,
+    exits: Vec<Exit>,
+    current: usize,
+    transition: Option<Transition>,
+}
+
+impl World {
+    /// Create a world of `boards` linked by `exits`, starting on the first
+    ///
+    /// # Panics
+    ///
+    /// Panics if there are no boards or an exit refers to a board that
+    /// doesn't exist.
+    pub fn new(boards: Vec<Sokoban>, exits: Vec<Exit>) -> World {
+        assert!(!boards.is_empty(), "a world needs at least one board");
+        assert!(
+            exits
+                .iter()
+                .all(|exit| exit.board < boards.len() && exit.to < boards.len()),
+            "every exit must link boards in the world"
+        );
+        World {
+            boards,
+            exits,
+            current: 0,
+            transition: None,
+        }
+    }
+
+    /// The index of the board you're on
+    pub fn current(&self) -> usize {
+        self.current
+    }
+
+    /// The board you're on
+    pub fn board(&self) -> &Sokoban {
+        &self.boards[self.current]
+    }
+
+    /// All the boards in the world, in the state they were last left
+    pub fn boards(&self) -> &[Sokoban] {
+        &self.boards
+    }
+
+    /// The board change caused by the last move, if it caused one
+    pub fn transition(&self) -> Option<Transition> {
+        self.transition
+    }
+
+    /// Move the player one tile over toward direction
+    ///
+    /// This follows the rules of [`Sokoban::you_move`] on the current
+    /// board.  Should you end up on an exit, you're taken to its
+    /// arrival tile on the board it leads to.  Likewise, a push shoved
+    /// onto an exit is carried over to its arrival tile, so long as
+    /// there's room for it there; otherwise it stays on the exit.
+    ///
+    /// If a push has been left on the arrival tile, you shove it along
+    /// the way you were going as you arrive.  Should there be no room
+    /// for that, or something else is in the way, you don't move at
+    /// all, just as if you'd walked into a wall.
+    pub fn you_move(&self, direction: coordinate::Direction) -> World {
+        let mut boards: Vec<Sokoban> = self.boards.clone();
+        boards[self.current] = self.board().you_move(direction);
+        for exit in self.exits.iter().filter(|exit| exit.board == self.current) {
+            let shoved: bool = boards[exit.board].pushes().contains(&exit.at)
+                && !self.board().pushes().contains(&exit.at);
+            let is_vacant: bool = boards[exit.to].is_vacant(exit.arrival)
+                && (exit.to != self.current || boards[exit.to].you() != exit.arrival);
+            if !shoved || !is_vacant {
+                continue;
+            }
+            if let Some(push) = boards[exit.board].take_push(exit.at) {
+                boards[exit.to].put_push(exit.arrival, push);
+            }
+        }
+
+        let board: &Sokoban = &boards[self.current];
+        let exit: Option<&Exit> = self
+            .exits
+            .iter()
+            .find(|exit| exit.board == self.current && exit.at == board.you());
+        // standing still on an exit shouldn't bounce you back and forth
+        let exit: Option<&Exit> = exit.filter(|_| board.you() != self.board().you());
+
+        match exit {
+            Some(exit) => {
+                // something may have been left on the arrival tile since
+                // you were last there, and you can't stand on top of it
+                if !boards[exit.to].is_vacant(exit.arrival) {
+                    let beyond: Option<coordinate::I2> = exit
+                        .arrival
+                        .nudge(direction)
+                        .filter(|beyond| boards[exit.to].is_vacant(*beyond));
+                    match (beyond, boards[exit.to].take_push(exit.arrival)) {
+                        (Some(beyond), Some(push)) => boards[exit.to].put_push(beyond, push),
+                        _ => {
+                            return World {
+                                transition: None,
+                                ..self.clone()
+                            }
+                        }
+                    }
+                }
+                boards[exit.to] = boards[exit.to].clone().with_you(exit.arrival);
+                World {
+                    boards,
+                    current: exit.to,
+                    transition: Some(Transition {
+                        from: self.current,
+                        to: exit.to,
+                        arrival: exit.arrival,
+                    }),
+                    ..self.clone()
+                }
+            }
+            None => World {
+                boards,
+                transition: None,
+                ..self.clone()
+            },
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn room(you: [i32; 2], pushes: Vec<[i32; 2]>) -> Sokoban {
+        Sokoban::new(
+            coordinate::I2::new(you[0], you[1]),
+            coordinate::I2Array::from(vec![]),
+            coordinate::I2Array::from(pushes),
+            coordinate::I2Array::from(vec![]),
+        )
+    }
+
+    fn world() -> World {
+        // room 0   room 1
+        // @0.E     E..
+        World::new(
+            vec![room([0, 0], vec![[1, 0]]), room([1, 0], vec![])],
+            vec![
+                Exit {
+                    board: 0,
+                    at: coordinate::I2::new(3, 0),
+                    to: 1,
+                    arrival: coordinate::I2::new(1, 0),
+                },
+                Exit {
+                    board: 1,
+                    at: coordinate::I2::new(0, 0),
+                    to: 0,
+                    arrival: coordinate::I2::new(2, 1),
+                },
+            ],
+        )
+    }
+
+    #[test]
+    fn moves_within_a_board_do_not_transition() {
+        let world: World = world().you_move(coordinate::Direction::Right);
+
+        assert_eq!(world.current(), 0);
+        assert_eq!(world.transition(), None);
+        assert_eq!(world.board(), &room([1, 0], vec![[2, 0]]));
+    }
+
+    #[test]
+    fn walking_onto_an_exit_takes_you_to_the_linked_board() {
+        let world: World = world()
+            .you_move(coordinate::Direction::Down)
+            .you_move(coordinate::Direction::Right)
+            .you_move(coordinate::Direction::Right)
+            .you_move(coordinate::Direction::Right)
+            .you_move(coordinate::Direction::Up);
+
+        assert_eq!(world.current(), 1);
+        assert_eq!(
+            world.transition(),
+            Some(Transition {
+                from: 0,
+                to: 1,
+                arrival: coordinate::I2::new(1, 0),
+            })
+        );
+        assert_eq!(world.board().you(), coordinate::I2::new(1, 0));
+    }
+
+    #[test]
+    fn boards_keep_their_state_when_you_come_back() {
+        // push the block along first so it's out of the way
+        let world: World = world()
+            .you_move(coordinate::Direction::Right)
+            .you_move(coordinate::Direction::Down)
+            .you_move(coordinate::Direction::Right)
+            .you_move(coordinate::Direction::Right)
+            .you_move(coordinate::Direction::Up);
+        assert_eq!(world.current(), 1);
+
+        let world: World = world.you_move(coordinate::Direction::Left);
+        assert_eq!(world.current(), 0);
+        assert_eq!(world.board(), &room([2, 1], vec![[2, 0]]));
+    }
+
+    #[test]
+    fn pushes_shoved_onto_an_exit_go_through_it() {
+        let world: World = world()
+            .you_move(coordinate::Direction::Right)
+            .you_move(coordinate::Direction::Right);
+
+        assert_eq!(world.current(), 0);
+        assert_eq!(world.board(), &room([2, 0], vec![]));
+        assert_eq!(
+            world.boards()[1].pushes(),
+            coordinate::I2Array::from(vec![[1, 0]])
+        );
+
+        // following the push through doesn't leave you standing on it
+        let world: World = world.you_move(coordinate::Direction::Right);
+        assert_eq!(world.current(), 1);
+        assert!(!world.board().pushes().contains(&world.board().you()));
+    }
+
+    #[test]
+    fn pushes_stay_on_an_exit_with_no_room_past_it() {
+        // there's already a push on the arrival tile in room 1
+        let world: World = World {
+            boards: vec![room([1, 0], vec![[2, 0]]), room([2, 0], vec![[1, 0]])],
+            ..world()
+        }
+        .you_move(coordinate::Direction::Right);
+
+        assert_eq!(world.board(), &room([2, 0], vec![[3, 0]]));
+        assert_eq!(world.boards()[1], room([2, 0], vec![[1, 0]]));
+    }
+
+    #[test]
+    fn arriving_shoves_pushes_off_the_arrival_tile() {
+        // push the block through the exit, then follow it
+        let world: World = world()
+            .you_move(coordinate::Direction::Right)
+            .you_move(coordinate::Direction::Right)
+            .you_move(coordinate::Direction::Right);
+
+        assert_eq!(world.current(), 1);
+        assert_eq!(world.board().you(), coordinate::I2::new(1, 0));
+        assert_eq!(
+            world.board().pushes(),
+            coordinate::I2Array::from(vec![[2, 0]])
+        );
+    }
+
+    #[test]
+    fn arriving_is_refused_when_the_arrival_tile_can_not_be_cleared() {
+        // room 1 has a push on the arrival tile with another behind it
+        let world: World = World {
+            boards: vec![room([2, 0], vec![]), room([0, 0], vec![[1, 0], [2, 0]])],
+            ..world()
+        };
+        let moved: World = world.you_move(coordinate::Direction::Right);
+
+        assert_eq!(moved.current(), 0);
+        assert_eq!(moved.transition(), None);
+        assert_eq!(moved.boards(), world.boards());
+    }
+
+    #[test]
+    #[should_panic]
+    fn exits_to_missing_boards_panic() {
+        World::new(
+            vec![room([0, 0], vec![])],
+            vec![Exit {
+                board: 0,
+                at: coordinate::I2::new(1, 0),
+                to: 1,
+                arrival: coordinate::I2::new(0, 0),
+            }],
+        );
+    }
+}